@@ -1,17 +1,51 @@
 use std::time::{Instant};
 use cgmath::Vector2;
-use wgpu::{CommandEncoder, TextureView};
+use serde::{Deserialize, Serialize};
+use wgpu::TextureFormat;
 use winit::event::MouseButton;
 use winit::keyboard::KeyCode;
 use winit::keyboard::KeyCode::{KeyB};
-use crate::bundles::automata::automata_compute_pipeline::{Automata, AutomataComputePipeline};
+use crate::bundles::automata::automata_compute_pipeline::{Automata, AutomataComputePipeline, Boundary};
 use crate::bundles::automata::automata_package::AutomataPackage;
 use crate::bundles::automata::automata_pipeline::AutomataRenderPipeline;
 use crate::bundles::automata::automata_queue_compute_pipeline::QueueComputePipeline;
+use crate::bundles::debug_draw::debug_draw_bundle::DebugShapes;
+use crate::inbuilt::render_graph::{RenderGraph, Resource};
 use crate::inbuilt::setup::Setup;
 use crate::packages::camera_package::CameraPackage;
 use crate::packages::input_manager_package::InputManager;
-use crate::packages::time_package::{TimePackage, Timer};
+use crate::packages::scene_package::{NodeId, ScenePackage, Transform};
+use crate::packages::shader_package::ShaderPackage;
+
+
+/// the user facing settings, kept between runs by `PersistencePackage`
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutomataSettings {
+   /// cells per side, applied on the next reset
+   pub size: u32,
+   pub update_rate: f64,
+   pub limit_compute_fps: bool,
+   pub generate_random: bool,
+   pub brush_type: i32,
+   pub brush_size: i32,
+   pub automata: Automata,
+   pub boundary: Boundary,
+}
+impl Default for AutomataSettings {
+   fn default() -> Self {
+      Self {
+         size: 56,
+         update_rate: 60.0,
+         limit_compute_fps: true,
+         generate_random: true,
+         brush_type: 1,
+         brush_size: 5,
+         automata: Automata::GameOfLife,
+         boundary: Boundary::Unchecked,
+      }
+   }
+}
 
 
 pub struct AutomataBundle {
@@ -19,15 +53,22 @@ pub struct AutomataBundle {
    render_pipeline: AutomataRenderPipeline,
    compute_pipeline: AutomataComputePipeline,
    queue_compute_pipeline: QueueComputePipeline,
+   render_format: TextureFormat,
+   /// where the grid is placed, the cells fill -1..1 of the node's space
+   pub node: NodeId,
 
    pub target_size: Vector2<u32>,
    pub running: bool,
    pub generate_random: bool,
    pub update_rate: f64,
    pub active_automata: Automata,
+   pub boundary: Boundary,
 
    pub limit_compute_fps: bool,
    time_since_last_compute_pass: Instant,
+   /// while set, generations only advance through `request_step`, independent of the frame rate
+   pub manual_clock: bool,
+   step_requested: bool,
 
    update_queued: bool,
 
@@ -38,51 +79,69 @@ impl AutomataBundle {
    pub fn new(
       setup: &Setup,
       camera_package: &CameraPackage,
+      scene_package: &mut ScenePackage,
+      render_format: TextureFormat,
+      settings: &AutomataSettings,
+      shaders: &mut ShaderPackage,
    ) -> Self {
-      let target_size = Vector2::new(56, 56);
-      let generate_random = true;
-      let update_rate = 60.0;
-      let active_automata = Automata::GameOfLife;
+      let target_size = Vector2::new(settings.size, settings.size);
+      let generate_random = settings.generate_random;
+      let update_rate = settings.update_rate;
+      let active_automata = settings.automata;
+      let boundary = settings.boundary;
 
-      let brush_type = 1;
-      let brush_size = 5;
+      let brush_type = settings.brush_type;
+      let brush_size = settings.brush_size;
 
-      let automata_package = AutomataPackage::new(&setup, target_size.x, target_size.y, generate_random);
-      let automata_render_pipeline = AutomataRenderPipeline::new(&setup, camera_package, &automata_package);
-      let automata_compute_pipeline = AutomataComputePipeline::new(&setup, &automata_package, &active_automata);
-      let queue_pipeline = QueueComputePipeline::new(&setup.device, &automata_package);
+      let node = scene_package.add("automata", Transform::default())
+          .unwrap_or_else(|err| panic!("no room for the automata node: {err}"));
+      let automata_package = AutomataPackage::new(setup, target_size.x, target_size.y, generate_random);
+      let automata_render_pipeline = shaders.create_or_embedded(&setup.device, AutomataRenderPipeline::SHADER_PATH, |shaders| {
+         AutomataRenderPipeline::new(setup, camera_package, scene_package, &automata_package, render_format, shaders)
+      });
+      let automata_compute_pipeline = shaders.create_or_embedded(&setup.device, active_automata.shader_path(), |shaders| {
+         AutomataComputePipeline::new(setup, &automata_package, &active_automata, &boundary, shaders)
+      });
+      let queue_pipeline = shaders.create_or_embedded(&setup.device, QueueComputePipeline::SHADER_PATH, |shaders| {
+         QueueComputePipeline::new(&setup.device, &automata_package, &boundary, shaders)
+      });
 
       Self {
          package: automata_package,
          render_pipeline: automata_render_pipeline,
          compute_pipeline: automata_compute_pipeline,
          queue_compute_pipeline: queue_pipeline,
+         render_format,
+         node,
 
          target_size,
          generate_random,
          update_rate,
          active_automata,
+         boundary,
          brush_type,
          brush_size,
 
-         limit_compute_fps: true,
+         limit_compute_fps: settings.limit_compute_fps,
          time_since_last_compute_pass: Instant::now(),
+         manual_clock: false,
+         step_requested: false,
 
          update_queued: false,
          running: true
       }
    }
 
-   pub fn update(&mut self, input_manager: &InputManager, setup: &Setup, camera_package: &CameraPackage) {
+   pub fn update(&mut self, input_manager: &InputManager, setup: &Setup, camera_package: &CameraPackage, scene_package: &ScenePackage) {
       if input_manager.is_mouse_key_just_pressed(MouseButton::Left) {
-         let pix_pos = self.get_pix_pos(input_manager, camera_package, setup);
+         let pix_pos = self.get_pix_pos(input_manager, camera_package, scene_package, setup);
 
          self.queue_compute_pipeline.update_queue(setup, vec![[pix_pos.x, pix_pos.y, self.brush_type, self.brush_size]]);
 
          self.update_queued = true;
       }
       else if input_manager.is_mouse_key_just_pressed(MouseButton::Right) {
-         let pix_pos = self.get_pix_pos(input_manager, camera_package, setup);
+         let pix_pos = self.get_pix_pos(input_manager, camera_package, scene_package, setup);
 
          self.queue_compute_pipeline.update_queue(setup, vec![[pix_pos.x, pix_pos.y, -self.brush_type, self.brush_size]]);
 
@@ -99,63 +158,205 @@ impl AutomataBundle {
       }
    }
 
-   fn get_pix_pos(&self, input_manager: &InputManager, camera_package: &CameraPackage, setup: &Setup) -> Vector2<i32> {
+   fn get_pix_pos(&self, input_manager: &InputManager, camera_package: &CameraPackage, scene_package: &ScenePackage, setup: &Setup) -> Vector2<i32> {
+      if !input_manager.is_mouse_in_viewport() { return Vector2::new(i32::MAX, i32::MAX) }
+
       let world_pos = input_manager.pull_world_pos_2d(camera_package, setup);
+      let Some(local_pos) = scene_package.graph().world_to_local(self.node, world_pos.extend(0.0)) else {
+         return Vector2::new(i32::MAX, i32::MAX)
+      };
+
       let cube_pos_normal = Vector2::new(
-         (world_pos.x + 1.0) / 2.0,
-         (world_pos.y + 1.0) / 2.0,
+         (local_pos.x + 1.0) / 2.0,
+         (local_pos.y + 1.0) / 2.0,
       );
 
-      if cube_pos_normal.x > 1.0 || cube_pos_normal.x < 0.0 {return Vector2::new(i32::MAX, i32::MAX)}
-      else if cube_pos_normal.y > 1.0 || cube_pos_normal.y < 0.0 {return Vector2::new(i32::MAX, i32::MAX)}
+      if cube_pos_normal.x > 1.0 || cube_pos_normal.x < 0.0
+          || cube_pos_normal.y > 1.0 || cube_pos_normal.y < 0.0 {
+         return Vector2::new(i32::MAX, i32::MAX)
+      }
 
-      let pix_pos = Vector2::new(
+      Vector2::new(
          (self.package.size.width as f32 * cube_pos_normal.x).ceil() as i32 -1,
          (self.package.size.height as f32 * cube_pos_normal.y).ceil() as i32 -1,
-      );
+      )
+   }
+
+   /// the grid bounds and the cells the brush would paint under the cursor
+   pub fn debug_draw(&self, shapes: &mut DebugShapes, input_manager: &InputManager, camera_package: &CameraPackage, scene_package: &ScenePackage, setup: &Setup) {
+      let pix_pos = self.get_pix_pos(input_manager, camera_package, scene_package, setup);
+      shapes.with_transform(scene_package.graph().world_matrix(self.node), |shapes| self.debug_draw_local(shapes, pix_pos));
+   }
+
+   fn debug_draw_local(&self, shapes: &mut DebugShapes, pix_pos: Vector2<i32>) {
+      let (width, height) = (self.package.size.width, self.package.size.height);
+      shapes.rect([-1.0, -1.0], [1.0, 1.0], [0.3, 0.8, 1.0, 1.0]);
+      shapes.text([-1.0, 1.0], format!("{width}x{height}"), [0.3, 0.8, 1.0, 1.0]);
+
+      if pix_pos.x == i32::MAX { return; }
+
+      // cell `i` covers -1 + i * cell ..= -1 + (i + 1) * cell
+      let cell = [2.0 / width as f32, 2.0 / height as f32];
+      let corner = |x: i32, y: i32| [-1.0 + x as f32 * cell[0], -1.0 + y as f32 * cell[1]];
+      let color = [1.0, 0.9, 0.2, 1.0];
+
+      if self.brush_type == 2 {
+         let center = corner(pix_pos.x, pix_pos.y);
+         let center = [center[0] + cell[0] / 2.0, center[1] + cell[1] / 2.0];
+         shapes.circle(center, (self.brush_size as f32 + 0.5) * cell[0], color);
+      }
+      else {
+         let min = corner(pix_pos.x - self.brush_size / 2, pix_pos.y - self.brush_size / 2);
+         let max = corner(pix_pos.x - self.brush_size / 2 + self.brush_size, pix_pos.y - self.brush_size / 2 + self.brush_size);
+         shapes.rect(min, max, color);
+      }
+   }
+
+   pub fn settings(&self) -> AutomataSettings {
+      AutomataSettings {
+         size: self.target_size.x,
+         update_rate: self.update_rate,
+         limit_compute_fps: self.limit_compute_fps,
+         generate_random: self.generate_random,
+         brush_type: self.brush_type,
+         brush_size: self.brush_size,
+         automata: self.active_automata,
+         boundary: self.boundary,
+      }
+   }
+
+   /// only rebuilds the grid or the compute pipelines when their settings changed
+   pub fn apply_settings(&mut self, setup: &Setup, shaders: &mut ShaderPackage, settings: &AutomataSettings) {
+      let old = self.settings();
+
+      self.target_size = Vector2::new(settings.size, settings.size);
+      self.update_rate = settings.update_rate;
+      self.limit_compute_fps = settings.limit_compute_fps;
+      self.generate_random = settings.generate_random;
+      self.brush_type = settings.brush_type;
+      self.brush_size = settings.brush_size;
+      self.active_automata = settings.automata;
+      self.boundary = settings.boundary;
 
-      pix_pos
+      if self.package.size.width != settings.size || self.package.size.height != settings.size {
+         self.reset_package(setup);
+      }
+      if old.automata != settings.automata || old.boundary != settings.boundary {
+         self.reset_compute(setup, shaders);
+      }
    }
 
    pub fn reset_package(&mut self, setup: &Setup) {
-      self.package = AutomataPackage::new(&setup, self.target_size.x, self.target_size.y, self.generate_random);
+      self.package = AutomataPackage::new(setup, self.target_size.x, self.target_size.y, self.generate_random);
       self.package.bind_groups.ping_pong(); // needed or it breaks
    }
 
-   pub fn reset_compute(&mut self, setup: &Setup) {
-      self.compute_pipeline = AutomataComputePipeline::new(&setup, &self.package, &self.active_automata);
+   pub fn reset_compute(&mut self, setup: &Setup, shaders: &mut ShaderPackage) {
+      self.compute_pipeline = shaders.create_or_embedded(&setup.device, self.active_automata.shader_path(), |shaders| {
+         AutomataComputePipeline::new(setup, &self.package, &self.active_automata, &self.boundary, shaders)
+      });
+      self.queue_compute_pipeline = shaders.create_or_embedded(&setup.device, QueueComputePipeline::SHADER_PATH, |shaders| {
+         QueueComputePipeline::new(&setup.device, &self.package, &self.boundary, shaders)
+      });
    }
 
-   pub fn automata_pass(
-      &mut self, encoder: &mut CommandEncoder,
-      view: &TextureView,
-      camera_package: &CameraPackage,
-      time_package: &mut TimePackage,
-   ) {
-      if self.update_queued { self.queue_compute_pipeline.compute_pass(encoder, &self.package); }
-
-      self.render_pipeline.render_pass(encoder, view, camera_package, &self.package);
-
-      if self.limit_compute_fps {
-         if self.update_rate > 0.0 {
-            let target = 1.0 / self.update_rate;
-            let diff = target - time_package.delta_time;
-
-            if self.time_since_last_compute_pass.elapsed().as_secs_f64() > diff {
-               if self.running {
-                  self.compute_pipeline.compute_pass(encoder, &self.package);
-                  self.time_since_last_compute_pass = Instant::now();
-                  self.package.bind_groups.ping_pong();
-               }
-            }
+   /// rebuilds the pipelines whose shader changed, a pipeline that fails to build keeps the old one
+   pub fn reload_shaders(&mut self, setup: &Setup, camera_package: &CameraPackage, scene_package: &ScenePackage, shaders: &mut ShaderPackage, changed: &[String]) {
+      let path = AutomataRenderPipeline::SHADER_PATH;
+      if changed.iter().any(|p| p == path) {
+         match ShaderPackage::try_create(&setup.device, || AutomataRenderPipeline::new(setup, camera_package, scene_package, &self.package, self.render_format, shaders)) {
+            Ok(pipeline) => self.render_pipeline = pipeline,
+            Err(err) => shaders.report_error(path, err),
          }
       }
-      else {
-         self.compute_pipeline.compute_pass(encoder, &self.package);
+
+      let path = self.active_automata.shader_path();
+      if changed.iter().any(|p| p == path) {
+         match ShaderPackage::try_create(&setup.device, || AutomataComputePipeline::new(setup, &self.package, &self.active_automata, &self.boundary, shaders)) {
+            Ok(pipeline) => self.compute_pipeline = pipeline,
+            Err(err) => shaders.report_error(path, err),
+         }
+      }
+
+      let path = QueueComputePipeline::SHADER_PATH;
+      if changed.iter().any(|p| p == path) {
+         match ShaderPackage::try_create(&setup.device, || QueueComputePipeline::new(&setup.device, &self.package, &self.boundary, shaders)) {
+            Ok(pipeline) => self.queue_compute_pipeline = pipeline,
+            Err(err) => shaders.report_error(path, err),
+         }
+      }
+   }
+
+   /// steps exactly one generation in the next `add_passes` when `manual_clock` is set
+   pub fn request_step(&mut self) {
+      self.step_requested = true;
+   }
+
+   /// queue -> step -> draw into `target`, the step runs at most once per frame and is rate limited by `update_rate`
+   pub fn add_passes<'a>(&'a mut self, graph: &mut RenderGraph<'a>, camera_package: &'a CameraPackage, scene_package: &'a ScenePackage, delta_time: f64, target: Resource) {
+      let step = self.should_step(delta_time);
+      self.step_requested = false;
+      if step {
          self.time_since_last_compute_pass = Instant::now();
          self.package.bind_groups.ping_pong();
       }
 
+      let this = &*self;
+      let cells = Resource::Texture("automata_cells");
+      let bind_groups = &this.package.bind_groups;
 
+      // after the ping pong `current` is what gets drawn, so the brush has to go into
+      // the texture the step reads from
+      let (brush_read, brush_write) = match step {
+         true => (bind_groups.pull_current(), bind_groups.pull_other()),
+         false => (bind_groups.pull_other(), bind_groups.pull_current()),
+      };
+
+      if this.update_queued {
+         graph.add_pass("automata queue")
+             .read(cells)
+             .write(cells)
+             .run(move |ctx| {
+                if let Err(err) = this.queue_compute_pipeline.compute_pass(ctx.encoder, brush_read, brush_write) {
+                   log::error!("{err}");
+                }
+                Ok(())
+             });
+      }
+
+      if step {
+         graph.add_pass("automata step")
+             .read(cells)
+             .write(cells)
+             .run(move |ctx| {
+                // e.g. a grid bigger than the device can dispatch, the last generation stays on screen
+                if let Err(err) = this.compute_pipeline.compute_pass(ctx.encoder, &this.package) {
+                   log::error!("{err}");
+                }
+                Ok(())
+             });
+      }
+
+      this.add_render_pass(graph, camera_package, scene_package, target);
+   }
+
+   /// only draws the current generation into `target`
+   pub fn add_render_pass<'a>(&'a self, graph: &mut RenderGraph<'a>, camera_package: &'a CameraPackage, scene_package: &'a ScenePackage, target: Resource) {
+      graph.add_pass("automata render")
+          .read(Resource::Texture("automata_cells"))
+          .write(target)
+          .run(move |ctx| {
+             self.render_pipeline.render_pass(ctx.encoder, ctx.view(target)?, camera_package, scene_package, self.node, &self.package);
+             Ok(())
+          });
    }
-}
\ No newline at end of file
+
+   fn should_step(&self, delta_time: f64) -> bool {
+      if self.manual_clock { return self.step_requested; }
+      if !self.limit_compute_fps { return true; }
+      if self.update_rate <= 0.0 || !self.running { return false; }
+
+      let target = 1.0 / self.update_rate;
+      self.time_since_last_compute_pass.elapsed().as_secs_f64() > target - delta_time
+   }
+}
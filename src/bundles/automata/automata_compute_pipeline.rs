@@ -1,69 +1,81 @@
-use wgpu::{CommandEncoder, ComputePipeline, ComputePipelineDescriptor, PipelineLayoutDescriptor, ShaderModuleDescriptor};
+use serde::{Deserialize, Serialize};
+use wgpu::CommandEncoder;
 use crate::bundles::automata::automata_package::AutomataPackage;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
 use crate::inbuilt::setup::Setup;
+use crate::packages::compute_package::ComputePackage;
+use crate::packages::shader_package::ShaderPackage;
 
 
-
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Automata {
    GameOfLife,
    SmoothLife,
 }
+/// how cells at the edge of the grid see their neighbours, picked with a define in `common/automata_texture.wgsl`
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Boundary {
+   Unchecked,
+   Dead,
+   Wrap,
+}
+impl Boundary {
+   pub fn add_define(&self, defines: ShaderDefines) -> ShaderDefines {
+      match self {
+         Boundary::Unchecked => defines,
+         Boundary::Dead => defines.flag("BOUNDARY_DEAD"),
+         Boundary::Wrap => defines.flag("BOUNDARY_WRAP"),
+      }
+   }
+}
+
+
+impl Automata {
+   pub fn shader_path(&self) -> &'static str {
+      match self {
+         Automata::GameOfLife => "compute/game_of_life.wgsl",
+         Automata::SmoothLife => "compute/smooth_life.wgsl",
+      }
+   }
+}
+
+
+/// only picks the size the shader is built with, dispatches read it back from the shader
+const WORKGROUP_SIZE: u32 = 16;
 
 
 pub struct AutomataComputePipeline {
-   pub pipeline: ComputePipeline,
+   pub compute: ComputePackage,
 }
 impl AutomataComputePipeline {
-   pub fn new(setup: &Setup, automata_package: &AutomataPackage, selected: &Automata) -> Self {
-      let shader = match selected {
+   pub fn new(setup: &Setup, automata_package: &AutomataPackage, selected: &Automata, boundary: &Boundary, shaders: &mut ShaderPackage) -> Result<Self, String> {
+      let embedded = match selected {
          Automata::GameOfLife => include_str!("../../shaders/compute/game_of_life.wgsl"),
          Automata::SmoothLife => include_str!("../../shaders/compute/smooth_life.wgsl"),
       };
 
-      let cs_module = setup.device.create_shader_module(ShaderModuleDescriptor {
-         label: Some("compute_shader"),
-         source: wgpu::ShaderSource::Wgsl(shader.into()),
-      });
+      let defines = boundary.add_define(ShaderDefines::new().with("WORKGROUP_SIZE", WORKGROUP_SIZE));
+      let (cs_module, reflected) = shaders.reflected_module(&setup.device, selected.shader_path(), embedded, &defines)?;
 
-      let layout = setup.device.create_pipeline_layout(&PipelineLayoutDescriptor {
-         label: Some("compute pipeline"),
-         bind_group_layouts: &[
-            &automata_package.bind_group_layout,
-            &automata_package.bind_group_layout,
-         ],
-         push_constant_ranges: &[],
-      });
+      let layout = &automata_package.bind_group_layout;
+      let compute = ComputePackage::new(&setup.device, selected.shader_path(), &cs_module, &reflected, "cs_main", &[layout, layout])?;
 
-      let pipeline = setup.device.create_compute_pipeline(&ComputePipelineDescriptor {
-         label: Some("Compute Pipeline"),
-         layout: Some(&layout),
-         module: &cs_module,
-         entry_point: "cs_main",
-      });
-
-      Self {
-         pipeline,
-      }
+      Ok(Self {
+         compute,
+      })
    }
 
-   pub fn compute_pass(&mut self, encoder: &mut CommandEncoder, automata_package: &AutomataPackage) {
-      let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-         label: Some("Compute Pass"),
-         timestamp_writes: None,
-      });
-
-      compute_pass.set_pipeline(&self.pipeline);
+   /// one step from `other` into `current`, so ping pong before recording it. fails when the grid needs more
+   /// workgroups than the device allows
+   pub fn compute_pass(&self, encoder: &mut CommandEncoder, automata_package: &AutomataPackage) -> Result<(), String> {
+      let mut compute_pass = self.compute.begin(encoder);
 
       // read
-      compute_pass.set_bind_group(0, automata_package.bind_groups.pull_current(), &[]);
-      compute_pass.set_bind_group(1, automata_package.bind_groups.pull_other(), &[]);
+      compute_pass.set_bind_group(0, automata_package.bind_groups.pull_other(), &[]);
+      compute_pass.set_bind_group(1, automata_package.bind_groups.pull_current(), &[]);
 
 
       let texture_extent = automata_package.size;
-      compute_pass.dispatch_workgroups(
-         (texture_extent.width as f32 / 16.).ceil() as u32,
-         (texture_extent.height as f32 / 16.).ceil() as u32,
-         1
-      );
+      self.compute.dispatch(&mut compute_pass, [texture_extent.width, texture_extent.height, 1])
    }
-}
\ No newline at end of file
+}
@@ -1,6 +1,6 @@
 use bytemuck::{cast_slice, Pod, Zeroable};
 use rand::{Rng, thread_rng};
-use wgpu::{BindGroup, BindGroupLayout, Extent3d, ImageDataLayout, SamplerBindingType, ShaderStages, StorageTextureAccess, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureViewDimension};
+use wgpu::{BindGroup, BindGroupLayout, BindGroupLayoutEntry, Extent3d, ImageDataLayout, SamplerBindingType, ShaderStages, StorageTextureAccess, Texture, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages, TextureViewDimension};
 use crate::inbuilt::setup::Setup;
 use crate::utility::structs::PingPongData;
 
@@ -37,8 +37,8 @@ impl AutomataPackage {
 
       if generate_random {
          let random_data =  &Self::generate_random_data_0_to_1(size);
-         Self::write_texture_data(&setup, &texture_1, size, random_data); // the next one in the flipper must be written to
-         Self::write_texture_data(&setup, &texture_2, size, random_data); // the next one in the flipper must be written to
+         Self::write_texture_data(setup, &texture_1, size, random_data); // the next one in the flipper must be written to
+         Self::write_texture_data(setup, &texture_2, size, random_data); // the next one in the flipper must be written to
       }
 
       let view_1 = texture_1.create_view(&wgpu::TextureViewDescriptor::default());
@@ -56,24 +56,7 @@ impl AutomataPackage {
       });
 
       let bind_group_layout = setup.device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-          entries: &[
-             wgpu::BindGroupLayoutEntry {
-                binding: 0,
-                visibility: ShaderStages::FRAGMENT | ShaderStages::COMPUTE,
-                ty: wgpu::BindingType::StorageTexture {
-                   access: StorageTextureAccess::ReadWrite,
-                   format: TextureFormat::R32Float,
-                   view_dimension: TextureViewDimension::D2,
-                },
-                count: None,
-             },
-             wgpu::BindGroupLayoutEntry {
-                binding: 1,
-                visibility: ShaderStages::FRAGMENT,
-                ty: wgpu::BindingType::Sampler(SamplerBindingType::NonFiltering),
-                count: None,
-             },
-          ],
+          entries: &Self::bind_group_layout_entries(),
           label: Some("texture_bind_group_layout"),
       });
 
@@ -117,7 +100,31 @@ impl AutomataPackage {
       }
    }
 
-   pub fn write_texture_data(setup: &Setup, texture: &Texture, size: Extent3d, data: &Vec<Texel> ) {
+   /// read_write r32float storage texture at binding 0, sampler at binding 1.
+   /// the render, step and queue shaders all bind this group, so it is written out here instead of
+   /// reflected from one of them. tests/shader_validation.rs checks it against each
+   pub fn bind_group_layout_entries() -> [BindGroupLayoutEntry; 2] {
+      [
+         BindGroupLayoutEntry {
+            binding: 0,
+            visibility: ShaderStages::FRAGMENT | ShaderStages::COMPUTE,
+            ty: wgpu::BindingType::StorageTexture {
+               access: StorageTextureAccess::ReadWrite,
+               format: TextureFormat::R32Float,
+               view_dimension: TextureViewDimension::D2,
+            },
+            count: None,
+         },
+         BindGroupLayoutEntry {
+            binding: 1,
+            visibility: ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(SamplerBindingType::NonFiltering),
+            count: None,
+         },
+      ]
+   }
+
+   pub fn write_texture_data(setup: &Setup, texture: &Texture, size: Extent3d, data: &[Texel]) {
       let bytes_per_pixel = std::mem::size_of::<Texel>();
       let bytes_per_row = (size.width as usize * bytes_per_pixel) as u32;
       let rows_per_image = size.height;
@@ -129,7 +136,7 @@ impl AutomataPackage {
             origin: wgpu::Origin3d::ZERO,
             aspect: wgpu::TextureAspect::All,
          },
-         &cast_slice(data),
+         cast_slice(data),
          ImageDataLayout {
             offset: 0,
             bytes_per_row: Some(bytes_per_row),
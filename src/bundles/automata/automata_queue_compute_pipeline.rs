@@ -1,55 +1,40 @@
-use wgpu::{
-   BindGroup,
-   BindGroupLayout,
-   BindGroupLayoutDescriptor,
-   BindGroupLayoutEntry,
-   BindingType,
-   Buffer,
-   BufferBindingType,
-   BufferSize,
-   CommandEncoder,
-   ComputePipeline,
-   ComputePipelineDescriptor,
-   Device,
-   PipelineLayoutDescriptor,
-   ShaderModuleDescriptor,
-   ShaderStages,
-   util::DeviceExt
-};
+use wgpu::{BindGroup, BindGroupLayout, Buffer, CommandEncoder, Device, util::DeviceExt};
+use crate::bundles::automata::automata_compute_pipeline::Boundary;
 use crate::bundles::automata::automata_package::AutomataPackage;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
 use crate::inbuilt::setup::Setup;
+use crate::packages::compute_package::ComputePackage;
+use crate::packages::shader_package::ShaderPackage;
+
+/// only picks the size the shader is built with, dispatches read it back from the shader
+const WORKGROUP_SIZE: u32 = 8;
+/// has to match the array length of `Queue` in input_queue.wgsl
+pub const QUEUE_LENGTH: usize = 100;
 
 pub struct QueueComputePipeline {
-   pub pipeline: ComputePipeline,
+   pub compute: ComputePackage,
    pub buffer: Buffer,
    pub input_data: Vec<[i32; 4]>,
    pub input_array_layout: BindGroupLayout,
    pub bind_group: BindGroup,
 }
 impl QueueComputePipeline {
-   pub fn new(device: &Device, automata_package: &AutomataPackage) -> Self {
-      let cs_module = device.create_shader_module(ShaderModuleDescriptor {
-         label: Some("compute_shader"),
-         source: wgpu::ShaderSource::Wgsl(include_str!("../../shaders/compute/input_queue.wgsl").into()),
-      });
-
-      let input_data: Vec<[i32; 4]> = vec![[0, 0, 0, 0]; 100];
-
-      let input_array_layout = device.create_bind_group_layout(&BindGroupLayoutDescriptor {
-         label: None,
-         entries: &[
-            BindGroupLayoutEntry {
-               binding: 0,
-               visibility: ShaderStages::COMPUTE,
-               ty: BindingType::Buffer {
-                  ty: BufferBindingType::Uniform,
-                  has_dynamic_offset: false,
-                  min_binding_size: BufferSize::new(16 * input_data.len() as u64),
-               },
-               count: None,
-            },
-         ]
-      });
+   pub const SHADER_PATH: &'static str = "compute/input_queue.wgsl";
+
+   pub fn new(device: &Device, automata_package: &AutomataPackage, boundary: &Boundary, shaders: &mut ShaderPackage) -> Result<Self, String> {
+      let defines = boundary.add_define(ShaderDefines::new().with("WORKGROUP_SIZE", WORKGROUP_SIZE));
+      let (cs_module, reflected) = shaders.reflected_module(device, Self::SHADER_PATH, include_str!("../../shaders/compute/input_queue.wgsl"), &defines)?;
+
+      let input_data: Vec<[i32; 4]> = vec![[0, 0, 0, 0]; QUEUE_LENGTH];
+
+      // group 2, the brush strokes, only this shader uses, so its layout comes from the shader.
+      // the buffer holds `QUEUE_LENGTH` strokes, which the shader's array has to match
+      let queue_size = (QUEUE_LENGTH * size_of::<[i32; 4]>()) as u64;
+      match reflected.layout_entries(2).first().map(|entry| entry.ty) {
+         Some(wgpu::BindingType::Buffer { min_binding_size: Some(size), .. }) if size.get() == queue_size => {}
+         _ => return Err(format!("{}: group 2 has to be a uniform array of {QUEUE_LENGTH} vec4<i32>", Self::SHADER_PATH)),
+      }
+      let input_array_layout = reflected.bind_group_layout(device, 2, Some("queue_bind_group_layout"));
 
       let buffer = device.create_buffer_init(
          &wgpu::util::BufferInitDescriptor {
@@ -59,45 +44,26 @@ impl QueueComputePipeline {
          }
       );
 
-      let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-         label: None,
-         layout: &input_array_layout,
-         entries: &[wgpu::BindGroupEntry {
-            binding: 0,
-            resource: buffer.as_entire_binding(),
-         }],
-      });
-
-
-      let layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
-         label: Some("compute pipeline"),
-         bind_group_layouts: &[
-            &automata_package.bind_group_layout,
-            &automata_package.bind_group_layout,
-            &input_array_layout,
-         ],
-         push_constant_ranges: &[],
-      });
-
-      let pipeline = device.create_compute_pipeline(&ComputePipelineDescriptor {
-         label: Some("Compute Pipeline"),
-         layout: Some(&layout),
-         module: &cs_module,
-         entry_point: "cs_main",
-      });
-
-      Self {
-         pipeline,
+      let bind_group = reflected.bind_group(2, &input_array_layout)
+          .buffer("queue", &buffer)
+          .build(device)?;
+
+
+      let layout = &automata_package.bind_group_layout;
+      let compute = ComputePackage::new(device, Self::SHADER_PATH, &cs_module, &reflected, "cs_main", &[layout, layout, &input_array_layout])?;
+
+      Ok(Self {
+         compute,
 
          bind_group,
          input_array_layout,
          input_data,
          buffer,
-      }
+      })
    }
 
    pub fn update_queue(&mut self, setup: &Setup, data: Vec<[i32; 4]>) {
-      let mut empty_data: [[i32; 4]; 100] = [[0; 4]; 100];
+      let mut empty_data: [[i32; 4]; QUEUE_LENGTH] = [[0; 4]; QUEUE_LENGTH];
 
       for (i, entry) in data.iter().enumerate() {
          empty_data[i] = *entry;
@@ -108,25 +74,17 @@ impl QueueComputePipeline {
       setup.queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&data));
    }
 
-   pub fn compute_pass(&mut self, encoder: &mut CommandEncoder, automata_package: &AutomataPackage) {
-      let mut compute_pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
-         label: Some("Compute Pass"),
-         timestamp_writes: None,
-      });
-
-      compute_pass.set_pipeline(&self.pipeline);
+   /// draws the queued brush strokes into `write`
+   pub fn compute_pass(&self, encoder: &mut CommandEncoder, read: &BindGroup, write: &BindGroup) -> Result<(), String> {
+      let mut compute_pass = self.compute.begin(encoder);
 
       // read
-      compute_pass.set_bind_group(0, &automata_package.bind_groups.pull_other(), &[]);
-      compute_pass.set_bind_group(1, &automata_package.bind_groups.pull_current(), &[]);
+      compute_pass.set_bind_group(0, read, &[]);
+      compute_pass.set_bind_group(1, write, &[]);
 
       compute_pass.set_bind_group(2, &self.bind_group, &[]);
 
 
-      compute_pass.dispatch_workgroups(
-         (self.input_data.len() as f32 / 8.).ceil() as u32,
-         1,
-         1
-      );
+      self.compute.dispatch(&mut compute_pass, [self.input_data.len() as u32, 1, 1])
    }
 }
\ No newline at end of file
@@ -0,0 +1,170 @@
+use std::f32::consts::TAU;
+use cgmath::{Matrix4, Vector4};
+use wgpu::TextureFormat;
+use crate::bundles::debug_draw::debug_draw_pipeline::DebugDrawPipeline;
+use crate::inbuilt::render_graph::{RenderGraph, Resource};
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::vertex_package::ColoredVertex;
+use crate::packages::camera_package::CameraPackage;
+use crate::packages::shader_package::ShaderPackage;
+
+
+/// segments a circle is drawn with
+pub const CIRCLE_SEGMENTS: usize = 32;
+
+/// text drawn over the scene at a world position
+#[derive(Clone, Debug, PartialEq)]
+pub struct DebugLabel {
+   pub position: [f32; 2],
+   pub text: String,
+   pub color: [f32; 4],
+}
+
+
+/// world space primitives for one frame, lines end up as vertex pairs for a line list
+#[derive(Default)]
+pub struct DebugShapes {
+   vertices: Vec<ColoredVertex>,
+   labels: Vec<DebugLabel>,
+}
+impl DebugShapes {
+   pub fn new() -> Self {
+      Self::default()
+   }
+
+   pub fn line(&mut self, from: [f32; 2], to: [f32; 2], color: [f32; 4]) {
+      self.vertices.push(ColoredVertex { position: [from[0], from[1], 0.0], color });
+      self.vertices.push(ColoredVertex { position: [to[0], to[1], 0.0], color });
+   }
+
+   /// an outline between two opposite corners
+   pub fn rect(&mut self, min: [f32; 2], max: [f32; 2], color: [f32; 4]) {
+      let corners = [min, [max[0], min[1]], max, [min[0], max[1]]];
+      for i in 0..4 {
+         self.line(corners[i], corners[(i + 1) % 4], color);
+      }
+   }
+
+   pub fn circle(&mut self, center: [f32; 2], radius: f32, color: [f32; 4]) {
+      let point = |i: usize| {
+         let angle = i as f32 / CIRCLE_SEGMENTS as f32 * TAU;
+         [center[0] + radius * angle.cos(), center[1] + radius * angle.sin()]
+      };
+      for i in 0..CIRCLE_SEGMENTS {
+         self.line(point(i), point(i + 1), color);
+      }
+   }
+
+   /// a line with a head at `to` a fifth of its length
+   pub fn arrow(&mut self, from: [f32; 2], to: [f32; 2], color: [f32; 4]) {
+      self.line(from, to, color);
+
+      // the shaft pointing back from the tip, turned half a radian each way and scaled down
+      let (dx, dy) = (from[0] - to[0], from[1] - to[1]);
+      let (sin, cos) = (0.5f32.sin() * 0.2, 0.5f32.cos() * 0.2);
+      self.line(to, [to[0] + dx * cos - dy * sin, to[1] + dx * sin + dy * cos], color);
+      self.line(to, [to[0] + dx * cos + dy * sin, to[1] - dx * sin + dy * cos], color);
+   }
+
+   /// a plus `size` wide
+   pub fn crosshair(&mut self, center: [f32; 2], size: f32, color: [f32; 4]) {
+      let half = size / 2.0;
+      self.line([center[0] - half, center[1]], [center[0] + half, center[1]], color);
+      self.line([center[0], center[1] - half], [center[0], center[1] + half], color);
+   }
+
+   /// drawn through egui, centered above `position`
+   pub fn text(&mut self, position: [f32; 2], text: impl Into<String>, color: [f32; 4]) {
+      self.labels.push(DebugLabel { position, text: text.into(), color });
+   }
+
+   /// everything `draw` adds is moved by `transform`, to draw in a scene node's space
+   pub fn with_transform(&mut self, transform: Matrix4<f32>, draw: impl FnOnce(&mut DebugShapes)) {
+      let (vertices, labels) = (self.vertices.len(), self.labels.len());
+      draw(self);
+
+      for vertex in &mut self.vertices[vertices..] {
+         let [x, y, z] = vertex.position;
+         vertex.position = (transform * Vector4::new(x, y, z, 1.0)).truncate().into();
+      }
+      for label in &mut self.labels[labels..] {
+         let [x, y] = label.position;
+         let moved = transform * Vector4::new(x, y, 0.0, 1.0);
+         label.position = [moved.x, moved.y];
+      }
+   }
+
+   pub fn vertices(&self) -> &[ColoredVertex] {
+      &self.vertices
+   }
+
+   pub fn labels(&self) -> &[DebugLabel] {
+      &self.labels
+   }
+
+   pub fn is_empty(&self) -> bool {
+      self.vertices.is_empty() && self.labels.is_empty()
+   }
+
+   pub fn clear(&mut self) {
+      self.vertices.clear();
+      self.labels.clear();
+   }
+}
+
+
+/// where a world position ends up on a screen of `screen_size`, y down like egui and winit
+pub fn world_to_screen(view_proj: Matrix4<f32>, position: [f32; 2], screen_size: [f32; 2]) -> [f32; 2] {
+   let clip = view_proj * Vector4::new(position[0], position[1], 0.0, 1.0);
+   let (x, y) = (clip.x / clip.w, clip.y / clip.w);
+   [(x + 1.0) / 2.0 * screen_size[0], (1.0 - y) / 2.0 * screen_size[1]]
+}
+
+
+/// immediate mode debug drawing. anything can add shapes during `update`, they are drawn over the
+/// post processed scene and cleared at the start of the next frame
+pub struct DebugDrawBundle {
+   pub enabled: bool,
+   pub shapes: DebugShapes,
+   pipeline: DebugDrawPipeline,
+   format: TextureFormat,
+}
+impl DebugDrawBundle {
+   pub fn new(setup: &Setup, camera_package: &CameraPackage, format: TextureFormat, shaders: &mut ShaderPackage) -> Self {
+      Self {
+         enabled: false,
+         shapes: DebugShapes::new(),
+         pipeline: shaders.create_or_embedded(&setup.device, DebugDrawPipeline::SHADER_PATH, |shaders| DebugDrawPipeline::new(setup, camera_package, format, shaders)),
+         format,
+      }
+   }
+
+   pub fn reload_shaders(&mut self, setup: &Setup, camera_package: &CameraPackage, shaders: &mut ShaderPackage, changed: &[String]) {
+      let path = DebugDrawPipeline::SHADER_PATH;
+      if !changed.iter().any(|p| p == path) { return; }
+
+      match ShaderPackage::try_create(&setup.device, || DebugDrawPipeline::new(setup, camera_package, self.format, shaders)) {
+         Ok(pipeline) => self.pipeline = pipeline,
+         Err(err) => shaders.report_error(path, err),
+      }
+   }
+
+   pub fn begin_frame(&mut self) {
+      self.shapes.clear();
+   }
+
+   /// draws onto the swapchain, add it after the post processing and before the ui
+   pub fn add_pass<'a>(&'a mut self, graph: &mut RenderGraph<'a>, setup: &Setup, camera_package: &'a CameraPackage) {
+      if !self.enabled || self.shapes.vertices().is_empty() { return; }
+      self.pipeline.upload(setup, self.shapes.vertices());
+
+      let pipeline = &self.pipeline;
+      graph.add_pass("debug draw")
+          .read(Resource::Swapchain)
+          .write(Resource::Swapchain)
+          .run(move |ctx| {
+             pipeline.render_pass(ctx.encoder, ctx.view(Resource::Swapchain)?, camera_package);
+             Ok(())
+          });
+   }
+}
@@ -0,0 +1,34 @@
+use egui::{Align2, Color32, Context, FontId, LayerId, Pos2, Rect};
+use crate::bundles::debug_draw::debug_draw_bundle::{world_to_screen, DebugDrawBundle};
+use crate::packages::camera_package::CameraPackage;
+
+/// `viewport` is where the scene is shown in points, the whole screen if `None`
+pub fn gui(ui: &Context, debug_draw_bundle: &mut DebugDrawBundle, camera_package: &CameraPackage, viewport: Option<Rect>) {
+   egui::Window::new("Debug draw")
+       .default_open(false)
+       .resizable(false)
+       .show(ui, |ui| {
+          ui.add(egui::Checkbox::new(&mut debug_draw_bundle.enabled, "enabled (F3)"));
+          ui.label(format!("{} lines, {} labels", debug_draw_bundle.shapes.vertices().len() / 2, debug_draw_bundle.shapes.labels().len()));
+       });
+
+   if !debug_draw_bundle.enabled { return; }
+
+   // the background layer is under every window, so labels never cover the ui
+   let screen = viewport.unwrap_or(ui.screen_rect());
+   let painter = ui.layer_painter(LayerId::background()).with_clip_rect(screen);
+   let view_proj = camera_package.camera.build_view_projection_matrix();
+
+   for label in debug_draw_bundle.shapes.labels() {
+      let [x, y] = world_to_screen(view_proj, label.position, [screen.width(), screen.height()]);
+      let [r, g, b, a] = label.color.map(|c| (c.clamp(0.0, 1.0) * 255.0).round() as u8);
+
+      painter.text(
+         Pos2::new(x, y) + screen.min.to_vec2(),
+         Align2::CENTER_BOTTOM,
+         &label.text,
+         FontId::monospace(12.0),
+         Color32::from_rgba_unmultiplied(r, g, b, a),
+      );
+   }
+}
@@ -0,0 +1,68 @@
+use wgpu::{CommandEncoder, RenderPipeline, TextureFormat, TextureView};
+use crate::inbuilt::pipeline_builder::RenderPipelineBuilder;
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
+use crate::inbuilt::vertex_package::{ColoredVertex, DynamicVertexPackage, VertexLayout};
+use crate::packages::camera_package::CameraPackage;
+use crate::packages::shader_package::ShaderPackage;
+
+
+/// draws a line list in world space under the camera, on top of what is already in the target
+pub struct DebugDrawPipeline {
+   vertex_package: DynamicVertexPackage<ColoredVertex>,
+   render_pipeline: RenderPipeline,
+}
+impl DebugDrawPipeline {
+   pub const SHADER_PATH: &'static str = "render/debug_lines.wgsl";
+
+   pub fn new(setup: &Setup, camera_package: &CameraPackage, format: TextureFormat, shaders: &mut ShaderPackage) -> Result<Self, String> {
+      let vertex_package = DynamicVertexPackage::new::<u16>(&setup.device, &[], &[]);
+      let shader = shaders.module(&setup.device, Self::SHADER_PATH, include_str!("../../shaders/render/debug_lines.wgsl"), &ShaderDefines::new())?;
+
+      let render_pipeline = RenderPipelineBuilder::new(setup, &shader)
+          .label(Self::SHADER_PATH)
+          .bind_group_layout(camera_package.bind_group_layout())
+          .vertex_layout(ColoredVertex::desc())
+          .format(format)
+          .blend(Some(wgpu::BlendState::ALPHA_BLENDING))
+          .topology(wgpu::PrimitiveTopology::LineList)
+          .cull_mode(None)
+          .sample_count(1)
+          .build(&setup.device);
+
+      Ok(Self {
+         vertex_package,
+         render_pipeline,
+      })
+   }
+
+   /// replaces what `render_pass` draws, two vertices per line
+   pub fn upload(&mut self, setup: &Setup, vertices: &[ColoredVertex]) {
+      self.vertex_package.update::<u16>(&setup.device, &setup.queue, vertices, &[]);
+   }
+
+   /// nothing is recorded when there is nothing uploaded
+   pub fn render_pass(&self, encoder: &mut CommandEncoder, view: &TextureView, camera_package: &CameraPackage) {
+      if self.vertex_package.num_vertices == 0 { return; }
+
+      let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+         label: Some("Debug Draw Pass"),
+         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+               load: wgpu::LoadOp::Load,
+               store: wgpu::StoreOp::Store,
+            },
+         })],
+         depth_stencil_attachment: None,
+         occlusion_query_set: None,
+         timestamp_writes: None,
+      });
+
+      render_pass.set_pipeline(&self.render_pipeline);
+      render_pass.set_bind_group(0, camera_package.bind_group(), &[]);
+      render_pass.set_vertex_buffer(0, self.vertex_package.vertex_buffer.slice(..));
+      render_pass.draw(0..self.vertex_package.num_vertices, 0..1);
+   }
+}
@@ -0,0 +1,132 @@
+use wgpu::{Extent3d, Texture, TextureView};
+use crate::inbuilt::setup::Setup;
+
+
+pub const LUT_SIZE: u32 = 32;
+
+
+/// applied to linear hdr color, the defaults leave it unchanged
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GradingSettings {
+   /// in stops
+   pub exposure: f32,
+   /// around middle grey, 1 is unchanged
+   pub contrast: f32,
+   pub saturation: f32,
+   /// positive is warmer, negative cooler
+   pub temperature: f32,
+   /// positive is more magenta, negative more green
+   pub tint: f32,
+}
+impl Default for GradingSettings {
+   fn default() -> Self {
+      Self {
+         exposure: 0.0,
+         contrast: 1.0,
+         saturation: 1.0,
+         temperature: 0.0,
+         tint: 0.0,
+      }
+   }
+}
+impl GradingSettings {
+   pub fn apply(&self, color: [f32; 3]) -> [f32; 3] {
+      const MIDDLE_GREY: f32 = 0.18;
+
+      let exposure = self.exposure.exp2();
+      let white_balance = [1.0 + self.temperature * 0.1, 1.0 - self.tint * 0.1, 1.0 - self.temperature * 0.1];
+      let mut color = [0, 1, 2].map(|i| color[i] * exposure * white_balance[i]);
+
+      color = color.map(|c| MIDDLE_GREY * (c.max(0.0) / MIDDLE_GREY).powf(self.contrast));
+
+      let luma = color[0] * 0.2126 + color[1] * 0.7152 + color[2] * 0.0722;
+      color.map(|c| (luma + (c - luma) * self.saturation).max(0.0))
+   }
+}
+
+
+/// `color / (1 + color)`, squeezes hdr values into the 0..1 range the lut covers
+pub fn encode(c: f32) -> f32 {
+   c / (1.0 + c)
+}
+
+pub fn decode(e: f32) -> f32 {
+   e / (1.0 - e).max(0.0001)
+}
+
+/// rgba8 texels for a `size`³ lut, red changes fastest then green then blue
+pub fn bake_lut(settings: &GradingSettings, size: u32) -> Vec<[u8; 4]> {
+   let mut texels = Vec::with_capacity((size * size * size) as usize);
+   let step = 1.0 / (size - 1) as f32;
+
+   for b in 0..size {
+      for g in 0..size {
+         for r in 0..size {
+            let color = [r, g, b].map(|i| decode(i as f32 * step));
+            let graded = settings.apply(color);
+            let [r, g, b] = graded.map(|c| (encode(c).clamp(0.0, 1.0) * 255.0).round() as u8);
+            texels.push([r, g, b, 255]);
+         }
+      }
+   }
+
+   texels
+}
+
+
+/// the lut texture, rebaked whenever the settings it was made from change
+pub struct ColorLut {
+   texture: Texture,
+   pub view: TextureView,
+   baked: GradingSettings,
+}
+impl ColorLut {
+   pub fn new(setup: &Setup, settings: &GradingSettings) -> Self {
+      let texture = setup.device.create_texture(&wgpu::TextureDescriptor {
+         label: Some("color grading lut"),
+         size: Self::extent(),
+         mip_level_count: 1,
+         sample_count: 1,
+         dimension: wgpu::TextureDimension::D3,
+         format: wgpu::TextureFormat::Rgba8Unorm,
+         usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+         view_formats: &[],
+      });
+      let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+      let lut = Self { texture, view, baked: *settings };
+      lut.write(setup);
+      lut
+   }
+
+   pub fn update(&mut self, setup: &Setup, settings: &GradingSettings) {
+      if self.baked == *settings { return; }
+
+      self.baked = *settings;
+      self.write(setup);
+   }
+
+   fn write(&self, setup: &Setup) {
+      let texels = bake_lut(&self.baked, LUT_SIZE);
+
+      setup.queue.write_texture(
+         wgpu::ImageCopyTexture {
+            texture: &self.texture,
+            mip_level: 0,
+            origin: wgpu::Origin3d::ZERO,
+            aspect: wgpu::TextureAspect::All,
+         },
+         bytemuck::cast_slice(&texels),
+         wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(LUT_SIZE * 4),
+            rows_per_image: Some(LUT_SIZE),
+         },
+         Self::extent(),
+      );
+   }
+
+   fn extent() -> Extent3d {
+      Extent3d { width: LUT_SIZE, height: LUT_SIZE, depth_or_array_layers: LUT_SIZE }
+   }
+}
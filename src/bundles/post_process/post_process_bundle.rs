@@ -0,0 +1,349 @@
+use bytemuck::{Pod, Zeroable};
+use wgpu::{Sampler, TextureFormat};
+use crate::bundles::post_process::color_grading::{ColorLut, GradingSettings, LUT_SIZE};
+use crate::bundles::post_process::post_process_pipeline::PostProcessPipeline;
+use crate::inbuilt::render_graph::{RenderGraph, Resource, TargetSize, TransientTexture};
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
+use crate::packages::shader_package::ShaderPackage;
+
+
+/// format of the scene target and every stage of the stack
+pub const HDR_FORMAT: TextureFormat = TextureFormat::Rgba16Float;
+
+const SCENE_TARGET: &str = "post_scene";
+/// every pass writes a texture of its own so the graph can order them, the pool reuses the memory
+const STAGE_TARGETS: [&str; 4] = ["post_stage_0", "post_stage_1", "post_stage_2", "post_stage_3"];
+
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostEffect {
+   Bloom,
+   ColorGrading,
+   Vignette,
+   Crt,
+}
+impl PostEffect {
+   pub fn name(&self) -> &'static str {
+      match self {
+         PostEffect::Bloom => "Bloom",
+         PostEffect::ColorGrading => "Color grading",
+         PostEffect::Vignette => "Vignette",
+         PostEffect::Crt => "CRT",
+      }
+   }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tonemap {
+   None,
+   Reinhard,
+   Aces,
+}
+
+
+pub struct BloomSettings {
+   pub threshold: f32,
+   pub knee: f32,
+   pub intensity: f32,
+   /// blur tap spacing in half resolution texels
+   pub radius: f32,
+}
+
+pub struct VignetteSettings {
+   pub strength: f32,
+   pub radius: f32,
+   pub softness: f32,
+}
+
+pub struct CrtSettings {
+   pub curvature: f32,
+   pub scanline_strength: f32,
+   pub scanline_count: f32,
+   pub aberration: f32,
+}
+
+
+// uniforms, laid out like the structs in shaders/post
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct BloomExtractParams {
+   pub threshold: f32,
+   pub knee: f32,
+   pub _padding: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct BlurParams {
+   pub direction: [f32; 2],
+   pub radius: f32,
+   pub _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct BloomCompositeParams {
+   pub intensity: f32,
+   pub _padding: [f32; 3],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct ColorGradingParams {
+   pub strength: f32,
+   pub lut_size: f32,
+   pub _padding: [f32; 2],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct VignetteParams {
+   pub strength: f32,
+   pub radius: f32,
+   pub softness: f32,
+   pub _padding: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct CrtParams {
+   pub curvature: f32,
+   pub scanline_strength: f32,
+   pub scanline_count: f32,
+   pub aberration: f32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+pub struct TonemapParams {
+   pub exposure: f32,
+   pub mode: u32,
+   pub _padding: [f32; 2],
+}
+
+
+/// hdr scene target plus a chain of full screen effects, resolved into the swapchain.
+/// bundles draw into `PostProcessBundle::SCENE` instead of the swapchain, with `HDR_FORMAT`
+pub struct PostProcessBundle {
+   /// run top to bottom, disabled effects are skipped
+   pub chain: Vec<(PostEffect, bool)>,
+
+   pub bloom: BloomSettings,
+   pub grading: GradingSettings,
+   pub grading_strength: f32,
+   pub vignette: VignetteSettings,
+   pub crt: CrtSettings,
+   pub tonemap: Tonemap,
+   /// in stops, applied before tonemapping
+   pub exposure: f32,
+
+   sampler: Sampler,
+   lut: ColorLut,
+
+   bloom_extract_pipeline: PostProcessPipeline,
+   bloom_blur_h_pipeline: PostProcessPipeline,
+   bloom_blur_v_pipeline: PostProcessPipeline,
+   bloom_composite_pipeline: PostProcessPipeline,
+   color_grading_pipeline: PostProcessPipeline,
+   vignette_pipeline: PostProcessPipeline,
+   crt_pipeline: PostProcessPipeline,
+   tonemap_pipeline: PostProcessPipeline,
+}
+impl PostProcessBundle {
+   pub const SCENE: Resource = Resource::Texture(SCENE_TARGET);
+
+   pub fn new(setup: &Setup, shaders: &mut ShaderPackage) -> Self {
+      let grading = GradingSettings::default();
+
+      let sampler = setup.device.create_sampler(&wgpu::SamplerDescriptor {
+         label: Some("post process sampler"),
+         address_mode_u: wgpu::AddressMode::ClampToEdge,
+         address_mode_v: wgpu::AddressMode::ClampToEdge,
+         address_mode_w: wgpu::AddressMode::ClampToEdge,
+         mag_filter: wgpu::FilterMode::Linear,
+         min_filter: wgpu::FilterMode::Linear,
+         mipmap_filter: wgpu::FilterMode::Nearest,
+         ..Default::default()
+      });
+
+      let mut effect = |path: &'static str, embedded: &'static str, textures: &'static [&'static str]| {
+         shaders.create_or_embedded(&setup.device, path, |shaders| PostProcessPipeline::new(setup, shaders, path, embedded, ShaderDefines::new(), HDR_FORMAT, textures))
+      };
+
+      let bloom_extract_pipeline = effect("post/bloom_extract.wgsl", include_str!("../../shaders/post/bloom_extract.wgsl"), &[]);
+      let bloom_blur_h_pipeline = effect("post/blur.wgsl", include_str!("../../shaders/post/blur.wgsl"), &[]);
+      let bloom_blur_v_pipeline = effect("post/blur.wgsl", include_str!("../../shaders/post/blur.wgsl"), &[]);
+      let bloom_composite_pipeline = effect("post/bloom_composite.wgsl", include_str!("../../shaders/post/bloom_composite.wgsl"), &["bloom"]);
+      let color_grading_pipeline = effect("post/color_grading.wgsl", include_str!("../../shaders/post/color_grading.wgsl"), &["lut"]);
+      let vignette_pipeline = effect("post/vignette.wgsl", include_str!("../../shaders/post/vignette.wgsl"), &[]);
+      let crt_pipeline = effect("post/crt.wgsl", include_str!("../../shaders/post/crt.wgsl"), &[]);
+
+      // the swapchain may not be an srgb format, then the shader has to encode
+      let mut tonemap_defines = ShaderDefines::new();
+      if !setup.config.format.is_srgb() {
+         tonemap_defines = tonemap_defines.flag("MANUAL_SRGB");
+      }
+      let tonemap_pipeline = shaders.create_or_embedded(&setup.device, "post/tonemap.wgsl", |shaders| PostProcessPipeline::new(
+         setup, shaders,
+         "post/tonemap.wgsl", include_str!("../../shaders/post/tonemap.wgsl"),
+         tonemap_defines.clone(), setup.config.format, &[],
+      ));
+
+      Self {
+         chain: vec![
+            (PostEffect::Bloom, true),
+            (PostEffect::ColorGrading, false),
+            (PostEffect::Vignette, true),
+            (PostEffect::Crt, false),
+         ],
+
+         bloom: BloomSettings { threshold: 0.8, knee: 0.2, intensity: 0.6, radius: 1.5 },
+         grading,
+         grading_strength: 1.0,
+         vignette: VignetteSettings { strength: 0.5, radius: 1.0, softness: 0.6 },
+         crt: CrtSettings { curvature: 0.05, scanline_strength: 0.25, scanline_count: 320.0, aberration: 0.3 },
+         tonemap: Tonemap::Aces,
+         exposure: 0.0,
+
+         lut: ColorLut::new(setup, &grading),
+         sampler,
+
+         bloom_extract_pipeline,
+         bloom_blur_h_pipeline,
+         bloom_blur_v_pipeline,
+         bloom_composite_pipeline,
+         color_grading_pipeline,
+         vignette_pipeline,
+         crt_pipeline,
+         tonemap_pipeline,
+      }
+   }
+
+   /// rebuilds the effects whose shader changed, an effect that fails to build keeps the old one
+   pub fn reload_shaders(&mut self, setup: &Setup, shaders: &mut ShaderPackage, changed: &[String]) {
+      let pipelines = [
+         &mut self.bloom_extract_pipeline,
+         &mut self.bloom_blur_h_pipeline,
+         &mut self.bloom_blur_v_pipeline,
+         &mut self.bloom_composite_pipeline,
+         &mut self.color_grading_pipeline,
+         &mut self.vignette_pipeline,
+         &mut self.crt_pipeline,
+         &mut self.tonemap_pipeline,
+      ];
+
+      for pipeline in pipelines {
+         let path = pipeline.path;
+         if !changed.iter().any(|p| p == path) { continue; }
+
+         match ShaderPackage::try_create(&setup.device, || pipeline.rebuild(setup, shaders)) {
+            Ok(rebuilt) => *pipeline = rebuilt,
+            Err(err) => shaders.report_error(path, err),
+         }
+      }
+   }
+
+   /// declares the scene target and adds the enabled effects plus the final resolve into the swapchain
+   pub fn add_passes<'a>(&'a mut self, graph: &mut RenderGraph<'a>, setup: &Setup) {
+      self.lut.update(setup, &self.grading);
+      self.write_params(setup);
+
+      let this = &*self;
+      let full = |graph: &mut RenderGraph, name| graph.transient_texture(name, TransientTexture::new(HDR_FORMAT, TargetSize::Swapchain));
+      let half = |graph: &mut RenderGraph, name| graph.transient_texture(name, TransientTexture::new(HDR_FORMAT, TargetSize::Divided(2)));
+
+      let mut input = full(graph, SCENE_TARGET);
+      let enabled = this.chain.iter().filter(|(_, enabled)| *enabled).map(|(effect, _)| *effect);
+
+      for (stage, effect) in enabled.enumerate() {
+         let output = full(graph, STAGE_TARGETS[stage]);
+
+         match effect {
+            PostEffect::Bloom => {
+               let bright = half(graph, "bloom_bright");
+               let blurred_h = half(graph, "bloom_blur_h");
+               let blurred = half(graph, "bloom_blur_v");
+
+               this.simple_pass(graph, "bloom extract", &this.bloom_extract_pipeline, input, bright);
+               this.simple_pass(graph, "bloom blur h", &this.bloom_blur_h_pipeline, bright, blurred_h);
+               this.simple_pass(graph, "bloom blur v", &this.bloom_blur_v_pipeline, blurred_h, blurred);
+
+               graph.add_pass("bloom composite")
+                   .read(input)
+                   .read(blurred)
+                   .write(output)
+                   .run(move |ctx| {
+                      let (scene, bloom, target) = (ctx.view(input)?, ctx.view(blurred)?, ctx.view(output)?);
+                      this.bloom_composite_pipeline.pass(ctx, scene, &[bloom], &this.sampler, target);
+                      Ok(())
+                   });
+            }
+            PostEffect::ColorGrading => {
+               graph.add_pass("color grading")
+                   .read(input)
+                   .write(output)
+                   .run(move |ctx| {
+                      let (source, target) = (ctx.view(input)?, ctx.view(output)?);
+                      this.color_grading_pipeline.pass(ctx, source, &[&this.lut.view], &this.sampler, target);
+                      Ok(())
+                   });
+            }
+            PostEffect::Vignette => this.simple_pass(graph, "vignette", &this.vignette_pipeline, input, output),
+            PostEffect::Crt => this.simple_pass(graph, "crt", &this.crt_pipeline, input, output),
+         }
+
+         input = output;
+      }
+
+      this.simple_pass(graph, "tonemap", &this.tonemap_pipeline, input, Resource::Swapchain);
+   }
+
+   fn simple_pass<'a>(&'a self, graph: &mut RenderGraph<'a>, name: &'static str, pipeline: &'a PostProcessPipeline, input: Resource, output: Resource) {
+      graph.add_pass(name)
+          .read(input)
+          .write(output)
+          .run(move |ctx| {
+             let (source, target) = (ctx.view(input)?, ctx.view(output)?);
+             pipeline.pass(ctx, source, &[], &self.sampler, target);
+             Ok(())
+          });
+   }
+
+   fn write_params(&self, setup: &Setup) {
+      let queue = &setup.queue;
+
+      self.bloom_extract_pipeline.write_params(queue, &BloomExtractParams {
+         threshold: self.bloom.threshold,
+         knee: self.bloom.knee.max(0.0001),
+         _padding: [0.0; 2],
+      });
+      self.bloom_blur_h_pipeline.write_params(queue, &BlurParams { direction: [1.0, 0.0], radius: self.bloom.radius, _padding: 0.0 });
+      self.bloom_blur_v_pipeline.write_params(queue, &BlurParams { direction: [0.0, 1.0], radius: self.bloom.radius, _padding: 0.0 });
+      self.bloom_composite_pipeline.write_params(queue, &BloomCompositeParams { intensity: self.bloom.intensity, _padding: [0.0; 3] });
+
+      self.color_grading_pipeline.write_params(queue, &ColorGradingParams {
+         strength: self.grading_strength,
+         lut_size: LUT_SIZE as f32,
+         _padding: [0.0; 2],
+      });
+      self.vignette_pipeline.write_params(queue, &VignetteParams {
+         strength: self.vignette.strength,
+         radius: self.vignette.radius,
+         softness: self.vignette.softness,
+         _padding: 0.0,
+      });
+      self.crt_pipeline.write_params(queue, &CrtParams {
+         curvature: self.crt.curvature,
+         scanline_strength: self.crt.scanline_strength,
+         scanline_count: self.crt.scanline_count,
+         aberration: self.crt.aberration,
+      });
+      self.tonemap_pipeline.write_params(queue, &TonemapParams {
+         exposure: self.exposure,
+         mode: self.tonemap as u32,
+         _padding: [0.0; 2],
+      });
+   }
+}
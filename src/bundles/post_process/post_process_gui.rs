@@ -0,0 +1,79 @@
+use egui::{Context, Ui};
+use crate::bundles::post_process::post_process_bundle::{PostEffect, PostProcessBundle, Tonemap};
+
+pub fn gui(ui: &Context, post_process_bundle: &mut PostProcessBundle) {
+   let code = |ui: &mut Ui| {
+      ui.add(egui::Label::new("Tonemap"));
+      ui.horizontal(|ui| {
+         ui.radio_value(&mut post_process_bundle.tonemap, Tonemap::None, "None");
+         ui.radio_value(&mut post_process_bundle.tonemap, Tonemap::Reinhard, "Reinhard");
+         ui.radio_value(&mut post_process_bundle.tonemap, Tonemap::Aces, "ACES");
+      });
+      ui.add(egui::Slider::new(&mut post_process_bundle.exposure, -4.0..=4.0).text("exposure"));
+
+      ui.add_space(20.0);
+      ui.add(egui::Label::new("Effects, run top to bottom"));
+
+      let mut swap = None;
+      let count = post_process_bundle.chain.len();
+
+      for i in 0..count {
+         let (effect, enabled) = &mut post_process_bundle.chain[i];
+         let effect = *effect;
+
+         ui.horizontal(|ui| {
+            ui.add(egui::Checkbox::new(enabled, effect.name()));
+            if ui.add_enabled(i > 0, egui::Button::new("up")).clicked() { swap = Some((i, i - 1)); }
+            if ui.add_enabled(i + 1 < count, egui::Button::new("down")).clicked() { swap = Some((i, i + 1)); }
+         });
+
+         egui::CollapsingHeader::new(format!("{} settings", effect.name()))
+             .id_source(effect.name())
+             .show(ui, |ui| effect_settings(ui, post_process_bundle, effect));
+      }
+
+      if let Some((a, b)) = swap {
+         post_process_bundle.chain.swap(a, b);
+      }
+   };
+
+   egui::Window::new("Post processing")
+       .default_open(false)
+       .resizable(true)
+       .show(ui, code);
+}
+
+fn effect_settings(ui: &mut Ui, post_process_bundle: &mut PostProcessBundle, effect: PostEffect) {
+   match effect {
+      PostEffect::Bloom => {
+         let bloom = &mut post_process_bundle.bloom;
+         ui.add(egui::Slider::new(&mut bloom.threshold, 0.0..=4.0).text("threshold"));
+         ui.add(egui::Slider::new(&mut bloom.knee, 0.0..=1.0).text("knee"));
+         ui.add(egui::Slider::new(&mut bloom.intensity, 0.0..=4.0).text("intensity"));
+         ui.add(egui::Slider::new(&mut bloom.radius, 0.5..=4.0).text("radius"));
+      }
+      PostEffect::ColorGrading => {
+         ui.add(egui::Slider::new(&mut post_process_bundle.grading_strength, 0.0..=1.0).text("strength"));
+
+         let grading = &mut post_process_bundle.grading;
+         ui.add(egui::Slider::new(&mut grading.exposure, -4.0..=4.0).text("exposure"));
+         ui.add(egui::Slider::new(&mut grading.contrast, 0.5..=2.0).text("contrast"));
+         ui.add(egui::Slider::new(&mut grading.saturation, 0.0..=2.0).text("saturation"));
+         ui.add(egui::Slider::new(&mut grading.temperature, -1.0..=1.0).text("temperature"));
+         ui.add(egui::Slider::new(&mut grading.tint, -1.0..=1.0).text("tint"));
+      }
+      PostEffect::Vignette => {
+         let vignette = &mut post_process_bundle.vignette;
+         ui.add(egui::Slider::new(&mut vignette.strength, 0.0..=1.0).text("strength"));
+         ui.add(egui::Slider::new(&mut vignette.radius, 0.1..=1.5).text("radius"));
+         ui.add(egui::Slider::new(&mut vignette.softness, 0.01..=1.0).text("softness"));
+      }
+      PostEffect::Crt => {
+         let crt = &mut post_process_bundle.crt;
+         ui.add(egui::Slider::new(&mut crt.curvature, 0.0..=0.3).text("curvature"));
+         ui.add(egui::Slider::new(&mut crt.scanline_strength, 0.0..=1.0).text("scanlines"));
+         ui.add(egui::Slider::new(&mut crt.scanline_count, 50.0..=1000.0).text("scanline count"));
+         ui.add(egui::Slider::new(&mut crt.aberration, 0.0..=2.0).text("aberration"));
+      }
+   }
+}
@@ -0,0 +1,151 @@
+use std::cell::RefCell;
+use bytemuck::Pod;
+use wgpu::{BindGroup, BindGroupLayout, Buffer, Id, Queue, RenderPipeline, Sampler, TextureFormat, TextureView};
+use crate::inbuilt::pipeline_builder::RenderPipelineBuilder;
+use crate::inbuilt::render_graph::PassContext;
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
+use crate::inbuilt::shader_reflection::{ReflectedShader, ShaderLayout};
+use crate::packages::shader_package::ShaderPackage;
+
+
+/// the views and sampler a bind group was built from, `input` first
+type BindGroupKey = (Vec<Id<TextureView>>, Id<Sampler>);
+
+/// a full screen triangle pass built from a shader that includes `common/fullscreen.wgsl`.
+/// the layout is reflected from the shader, `params` is the uniform at binding 2 if it has one.
+/// `textures` names the extra textures `pass` binds next to `input`, in that order
+pub struct PostProcessPipeline {
+   pub path: &'static str,
+   embedded: &'static str,
+   defines: ShaderDefines,
+   format: TextureFormat,
+   textures: &'static [&'static str],
+
+   pipeline: RenderPipeline,
+   reflected: ReflectedShader,
+   layout: ShaderLayout,
+   params: Option<Buffer>,
+   /// the last bind group `pass` built and the views and sampler it was built from
+   bind_group: RefCell<Option<(BindGroupKey, BindGroup)>>,
+}
+impl PostProcessPipeline {
+   pub fn new(
+      setup: &Setup,
+      shaders: &mut ShaderPackage,
+      path: &'static str,
+      embedded: &'static str,
+      defines: ShaderDefines,
+      format: TextureFormat,
+      textures: &'static [&'static str],
+   ) -> Result<Self, String> {
+      let (shader, reflected) = shaders.reflected_module(&setup.device, path, embedded, &defines)?;
+      let layout = ShaderLayout::new(&setup.device, &reflected, path);
+
+      let pipeline = RenderPipelineBuilder::new(setup, &shader)
+          .label(path)
+          .pipeline_layout(&layout.pipeline_layout)
+          .format(format)
+          .cull_mode(None)
+          .sample_count(1)
+          .build(&setup.device);
+
+      let params = match reflected.bindings().into_iter().find(|binding| binding.name == "params") {
+         None => None,
+         Some(binding) => {
+            let size = match binding.entry.ty {
+               wgpu::BindingType::Buffer { min_binding_size, .. } => min_binding_size.map_or(16, |size| size.get()),
+               _ => return Err(format!("`params` in {path} has to be a uniform")),
+            };
+
+            Some(setup.device.create_buffer(&wgpu::BufferDescriptor {
+               label: Some(path),
+               size,
+               usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+               mapped_at_creation: false,
+            }))
+         }
+      };
+
+      // `pass` builds the bind group, a mismatch has to show up here where it can be reported
+      let mut names = vec!["input", "linear_sampler"];
+      names.extend(textures);
+      if params.is_some() { names.push("params"); }
+      reflected.check_bindings(0, &names).map_err(|err| format!("{path}: {err}"))?;
+
+      Ok(Self { path, embedded, defines, format, textures, pipeline, reflected, layout, params, bind_group: RefCell::new(None) })
+   }
+
+   /// the same shader and settings again, used when the shader changed on disk
+   pub fn rebuild(&self, setup: &Setup, shaders: &mut ShaderPackage) -> Result<Self, String> {
+      Self::new(setup, shaders, self.path, self.embedded, self.defines.clone(), self.format, self.textures)
+   }
+
+   pub fn write_params<P: Pod>(&self, queue: &Queue, params: &P) {
+      let buffer = self.params.as_ref().unwrap_or_else(|| panic!("{} has no params uniform", self.path));
+      queue.write_buffer(buffer, 0, bytemuck::bytes_of(params));
+   }
+
+   pub fn bind_group_layout(&self) -> &BindGroupLayout {
+      &self.layout.bind_group_layouts[0]
+   }
+
+   /// draws into `target`, `textures` are bound next to `input` under the names given to `new`.
+   /// the bind group is kept while the same views come in, the pool hands out new ones only when it recreates a target
+   pub fn pass(&self, ctx: &mut PassContext, input: &TextureView, textures: &[&TextureView], sampler: &Sampler, target: &TextureView) {
+      debug_assert_eq!(textures.len(), self.textures.len(), "{} binds {:?}", self.path, self.textures);
+
+      let key: BindGroupKey = (
+         std::iter::once(input).chain(textures.iter().copied()).map(TextureView::global_id).collect(),
+         sampler.global_id(),
+      );
+      let mut cached = self.bind_group.borrow_mut();
+      if cached.as_ref().is_none_or(|(cached_key, _)| *cached_key != key) {
+         let Some(bind_group) = self.build_bind_group(ctx, input, textures, sampler) else { return };
+         *cached = Some((key, bind_group));
+      }
+      let (_, bind_group) = cached.as_ref().unwrap();
+
+      let mut render_pass = ctx.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+         label: Some(self.path),
+         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: target,
+            resolve_target: None,
+            ops: wgpu::Operations {
+               load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+               store: wgpu::StoreOp::Store,
+            },
+         })],
+         depth_stencil_attachment: None,
+         occlusion_query_set: None,
+         timestamp_writes: None,
+      });
+
+      render_pass.set_pipeline(&self.pipeline);
+      render_pass.set_bind_group(0, bind_group, &[]);
+      render_pass.draw(0..3, 0..1);
+   }
+
+   fn build_bind_group(&self, ctx: &PassContext, input: &TextureView, textures: &[&TextureView], sampler: &Sampler) -> Option<BindGroup> {
+      let mut bind_group = self.reflected.bind_group(0, self.bind_group_layout())
+          .label(self.path)
+          .texture_view("input", input)
+          .sampler("linear_sampler", sampler);
+
+      for (name, view) in self.textures.iter().zip(textures) {
+         bind_group = bind_group.texture_view(name, view);
+      }
+      if let Some(params) = &self.params {
+         bind_group = bind_group.buffer("params", params);
+      }
+
+      // `new` checked the names, so this only fails on a wrong kind of resource
+      match bind_group.build(ctx.device) {
+         Ok(bind_group) => Some(bind_group),
+         Err(err) => {
+            log::error!("{}: {err}", self.path);
+            None
+         }
+      }
+   }
+}
@@ -0,0 +1,137 @@
+use cgmath::{Quaternion, Rad, Rotation3};
+use wgpu::TextureFormat;
+use crate::inbuilt::render_graph::{RenderGraph, Resource};
+use crate::inbuilt::setup::Setup;
+use crate::packages::camera_package::CameraPackage;
+use crate::packages::globals_package::GlobalsPackage;
+use crate::packages::shader_package::ShaderPackage;
+use crate::packages::texture_package::{DecodedImage, SamplerSettings, TexturePackage};
+use crate::packages::time_package::TimePackage;
+use crate::pipelines::instanced_render_pipeline::InstancedRenderPipeline;
+use crate::pipelines::textured_quad_pipeline::{TexturedQuad, TexturedQuadPipeline};
+
+
+/// world units between two instances of the grid
+const GRID_SPACING: f32 = 0.2;
+
+/// a `size` x `size` checkerboard of `cells` squares per side
+fn checkerboard(size: u32, cells: u32) -> DecodedImage {
+   let mut rgba = Vec::with_capacity((size * size * 4) as usize);
+   for y in 0..size {
+      for x in 0..size {
+         let light = (x * cells / size + y * cells / size).is_multiple_of(2);
+         rgba.extend_from_slice(if light { &[230, 230, 230, 255] } else { &[60, 60, 60, 255] });
+      }
+   }
+   DecodedImage { width: size, height: size, rgba }
+}
+
+/// the pipelines the automata doesn't use, each behind its own toggle and drawn over the automata
+pub struct ShowcaseBundle {
+   pub instanced_enabled: bool,
+   /// instances per side of the grid, a bigger grid grows the instance buffer
+   pub grid_size: u32,
+   instanced_pipeline: InstancedRenderPipeline,
+
+   pub quads_enabled: bool,
+   quad_pipeline: TexturedQuadPipeline,
+   checkerboard: TexturePackage,
+
+   format: TextureFormat,
+}
+impl ShowcaseBundle {
+   pub fn new(setup: &Setup, camera_package: &CameraPackage, globals_package: &GlobalsPackage, format: TextureFormat, shaders: &mut ShaderPackage) -> Self {
+      let grid_size = 8;
+      let instances = InstancedRenderPipeline::grid_instances(grid_size, grid_size, GRID_SPACING);
+
+      let quad_pipeline = shaders.create_or_embedded(&setup.device, TexturedQuadPipeline::SHADER_PATH, |shaders| {
+         TexturedQuadPipeline::new(setup, camera_package, format, shaders)
+      });
+      let checkerboard = TexturePackage::from_image(&setup.device, &setup.queue, &quad_pipeline.texture_layout, "checkerboard", &checkerboard(64, 8), SamplerSettings::nearest())
+          .unwrap_or_else(|err| panic!("{err}"));
+
+      Self {
+         instanced_enabled: false,
+         grid_size,
+         instanced_pipeline: shaders.create_or_embedded(&setup.device, InstancedRenderPipeline::SHADER_PATH, |shaders| {
+            InstancedRenderPipeline::new(setup, camera_package, globals_package, format, &instances, shaders)
+         }),
+
+         quads_enabled: false,
+         quad_pipeline,
+         checkerboard,
+
+         format,
+      }
+   }
+
+   /// the checkerboard keeps its bind group, equal layouts are interchangeable
+   pub fn reload_shaders(&mut self, setup: &Setup, camera_package: &CameraPackage, globals_package: &GlobalsPackage, shaders: &mut ShaderPackage, changed: &[String]) {
+      let path = InstancedRenderPipeline::SHADER_PATH;
+      if changed.iter().any(|p| p == path) {
+         let instances = InstancedRenderPipeline::grid_instances(self.grid_size, self.grid_size, GRID_SPACING);
+         match ShaderPackage::try_create(&setup.device, || InstancedRenderPipeline::new(setup, camera_package, globals_package, self.format, &instances, shaders)) {
+            Ok(pipeline) => self.instanced_pipeline = pipeline,
+            Err(err) => shaders.report_error(path, err),
+         }
+      }
+
+      let path = TexturedQuadPipeline::SHADER_PATH;
+      if changed.iter().any(|p| p == path) {
+         match ShaderPackage::try_create(&setup.device, || TexturedQuadPipeline::new(setup, camera_package, self.format, shaders)) {
+            Ok(pipeline) => self.quad_pipeline = pipeline,
+            Err(err) => shaders.report_error(path, err),
+         }
+      }
+   }
+
+   /// spins the grid, every instance is uploaded again each frame
+   pub fn update(&mut self, setup: &Setup, time_package: &TimePackage) {
+      self.quad_pipeline.begin_frame();
+      if !self.instanced_enabled { return; }
+
+      let spin = Quaternion::from_angle_z(Rad(time_package.elapsed() as f32));
+      let mut instances = InstancedRenderPipeline::grid_instances(self.grid_size, self.grid_size, GRID_SPACING);
+      for instance in &mut instances {
+         instance.rotation = spin * instance.rotation;
+      }
+
+      self.instanced_pipeline.instance_package.update(&setup.device, &setup.queue, &instances);
+   }
+
+   /// draws into `target` after whatever produced it
+   pub fn add_passes<'a>(
+      &'a mut self,
+      graph: &mut RenderGraph<'a>,
+      setup: &'a Setup,
+      camera_package: &'a CameraPackage,
+      globals_package: &'a GlobalsPackage,
+      target: Resource,
+   ) {
+      if self.instanced_enabled {
+         let pipeline = &self.instanced_pipeline;
+         graph.add_pass("instanced grid")
+             .read(target)
+             .write(target)
+             .run(move |ctx| {
+                pipeline.render_pass(ctx.encoder, ctx.view(target)?, camera_package, globals_package);
+                Ok(())
+             });
+      }
+
+      if self.quads_enabled {
+         let (pipeline, checkerboard) = (&mut self.quad_pipeline, &self.checkerboard);
+         graph.add_pass("textured quads")
+             .read(target)
+             .write(target)
+             .run(move |ctx| {
+                let quads = [
+                   (checkerboard, TexturedQuad::new([-0.5, 0.5], [0.6, 0.6])),
+                   (checkerboard, TexturedQuad { tint: [1.0, 0.5, 0.5, 0.7], ..TexturedQuad::new([0.5, -0.5], [0.6, 0.6]) }),
+                ];
+                pipeline.render_pass(ctx.encoder, ctx.view(target)?, &setup.queue, camera_package, &quads);
+                Ok(())
+             });
+      }
+   }
+}
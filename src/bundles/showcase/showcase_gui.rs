@@ -0,0 +1,16 @@
+use egui::Context;
+use crate::bundles::showcase::showcase_bundle::ShowcaseBundle;
+
+pub fn gui(ui: &Context, showcase_bundle: &mut ShowcaseBundle) {
+   egui::Window::new("Showcase")
+       .default_open(false)
+       .resizable(false)
+       .show(ui, |ui| {
+          ui.add(egui::Checkbox::new(&mut showcase_bundle.instanced_enabled, "instanced grid"));
+          ui.add_enabled(
+             showcase_bundle.instanced_enabled,
+             egui::Slider::new(&mut showcase_bundle.grid_size, 1..=64).text("grid size"),
+          );
+          ui.add(egui::Checkbox::new(&mut showcase_bundle.quads_enabled, "textured quads"));
+       });
+}
@@ -1,17 +1,121 @@
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
 use egui::epaint::Shadow;
-use egui::{Context, Visuals};
+use egui::{Color32, Context, FontData, FontDefinitions, FontFamily, TextureId, Visuals};
 use egui_wgpu::ScreenDescriptor;
 use egui_wgpu::Renderer;
+use serde::{Deserialize, Serialize};
 
 use egui_winit::State;
-use wgpu::{CommandEncoder, Device, Queue, TextureFormat, TextureView};
+use wgpu::{CommandEncoder, Device, FilterMode, Queue, TextureFormat, TextureView};
+use crate::inbuilt::render_graph::{RenderGraph, Resource};
 use winit::event::WindowEvent;
 use winit::window::Window;
 
+#[derive(Debug)]
+pub enum FontError {
+    Io(PathBuf, std::io::Error),
+    Invalid(PathBuf),
+}
+impl Display for FontError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FontError::Io(path, err) => write!(f, "failed to read {}: {err}", path.display()),
+            FontError::Invalid(path) => write!(f, "{} is not a ttf or otf font", path.display()),
+        }
+    }
+}
+impl std::error::Error for FontError {}
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Theme {
+    Dark,
+    Light,
+}
+
+/// how the ui looks, applied with `EguiRenderer::apply_settings`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiSettings {
+    pub theme: Theme,
+    /// replaces the selection and link color
+    pub accent: Option<[u8; 3]>,
+    /// on top of the window's scale factor
+    pub scale: f32,
+    /// ttf or otf files, used before egui's own fonts in the order given
+    pub fonts: Vec<PathBuf>,
+    /// controls in a side panel and the scene in a viewport next to it, `EguiRenderer` doesn't use it
+    pub docked: bool,
+}
+impl Default for UiSettings {
+    fn default() -> Self {
+        Self {
+            theme: Theme::Dark,
+            accent: None,
+            scale: 1.0,
+            fonts: vec![],
+            docked: false,
+        }
+    }
+}
+
+pub fn visuals(theme: Theme, accent: Option<[u8; 3]>) -> Visuals {
+    const BORDER_RADIUS: f32 = 2.0;
+
+    let mut visuals = Visuals {
+        window_rounding: egui::Rounding::same(BORDER_RADIUS),
+        window_shadow: Shadow::NONE,
+        ..match theme {
+            Theme::Dark => Visuals::dark(),
+            Theme::Light => Visuals::light(),
+        }
+    };
+
+    if let Some([r, g, b]) = accent {
+        let accent = Color32::from_rgb(r, g, b);
+        visuals.selection.bg_fill = accent;
+        visuals.selection.stroke.color = if theme == Theme::Dark { Color32::WHITE } else { Color32::BLACK };
+        visuals.hyperlink_color = accent;
+    }
+    visuals
+}
+
+/// egui's fonts with `paths` in front, every file is checked first since egui panics on a bad one
+pub fn font_definitions(paths: &[PathBuf]) -> Result<FontDefinitions, FontError> {
+    let mut fonts = FontDefinitions::default();
+
+    for (i, path) in paths.iter().enumerate() {
+        let bytes = std::fs::read(path).map_err(|err| FontError::Io(path.clone(), err))?;
+        if ab_glyph::FontRef::try_from_slice(&bytes).is_err() {
+            return Err(FontError::Invalid(path.clone()));
+        }
+
+        let name = path.to_string_lossy().to_string();
+        fonts.font_data.insert(name.clone(), FontData::from_owned(bytes));
+        for family in [FontFamily::Proportional, FontFamily::Monospace] {
+            fonts.families.entry(family).or_default().insert(i, name.clone());
+        }
+    }
+    Ok(fonts)
+}
+
+
+/// a finished ui frame waiting to be drawn
+pub struct EguiFrame {
+    tris: Vec<egui::ClippedPrimitive>,
+    textures_delta: egui::TexturesDelta,
+    screen_descriptor: ScreenDescriptor,
+}
+
 pub struct EguiRenderer {
     pub context: Context,
     state: State,
     renderer: Renderer,
+    /// what `apply_settings` last set up, so unchanged fonts aren't rebuilt
+    applied: UiSettings,
+    /// fonts that failed to load, not read again until the list changes or `reload_fonts` is called
+    failed_fonts: Option<Vec<PathBuf>>,
 }
 
 impl EguiRenderer {
@@ -25,16 +129,8 @@ impl EguiRenderer {
         let egui_context = Context::default();
         let id = egui_context.viewport_id();
 
-        const BORDER_RADIUS: f32 = 2.0;
-
-        let visuals = Visuals {
-            window_rounding: egui::Rounding::same(BORDER_RADIUS),
-            window_shadow: Shadow::NONE,
-            // menu_rounding: ,
-            ..Default::default()
-        };
-
-        egui_context.set_visuals(visuals);
+        let applied = UiSettings::default();
+        egui_context.set_visuals(visuals(applied.theme, applied.accent));
 
         let egui_state = State::new(egui_context.clone(), id, &window, None, None);
 
@@ -50,13 +146,75 @@ impl EguiRenderer {
             context: egui_context,
             state: egui_state,
             renderer: egui_renderer,
+            applied,
+            failed_fonts: None,
+        }
+    }
+
+    pub fn set_theme(&self, theme: Theme, accent: Option<[u8; 3]>) {
+        self.context.set_visuals(visuals(theme, accent));
+    }
+
+    /// multiplies the window's `pixels_per_point`, 1.0 leaves it alone
+    pub fn set_ui_scale(&self, scale: f32) {
+        self.context.set_zoom_factor(scale.clamp(0.25, 4.0));
+    }
+
+    /// on an error the fonts that were there before stay
+    pub fn load_fonts(&self, paths: &[PathBuf]) -> Result<(), FontError> {
+        self.context.set_fonts(font_definitions(paths)?);
+        Ok(())
+    }
+
+    /// loads the fonts again on the next `apply_settings`, e.g. after a font file was fixed
+    pub fn reload_fonts(&mut self) {
+        self.failed_fonts = None;
+        self.applied.fonts.clear();
+    }
+
+    /// applies whatever changed since the last call. a bad font is reported once but doesn't stop the rest,
+    /// the same fonts are only tried again after `reload_fonts`
+    pub fn apply_settings(&mut self, settings: &UiSettings) -> Result<(), FontError> {
+        if *settings == self.applied { return Ok(()); }
+        let fonts = self.applied.fonts.clone();
+        let previous = std::mem::replace(&mut self.applied, UiSettings { fonts, ..settings.clone() });
+
+        if (settings.theme, settings.accent) != (previous.theme, previous.accent) {
+            self.set_theme(settings.theme, settings.accent);
+        }
+        if settings.scale != previous.scale {
+            self.set_ui_scale(settings.scale);
         }
+        if settings.fonts != previous.fonts && self.failed_fonts.as_ref() != Some(&settings.fonts) {
+            if let Err(err) = self.load_fonts(&settings.fonts) {
+                self.failed_fonts = Some(settings.fonts.clone());
+                return Err(err);
+            }
+            self.failed_fonts = None;
+            self.applied.fonts = settings.fonts.clone();
+        }
+        Ok(())
+    }
+
+    /// lets the ui show a texture the app draws into, the id goes into an `egui::Image`
+    pub fn register_native_texture(&mut self, device: &Device, view: &TextureView, filter: FilterMode) -> TextureId {
+        self.renderer.register_native_texture(device, view, filter)
+    }
+
+    /// points `id` at another texture, after the one it showed was recreated
+    pub fn update_native_texture(&mut self, device: &Device, view: &TextureView, filter: FilterMode, id: TextureId) {
+        self.renderer.update_egui_texture_from_wgpu_texture(device, view, filter, id);
+    }
+
+    pub fn free_native_texture(&mut self, id: TextureId) {
+        self.renderer.free_texture(&id);
     }
 
     pub fn handle_input(&mut self, window: &Window, event: &WindowEvent) {
         let _ = self.state.on_window_event(window, event);
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn draw(
         &mut self,
         device: &Device,
@@ -67,27 +225,69 @@ impl EguiRenderer {
         screen_descriptor: ScreenDescriptor,
         run_ui: impl FnOnce(&Context),
     ) {
-        // self.state.set_pixels_per_point(window.scale_factor() as f32);
-        let raw_input = self.state.take_egui_input(&window);
+        let frame = self.prepare(window, screen_descriptor, run_ui);
+        self.render(device, queue, encoder, window_surface_view, frame);
+    }
+
+    /// runs the ui and tessellates it, nothing touches the gpu until `render`
+    pub fn prepare(
+        &mut self,
+        window: &Window,
+        mut screen_descriptor: ScreenDescriptor,
+        run_ui: impl FnOnce(&Context),
+    ) -> EguiFrame {
+        let raw_input = self.state.take_egui_input(window);
         let full_output = self.context.run(raw_input, |_| {
             run_ui(&self.context);
         });
 
         self.state
-            .handle_platform_output(&window, full_output.platform_output);
+            .handle_platform_output(window, full_output.platform_output);
 
         let tris = self
             .context
             .tessellate(full_output.shapes, full_output.pixels_per_point);
-        for (id, image_delta) in &full_output.textures_delta.set {
+
+        // includes the ui scale, the window's scale factor alone would draw at the wrong size
+        screen_descriptor.pixels_per_point = full_output.pixels_per_point;
+
+        EguiFrame {
+            tris,
+            textures_delta: full_output.textures_delta,
+            screen_descriptor,
+        }
+    }
+
+    /// draws `frame` on top of whatever is already on the swapchain
+    pub fn add_pass<'a>(&'a mut self, graph: &mut RenderGraph<'a>, device: &'a Device, queue: &'a Queue, frame: EguiFrame) {
+        graph.add_pass("egui")
+            .read(Resource::Swapchain)
+            .write(Resource::Swapchain)
+            .run(move |ctx| {
+                self.render(device, queue, ctx.encoder, ctx.view(Resource::Swapchain)?, frame);
+                Ok(())
+            });
+    }
+
+    pub fn render(
+        &mut self,
+        device: &Device,
+        queue: &Queue,
+        encoder: &mut CommandEncoder,
+        window_surface_view: &TextureView,
+        frame: EguiFrame,
+    ) {
+        let EguiFrame { tris, textures_delta, screen_descriptor } = frame;
+
+        for (id, image_delta) in &textures_delta.set {
             self.renderer
-                .update_texture(&device, &queue, *id, &image_delta);
+                .update_texture(device, queue, *id, image_delta);
         }
         self.renderer
-            .update_buffers(&device, &queue, encoder, &tris, &screen_descriptor);
+            .update_buffers(device, queue, encoder, &tris, &screen_descriptor);
         let mut rpass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
             color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                view: &window_surface_view,
+                view: window_surface_view,
                 resolve_target: None,
                 ops: wgpu::Operations {
                     load: wgpu::LoadOp::Load,
@@ -101,7 +301,7 @@ impl EguiRenderer {
         });
         self.renderer.render(&mut rpass, &tris, &screen_descriptor);
         drop(rpass);
-        for x in &full_output.textures_delta.free {
+        for x in &textures_delta.free {
             self.renderer.free_texture(x)
         }
     }
@@ -23,5 +23,5 @@ pub fn gui(
         .default_width(800.0)
         .resizable(true)
         .anchor(Align2::LEFT_TOP, [0.0, 0.0])
-        .show(&ui, code);
+        .show(ui, code);
 }
\ No newline at end of file
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+use egui::Context;
+use crate::egui::gui::{FontError, Theme, UiSettings};
+
+/// edits `settings`, the app hands them to `EguiRenderer::apply_settings` after the frame
+pub struct UiSettingsPanel {
+   pub settings: UiSettings,
+   /// the scale slider only takes effect when it is let go, the ui would resize under the cursor otherwise
+   pending_scale: f32,
+   font_path: String,
+   reload_fonts: bool,
+   status: Option<String>,
+}
+impl UiSettingsPanel {
+   pub fn new(settings: UiSettings) -> Self {
+      Self {
+         pending_scale: settings.scale,
+         settings,
+         font_path: String::new(),
+         reload_fonts: false,
+         status: None,
+      }
+   }
+
+   /// replaces the settings from outside, like a reset
+   pub fn set(&mut self, settings: UiSettings) {
+      *self = Self::new(settings);
+   }
+
+   pub fn report(&mut self, result: Result<(), FontError>) {
+      if let Err(err) = result {
+         log::error!("{err}");
+         self.status = Some(err.to_string());
+      }
+   }
+
+   /// whether "reload fonts" was clicked since the last call
+   pub fn take_reload_fonts(&mut self) -> bool {
+      std::mem::take(&mut self.reload_fonts)
+   }
+
+   pub fn gui(&mut self, ctx: &Context) {
+      egui::Window::new("Appearance")
+          .default_open(false)
+          .resizable(false)
+          .show(ctx, |ui| {
+             ui.horizontal(|ui| {
+                ui.radio_value(&mut self.settings.theme, Theme::Dark, "dark");
+                ui.radio_value(&mut self.settings.theme, Theme::Light, "light");
+             });
+
+             ui.horizontal(|ui| {
+                let mut custom = self.settings.accent.is_some();
+                ui.add(egui::Checkbox::new(&mut custom, "accent"));
+
+                let mut accent = self.settings.accent.unwrap_or([90, 170, 255]);
+                if custom {
+                   ui.color_edit_button_srgb(&mut accent);
+                }
+                self.settings.accent = custom.then_some(accent);
+             });
+
+             ui.add(egui::Checkbox::new(&mut self.settings.docked, "docked layout (F2)"));
+
+             let response = ui.add(egui::Slider::new(&mut self.pending_scale, 0.5..=3.0).text("ui scale"));
+             if response.drag_stopped() || (response.changed() && !response.dragged()) {
+                self.settings.scale = self.pending_scale;
+             }
+
+             ui.add_space(10.0);
+             ui.label("fonts");
+             let mut remove = None;
+             for (i, font) in self.settings.fonts.iter().enumerate() {
+                ui.horizontal(|ui| {
+                   ui.label(font.display().to_string());
+                   if ui.small_button("remove").clicked() { remove = Some(i); }
+                });
+             }
+             if let Some(i) = remove {
+                self.settings.fonts.remove(i);
+                self.status = None;
+             }
+
+             ui.horizontal(|ui| {
+                ui.add(egui::TextEdit::singleline(&mut self.font_path).hint_text("path to a .ttf or .otf"));
+                if ui.add_enabled(!self.font_path.trim().is_empty(), egui::Button::new("add")).clicked() {
+                   self.settings.fonts.push(PathBuf::from(self.font_path.trim()));
+                   self.font_path.clear();
+                   self.status = None;
+                }
+             });
+             if !self.settings.fonts.is_empty() && ui.button("reload fonts").clicked() {
+                self.reload_fonts = true;
+                self.status = None;
+             }
+
+             if let Some(status) = &self.status {
+                ui.colored_label(ui.visuals().error_fg_color, status);
+             }
+          });
+   }
+}
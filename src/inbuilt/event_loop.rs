@@ -1,15 +1,18 @@
+use log::LevelFilter;
 use winit::event::{ElementState, Event, KeyEvent, WindowEvent};
 use winit::event_loop::EventLoop;
 use winit::keyboard::{KeyCode, PhysicalKey};
 use winit::window::WindowBuilder;
+use crate::packages::log_package;
 use crate::state::State;
 
+#[allow(clippy::collapsible_match)]
 pub async fn run() {
-   env_logger::init();
+   let logs = log_package::init(LevelFilter::Info);
    let event_loop = EventLoop::new().unwrap();
    let window = WindowBuilder::new().build(&event_loop).unwrap();
 
-   let mut state = State::new(&window).await;
+   let mut state = State::new(&window, logs).await;
    let mut surface_configured = false;
 
    event_loop.run(move |event, control_flow| {
@@ -67,9 +70,10 @@ pub async fn run() {
                   }
                   _ => {}
                }
-               state.egui.handle_input(&mut state.setup.window, &event);
+               state.egui.handle_input(state.setup.window, event);
             }
          }
+         Event::LoopExiting => state.save(),
          _ => {}
       }
    }).unwrap();
@@ -0,0 +1,90 @@
+use bytemuck::{Pod, Zeroable};
+use cgmath::{Matrix4, Quaternion, Vector3};
+use wgpu::{Buffer, BufferUsages, Device, Queue, VertexAttribute};
+use wgpu::util::DeviceExt;
+use crate::utility::functions::write_or_grow_buffer;
+
+
+pub struct Instance {
+   pub position: Vector3<f32>,
+   pub rotation: Quaternion<f32>,
+   pub scale: Vector3<f32>,
+   pub color: [f32; 4],
+}
+impl Instance {
+   pub fn to_raw(&self) -> InstanceRaw {
+      let model = Matrix4::from_translation(self.position)
+          * Matrix4::from(self.rotation)
+          * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z);
+
+      InstanceRaw {
+         model: model.into(),
+         color: self.color,
+      }
+   }
+}
+
+
+/// @location(5..=8) model matrix columns | @location(9) color
+/// starts at 5 so it doesn't collide with any of the vertex types
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct InstanceRaw {
+   pub model: [[f32; 4]; 4],
+   pub color: [f32; 4],
+}
+impl InstanceRaw {
+   const ATTRIBUTES: [VertexAttribute; 5] = wgpu::vertex_attr_array![
+      5 => Float32x4,
+      6 => Float32x4,
+      7 => Float32x4,
+      8 => Float32x4,
+      9 => Float32x4,
+   ];
+
+   pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+      wgpu::VertexBufferLayout {
+         array_stride: std::mem::size_of::<InstanceRaw>() as wgpu::BufferAddress,
+         step_mode: wgpu::VertexStepMode::Instance,
+         attributes: &Self::ATTRIBUTES,
+      }
+   }
+}
+
+
+pub struct InstancePackage {
+   pub instance_buffer: Buffer,
+   pub num_instances: u32,
+}
+impl InstancePackage {
+   pub fn new(device: &Device, instances: &[Instance]) -> Self {
+      let raw: Vec<InstanceRaw> = instances.iter().map(Instance::to_raw).collect();
+
+      // never empty so the buffer can always be bound
+      let contents = if raw.is_empty() { vec![InstanceRaw::zeroed()] } else { raw };
+
+      let instance_buffer = device.create_buffer_init(
+         &wgpu::util::BufferInitDescriptor {
+            label: Some("Instance Buffer"),
+            contents: bytemuck::cast_slice(&contents),
+            usage: BufferUsages::VERTEX | BufferUsages::COPY_DST,
+         }
+      );
+
+      Self {
+         instance_buffer,
+         num_instances: instances.len() as u32,
+      }
+   }
+
+   pub fn update(&mut self, device: &Device, queue: &Queue, instances: &[Instance]) {
+      let raw: Vec<InstanceRaw> = instances.iter().map(Instance::to_raw).collect();
+      self.update_raw(device, queue, &raw);
+   }
+
+   /// skips the matrix building when the caller already has raw instance data
+   pub fn update_raw(&mut self, device: &Device, queue: &Queue, instances: &[InstanceRaw]) {
+      write_or_grow_buffer(device, queue, &mut self.instance_buffer, "Instance Buffer", bytemuck::cast_slice(instances), BufferUsages::VERTEX);
+      self.num_instances = instances.len() as u32;
+   }
+}
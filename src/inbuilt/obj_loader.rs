@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use wgpu::Device;
+use crate::inbuilt::vertex_package::{LitVertex, VertexPackage};
+
+
+#[derive(Debug)]
+pub enum ObjError {
+   Io(std::io::Error),
+   Parse { line: usize, message: String },
+}
+impl Display for ObjError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         ObjError::Io(err) => write!(f, "failed to read obj file: {err}"),
+         ObjError::Parse { line, message } => write!(f, "obj parse error on line {line}: {message}"),
+      }
+   }
+}
+impl std::error::Error for ObjError {}
+impl From<std::io::Error> for ObjError {
+   fn from(err: std::io::Error) -> Self {
+      ObjError::Io(err)
+   }
+}
+
+
+/// one `o` block of an obj file, vertices are deduplicated on their position/uv/normal triple
+pub struct ObjMesh {
+   pub name: String,
+   pub vertices: Vec<LitVertex>,
+   pub indices: Vec<u32>,
+}
+impl ObjMesh {
+   pub fn to_vertex_package(&self, device: &Device) -> VertexPackage<LitVertex> {
+      VertexPackage::new(device, &self.vertices, &self.indices)
+   }
+}
+
+
+pub fn load_obj(path: impl AsRef<Path>) -> Result<Vec<ObjMesh>, ObjError> {
+   let source = std::fs::read_to_string(path)?;
+   parse_obj(&source)
+}
+
+pub fn load_obj_packages(device: &Device, path: impl AsRef<Path>) -> Result<Vec<(String, VertexPackage<LitVertex>)>, ObjError> {
+   Ok(load_obj(path)?
+       .into_iter()
+       .map(|mesh| {
+          let package = mesh.to_vertex_package(device);
+          (mesh.name, package)
+       })
+       .collect())
+}
+
+
+/// index into the position / uv / normal lists, all zero based
+type VertexKey = (usize, Option<usize>, Option<usize>);
+
+struct MeshBuilder {
+   mesh: ObjMesh,
+   lookup: HashMap<VertexKey, u32>,
+}
+impl MeshBuilder {
+   fn new(name: String) -> Self {
+      Self {
+         mesh: ObjMesh { name, vertices: vec![], indices: vec![] },
+         lookup: HashMap::new(),
+      }
+   }
+}
+
+pub fn parse_obj(source: &str) -> Result<Vec<ObjMesh>, ObjError> {
+   let mut positions: Vec<[f32; 3]> = vec![];
+   let mut uvs: Vec<[f32; 2]> = vec![];
+   let mut normals: Vec<[f32; 3]> = vec![];
+
+   let mut meshes = vec![];
+   let mut current = MeshBuilder::new("default".to_string());
+
+   for (i, raw_line) in source.lines().enumerate() {
+      let line_number = i + 1;
+      let line = match raw_line.find('#') {
+         Some(comment) => &raw_line[..comment],
+         None => raw_line,
+      };
+
+      let mut parts = line.split_whitespace();
+      let Some(keyword) = parts.next() else { continue };
+      let args: Vec<&str> = parts.collect();
+
+      match keyword {
+         "v" => {
+            let v = parse_floats(&args, 3, 4, line_number)?;
+            positions.push([v[0], v[1], v[2]]);
+         }
+         "vt" => {
+            let v = parse_floats(&args, 1, 3, line_number)?;
+            uvs.push([v[0], v.get(1).copied().unwrap_or(0.0)]);
+         }
+         "vn" => {
+            let v = parse_floats(&args, 3, 3, line_number)?;
+            normals.push([v[0], v[1], v[2]]);
+         }
+         "o" => {
+            let name = args.join(" ");
+            let finished = std::mem::replace(&mut current, MeshBuilder::new(name));
+            if !finished.mesh.indices.is_empty() { meshes.push(finished.mesh); }
+         }
+         "f" => {
+            if args.len() < 3 {
+               return Err(parse_error(line_number, format!("face needs at least 3 vertices, found {}", args.len())));
+            }
+
+            let mut face = Vec::with_capacity(args.len());
+            for arg in &args {
+               let key = parse_face_vertex(arg, positions.len(), uvs.len(), normals.len(), line_number)?;
+
+               let index = match current.lookup.get(&key) {
+                  Some(index) => *index,
+                  None => {
+                     let index = current.mesh.vertices.len() as u32;
+                     current.mesh.vertices.push(LitVertex {
+                        position: positions[key.0],
+                        uv: key.1.map(|i| uvs[i]).unwrap_or([0.0, 0.0]),
+                        normal: key.2.map(|i| normals[i]).unwrap_or([0.0, 0.0, 0.0]),
+                     });
+                     current.lookup.insert(key, index);
+                     index
+                  }
+               };
+               face.push(index);
+            }
+
+            // fan triangulation keeps the winding of the source polygon
+            for j in 1..face.len() - 1 {
+               current.mesh.indices.extend_from_slice(&[face[0], face[j], face[j + 1]]);
+            }
+         }
+         // materials, smoothing groups and groups are not used
+         "mtllib" | "usemtl" | "s" | "g" | "l" | "p" => {}
+         // free form geometry and anything else we don't draw is skipped, the rest of the file still loads
+         other => log::warn!("obj line {line_number}: skipping unsupported keyword `{other}`"),
+      }
+   }
+
+   if !current.mesh.indices.is_empty() { meshes.push(current.mesh); }
+
+   Ok(meshes)
+}
+
+fn parse_floats(args: &[&str], min: usize, max: usize, line: usize) -> Result<Vec<f32>, ObjError> {
+   if args.len() < min || args.len() > max {
+      return Err(parse_error(line, format!("expected {min} to {max} numbers, found {}", args.len())));
+   }
+
+   args.iter()
+       .map(|arg| arg.parse::<f32>().map_err(|_| parse_error(line, format!("`{arg}` is not a number"))))
+       .collect()
+}
+
+fn parse_face_vertex(arg: &str, num_positions: usize, num_uvs: usize, num_normals: usize, line: usize) -> Result<VertexKey, ObjError> {
+   let mut parts = arg.split('/');
+
+   let position = match parts.next() {
+      Some(part) if !part.is_empty() => resolve_index(part, num_positions, "position", line)?,
+      _ => return Err(parse_error(line, format!("face vertex `{arg}` has no position index"))),
+   };
+   let uv = match parts.next() {
+      Some(part) if !part.is_empty() => Some(resolve_index(part, num_uvs, "texture coordinate", line)?),
+      _ => None,
+   };
+   let normal = match parts.next() {
+      Some(part) if !part.is_empty() => Some(resolve_index(part, num_normals, "normal", line)?),
+      _ => None,
+   };
+
+   if parts.next().is_some() {
+      return Err(parse_error(line, format!("face vertex `{arg}` has too many components")));
+   }
+
+   Ok((position, uv, normal))
+}
+
+/// obj indices are one based, negative indices count back from the last element
+fn resolve_index(part: &str, len: usize, kind: &str, line: usize) -> Result<usize, ObjError> {
+   let index: i64 = part.parse().map_err(|_| parse_error(line, format!("`{part}` is not a valid {kind} index")))?;
+
+   let resolved = match index {
+      0 => None,
+      i if i > 0 => Some(i - 1),
+      i => Some(len as i64 + i),
+   };
+
+   match resolved {
+      Some(i) if i >= 0 && (i as usize) < len => Ok(i as usize),
+      _ => Err(parse_error(line, format!("{kind} index {index} is out of range, {len} defined so far"))),
+   }
+}
+
+fn parse_error(line: usize, message: String) -> ObjError {
+   ObjError::Parse { line, message }
+}
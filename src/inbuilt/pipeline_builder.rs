@@ -0,0 +1,228 @@
+use wgpu::{BindGroupLayout, BlendState, ComputePipeline, Device, Face, PipelineLayout, PrimitiveTopology, RenderPipeline, ShaderModule, TextureFormat, VertexBufferLayout};
+use crate::inbuilt::setup::Setup;
+
+
+/// builds a `RenderPipeline` with the defaults every pipeline in the crate uses,
+/// target format and sample count are taken from `Setup` unless overridden
+pub struct RenderPipelineBuilder<'a> {
+   label: &'a str,
+   shader: &'a ShaderModule,
+   vs_entry: &'a str,
+   fs_entry: &'a str,
+   bind_group_layouts: Vec<&'a BindGroupLayout>,
+   pipeline_layout: Option<&'a PipelineLayout>,
+   vertex_layouts: Vec<VertexBufferLayout<'a>>,
+   format: TextureFormat,
+   blend: Option<BlendState>,
+   cull_mode: Option<Face>,
+   topology: PrimitiveTopology,
+   sample_count: u32,
+   depth_format: Option<TextureFormat>,
+}
+impl<'a> RenderPipelineBuilder<'a> {
+   pub fn new(setup: &Setup, shader: &'a ShaderModule) -> Self {
+      Self {
+         label: "Render Pipeline",
+         shader,
+         vs_entry: "vs_main",
+         fs_entry: "fs_main",
+         bind_group_layouts: vec![],
+         pipeline_layout: None,
+         vertex_layouts: vec![],
+         format: setup.config.format,
+         blend: Some(BlendState::REPLACE),
+         cull_mode: Some(Face::Back),
+         topology: PrimitiveTopology::TriangleList,
+         sample_count: setup.sample_count,
+         depth_format: None,
+      }
+   }
+
+   pub fn label(mut self, label: &'a str) -> Self {
+      self.label = label;
+      self
+   }
+
+   pub fn entry_points(mut self, vs_entry: &'a str, fs_entry: &'a str) -> Self {
+      self.vs_entry = vs_entry;
+      self.fs_entry = fs_entry;
+      self
+   }
+
+   /// bound in the order they are added, the first one is @group(0)
+   pub fn bind_group_layout(mut self, layout: &'a BindGroupLayout) -> Self {
+      self.bind_group_layouts.push(layout);
+      self
+   }
+
+   /// uses a finished layout, e.g. from `ShaderLayout`, instead of the added bind group layouts
+   pub fn pipeline_layout(mut self, layout: &'a PipelineLayout) -> Self {
+      self.pipeline_layout = Some(layout);
+      self
+   }
+
+   /// bound in the order they are added, the first one is vertex buffer slot 0
+   pub fn vertex_layout(mut self, layout: VertexBufferLayout<'a>) -> Self {
+      self.vertex_layouts.push(layout);
+      self
+   }
+
+   pub fn format(mut self, format: TextureFormat) -> Self {
+      self.format = format;
+      self
+   }
+
+   pub fn blend(mut self, blend: Option<BlendState>) -> Self {
+      self.blend = blend;
+      self
+   }
+
+   pub fn cull_mode(mut self, cull_mode: Option<Face>) -> Self {
+      self.cull_mode = cull_mode;
+      self
+   }
+
+   pub fn topology(mut self, topology: PrimitiveTopology) -> Self {
+      self.topology = topology;
+      self
+   }
+
+   pub fn sample_count(mut self, sample_count: u32) -> Self {
+      self.sample_count = sample_count;
+      self
+   }
+
+   /// enables depth testing with `Less` and depth writes
+   pub fn depth(mut self, format: TextureFormat) -> Self {
+      self.depth_format = Some(format);
+      self
+   }
+
+   pub fn build(self, device: &Device) -> RenderPipeline {
+      let created;
+      let layout = match self.pipeline_layout {
+         Some(layout) => layout,
+         None => {
+            created = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+               label: Some(self.label),
+               bind_group_layouts: &self.bind_group_layouts,
+               push_constant_ranges: &[],
+            });
+            &created
+         }
+      };
+
+      device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+         label: Some(self.label),
+         layout: Some(layout),
+
+         vertex: wgpu::VertexState {
+            module: self.shader,
+            entry_point: self.vs_entry,
+            buffers: &self.vertex_layouts,
+         },
+
+         fragment: Some(wgpu::FragmentState {
+            module: self.shader,
+            entry_point: self.fs_entry,
+            targets: &[Some(wgpu::ColorTargetState {
+               format: self.format,
+               blend: self.blend,
+               write_mask: wgpu::ColorWrites::ALL,
+            })],
+         }),
+
+         primitive: wgpu::PrimitiveState {
+            topology: self.topology,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            cull_mode: self.cull_mode,
+            // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+            polygon_mode: wgpu::PolygonMode::Fill,
+            // Requires Features::DEPTH_CLIP_CONTROL
+            unclipped_depth: false,
+            // Requires Features::CONSERVATIVE_RASTERIZATION
+            conservative: false,
+         },
+
+         depth_stencil: self.depth_format.map(|format| wgpu::DepthStencilState {
+            format,
+            depth_write_enabled: true,
+            depth_compare: wgpu::CompareFunction::Less,
+            stencil: wgpu::StencilState::default(),
+            bias: wgpu::DepthBiasState::default(),
+         }),
+         multisample: wgpu::MultisampleState {
+            count: self.sample_count,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+         },
+
+         multiview: None,
+      })
+   }
+}
+
+
+pub struct ComputePipelineBuilder<'a> {
+   label: &'a str,
+   shader: &'a ShaderModule,
+   entry_point: &'a str,
+   bind_group_layouts: Vec<&'a BindGroupLayout>,
+   pipeline_layout: Option<&'a PipelineLayout>,
+}
+impl<'a> ComputePipelineBuilder<'a> {
+   pub fn new(shader: &'a ShaderModule) -> Self {
+      Self {
+         label: "Compute Pipeline",
+         shader,
+         entry_point: "cs_main",
+         bind_group_layouts: vec![],
+         pipeline_layout: None,
+      }
+   }
+
+   pub fn label(mut self, label: &'a str) -> Self {
+      self.label = label;
+      self
+   }
+
+   pub fn entry_point(mut self, entry_point: &'a str) -> Self {
+      self.entry_point = entry_point;
+      self
+   }
+
+   /// bound in the order they are added, the first one is @group(0)
+   pub fn bind_group_layout(mut self, layout: &'a BindGroupLayout) -> Self {
+      self.bind_group_layouts.push(layout);
+      self
+   }
+
+   /// uses a finished layout, e.g. from `ShaderLayout`, instead of the added bind group layouts
+   pub fn pipeline_layout(mut self, layout: &'a PipelineLayout) -> Self {
+      self.pipeline_layout = Some(layout);
+      self
+   }
+
+   pub fn build(self, device: &Device) -> ComputePipeline {
+      let created;
+      let layout = match self.pipeline_layout {
+         Some(layout) => layout,
+         None => {
+            created = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+               label: Some(self.label),
+               bind_group_layouts: &self.bind_group_layouts,
+               push_constant_ranges: &[],
+            });
+            &created
+         }
+      };
+
+      device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+         label: Some(self.label),
+         layout: Some(layout),
+         module: self.shader,
+         entry_point: self.entry_point,
+      })
+   }
+}
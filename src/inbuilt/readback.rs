@@ -0,0 +1,184 @@
+use std::fmt::{Display, Formatter};
+use std::marker::PhantomData;
+use std::sync::Arc;
+use std::sync::mpsc::{channel, Receiver, TryRecvError};
+use bytemuck::Pod;
+use wgpu::{Buffer, BufferAddress, BufferAsyncError, CommandEncoder, Device, Texture, TextureFormat};
+
+
+#[derive(Debug)]
+pub enum ReadbackError {
+   /// depth stencil and compressed formats can't be copied out as a whole
+   UnsupportedFormat(TextureFormat),
+   Map(BufferAsyncError),
+   /// the device was dropped before the mapping finished
+   Disconnected,
+}
+impl Display for ReadbackError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         ReadbackError::UnsupportedFormat(format) => write!(f, "can't read back a {format:?} texture"),
+         ReadbackError::Map(err) => write!(f, "failed to map the readback buffer: {err}"),
+         ReadbackError::Disconnected => write!(f, "the device was dropped before the readback finished"),
+      }
+   }
+}
+impl std::error::Error for ReadbackError {}
+
+
+/// `bytes_per_row` of a texture to buffer copy has to be a multiple of 256
+pub fn padded_bytes_per_row(width: u32, bytes_per_pixel: u32) -> u32 {
+   (width * bytes_per_pixel).next_multiple_of(wgpu::COPY_BYTES_PER_ROW_ALIGNMENT)
+}
+
+/// drops the padding at the end of every row
+pub fn unpad_rows(data: &[u8], unpadded_bytes_per_row: usize, padded_bytes_per_row: usize, height: usize) -> Vec<u8> {
+   let mut packed = Vec::with_capacity(unpadded_bytes_per_row * height);
+   for row in data.chunks(padded_bytes_per_row).take(height) {
+      packed.extend_from_slice(&row[..unpadded_bytes_per_row]);
+   }
+   packed
+}
+
+
+/// copies instead of casting, the mapped bytes aren't guaranteed to be aligned for `T`
+pub fn to_vec<T: Pod>(bytes: &[u8]) -> Vec<T> {
+   let size = std::mem::size_of::<T>();
+   let mut data = vec![T::zeroed(); bytes.len() / size];
+   let used = data.len() * size;
+   bytemuck::cast_slice_mut(&mut data).copy_from_slice(&bytes[..used]);
+   data
+}
+
+
+/// how the staging buffer is laid out, buffers are one unpadded row
+#[derive(Copy, Clone)]
+struct Rows {
+   unpadded: usize,
+   padded: usize,
+   count: usize,
+}
+impl Rows {
+   fn read(&self, buffer: &Buffer) -> Vec<u8> {
+      let mapped = buffer.slice(..).get_mapped_range();
+      unpad_rows(&mapped, self.unpadded, self.padded, self.count)
+   }
+}
+
+
+/// a copy into a `MAP_READ` staging buffer recorded into an encoder. once that encoder is submitted,
+/// `map` starts reading it back without blocking, `then` hands the result to a callback instead.
+/// texture data comes back tightly packed, row after row
+pub struct Readback<T: Pod> {
+   buffer: Arc<Buffer>,
+   rows: Rows,
+   marker: PhantomData<T>,
+}
+impl<T: Pod> Readback<T> {
+   /// `size` bytes of `source` from `offset`, both have to be multiples of 4
+   pub fn buffer(device: &Device, encoder: &mut CommandEncoder, source: &Buffer, offset: BufferAddress, size: BufferAddress) -> Self {
+      let staging = Self::staging(device, size);
+      encoder.copy_buffer_to_buffer(source, offset, &staging, 0, size);
+
+      let rows = Rows { unpadded: size as usize, padded: size as usize, count: 1 };
+      Self { buffer: Arc::new(staging), rows, marker: PhantomData }
+   }
+
+   /// every layer of mip 0, the texture needs `COPY_SRC`
+   pub fn texture(device: &Device, encoder: &mut CommandEncoder, texture: &Texture) -> Result<Self, ReadbackError> {
+      let format = texture.format();
+      let bytes_per_pixel = format.block_copy_size(None)
+          .filter(|_| format.block_dimensions() == (1, 1))
+          .ok_or(ReadbackError::UnsupportedFormat(format))?;
+
+      let size = texture.size();
+      let padded = padded_bytes_per_row(size.width, bytes_per_pixel);
+      let count = size.height * size.depth_or_array_layers;
+
+      let staging = Self::staging(device, (padded * count) as BufferAddress);
+      encoder.copy_texture_to_buffer(
+         texture.as_image_copy(),
+         wgpu::ImageCopyBuffer {
+            buffer: &staging,
+            layout: wgpu::ImageDataLayout {
+               offset: 0,
+               bytes_per_row: Some(padded),
+               rows_per_image: Some(size.height),
+            },
+         },
+         size,
+      );
+
+      let rows = Rows { unpadded: (size.width * bytes_per_pixel) as usize, padded: padded as usize, count: count as usize };
+      Ok(Self { buffer: Arc::new(staging), rows, marker: PhantomData })
+   }
+
+   fn staging(device: &Device, size: BufferAddress) -> Buffer {
+      device.create_buffer(&wgpu::BufferDescriptor {
+         label: Some("readback staging buffer"),
+         size,
+         usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+         mapped_at_creation: false,
+      })
+   }
+
+   /// starts mapping, only call it after the encoder with the copy was submitted
+   pub fn map(self) -> PendingReadback<T> {
+      let (sender, receiver) = channel();
+      self.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| { let _ = sender.send(result); });
+
+      PendingReadback { buffer: self.buffer, rows: self.rows, receiver, marker: PhantomData }
+   }
+
+   /// maps and calls `callback` with the data once the gpu is done. callbacks only run while the device is
+   /// polled, `State::update` does that once per frame, anywhere else call `device.poll` yourself
+   pub fn then(self, callback: impl FnOnce(Result<Vec<T>, ReadbackError>) + Send + 'static) {
+      let (buffer, rows) = (self.buffer.clone(), self.rows);
+
+      self.buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+         let data = result.map_err(ReadbackError::Map).map(|_| {
+            let bytes = rows.read(&buffer);
+            buffer.unmap();
+            to_vec(&bytes)
+         });
+         callback(data);
+      });
+   }
+}
+
+
+/// a readback that is being mapped
+pub struct PendingReadback<T: Pod> {
+   buffer: Arc<Buffer>,
+   rows: Rows,
+   receiver: Receiver<Result<(), BufferAsyncError>>,
+   marker: PhantomData<T>,
+}
+impl<T: Pod> PendingReadback<T> {
+   /// the data if the gpu is done, never blocks
+   pub fn try_take(&self, device: &Device) -> Option<Result<Vec<T>, ReadbackError>> {
+      device.poll(wgpu::Maintain::Poll);
+
+      match self.receiver.try_recv() {
+         Ok(result) => Some(self.finish(result)),
+         Err(TryRecvError::Empty) => None,
+         Err(TryRecvError::Disconnected) => Some(Err(ReadbackError::Disconnected)),
+      }
+   }
+
+   /// blocks until the data is there, for tests and one off tools
+   pub fn wait(self, device: &Device) -> Result<Vec<T>, ReadbackError> {
+      device.poll(wgpu::Maintain::Wait);
+
+      let result = self.receiver.recv().map_err(|_| ReadbackError::Disconnected)?;
+      self.finish(result)
+   }
+
+   fn finish(&self, result: Result<(), BufferAsyncError>) -> Result<Vec<T>, ReadbackError> {
+      result.map_err(ReadbackError::Map)?;
+
+      let bytes = self.rows.read(&self.buffer);
+      self.buffer.unmap();
+      Ok(to_vec(&bytes))
+   }
+}
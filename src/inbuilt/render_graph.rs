@@ -0,0 +1,362 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+use wgpu::{CommandEncoder, Device, Texture, TextureFormat, TextureUsages, TextureView};
+use crate::inbuilt::setup::Setup;
+
+
+/// something a pass reads or writes, names only need to be unique within a frame
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Resource {
+   Swapchain,
+   Texture(&'static str),
+   Buffer(&'static str),
+}
+
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TargetSize {
+   Swapchain,
+   /// the swapchain size divided by n, rounded up
+   Divided(u32),
+   Fixed(u32, u32),
+}
+impl TargetSize {
+   fn resolve(&self, width: u32, height: u32) -> (u32, u32) {
+      match *self {
+         TargetSize::Swapchain => (width, height),
+         TargetSize::Divided(n) => (width.div_ceil(n).max(1), height.div_ceil(n).max(1)),
+         TargetSize::Fixed(width, height) => (width, height),
+      }
+   }
+}
+
+
+/// a texture the graph owns for the frame, targets with the same description and
+/// no overlapping passes share one texture
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct TransientTexture {
+   pub format: TextureFormat,
+   pub size: TargetSize,
+   pub usage: TextureUsages,
+}
+impl TransientTexture {
+   pub fn new(format: TextureFormat, size: TargetSize) -> Self {
+      Self {
+         format,
+         size,
+         usage: TextureUsages::RENDER_ATTACHMENT | TextureUsages::TEXTURE_BINDING,
+      }
+   }
+
+   pub fn usage(mut self, usage: TextureUsages) -> Self {
+      self.usage |= usage;
+      self
+   }
+}
+
+
+/// what a pass gets when it runs
+pub struct PassContext<'r> {
+   pub device: &'r Device,
+   pub encoder: &'r mut CommandEncoder,
+   swapchain: &'r TextureView,
+   views: HashMap<&'static str, &'r TextureView>,
+}
+impl<'r> PassContext<'r> {
+   /// the swapchain or a transient texture, external resources are owned by whoever added the pass
+   pub fn view(&self, resource: Resource) -> Result<&'r TextureView, String> {
+      match resource {
+         Resource::Swapchain => Ok(self.swapchain),
+         Resource::Texture(name) => self.views.get(name)
+             .copied()
+             .ok_or_else(|| format!("`{name}` is not a transient texture of this graph")),
+         Resource::Buffer(name) => Err(format!("`{name}` is a buffer, not a texture")),
+      }
+   }
+}
+
+
+type PassFn<'a> = Box<dyn FnOnce(&mut PassContext<'_>) -> Result<(), String> + 'a>;
+
+struct Pass<'a> {
+   name: &'static str,
+   reads: Vec<Resource>,
+   writes: Vec<Resource>,
+   run: PassFn<'a>,
+}
+
+pub struct PassBuilder<'g, 'a> {
+   graph: &'g mut RenderGraph<'a>,
+   name: &'static str,
+   reads: Vec<Resource>,
+   writes: Vec<Resource>,
+}
+impl<'g, 'a> PassBuilder<'g, 'a> {
+   pub fn read(mut self, resource: Resource) -> Self {
+      self.reads.push(resource);
+      self
+   }
+
+   pub fn write(mut self, resource: Resource) -> Self {
+      self.writes.push(resource);
+      self
+   }
+
+   /// an error stops the graph, `execute` returns it
+   pub fn run(self, run: impl FnOnce(&mut PassContext<'_>) -> Result<(), String> + 'a) {
+      self.graph.passes.push(Pass {
+         name: self.name,
+         reads: self.reads,
+         writes: self.writes,
+         run: Box::new(run),
+      });
+   }
+}
+
+
+/// collects the passes of one frame and runs them in dependency order.
+/// for every resource, passes that only write it come first, then passes that read and write it
+/// in the order they were added, then passes that only read it.
+/// passes with nothing in common keep the order they were added in
+#[derive(Default)]
+pub struct RenderGraph<'a> {
+   passes: Vec<Pass<'a>>,
+   transients: BTreeMap<&'static str, TransientTexture>,
+}
+impl<'a> RenderGraph<'a> {
+   pub fn new() -> Self {
+      Self {
+         passes: vec![],
+         transients: BTreeMap::new(),
+      }
+   }
+
+   /// declares a texture the graph allocates for this frame
+   pub fn transient_texture(&mut self, name: &'static str, texture: TransientTexture) -> Resource {
+      self.transients.insert(name, texture);
+      Resource::Texture(name)
+   }
+
+   pub fn add_pass(&mut self, name: &'static str) -> PassBuilder<'_, 'a> {
+      PassBuilder { graph: self, name, reads: vec![], writes: vec![] }
+   }
+
+   /// pass names in the order they will run
+   pub fn pass_order(&self) -> Result<Vec<&'static str>, String> {
+      Ok(self.order()?.into_iter().map(|i| self.passes[i].name).collect())
+   }
+
+   /// records every pass into `encoder`. a cycle or an unwritten transient fails before anything is recorded,
+   /// a failing pass stops the ones after it, either way the encoder shouldn't be submitted
+   pub fn execute(self, setup: &Setup, encoder: &mut CommandEncoder, swapchain: &TextureView, targets: &mut RenderTargetPool) -> Result<(), String> {
+      let size = (setup.config.width, setup.config.height);
+      self.execute_at(&setup.device, encoder, swapchain, size, targets)
+   }
+
+   /// like `execute`, but `swapchain` is any view of `size` and transients are sized from that instead of the window
+   pub fn execute_at(
+      mut self,
+      device: &Device,
+      encoder: &mut CommandEncoder,
+      swapchain: &TextureView,
+      size: (u32, u32),
+      targets: &mut RenderTargetPool,
+   ) -> Result<(), String> {
+      let order = self.order()?;
+
+      // first and last position in `order` of every transient texture
+      let mut lifetimes: BTreeMap<&'static str, (usize, usize)> = BTreeMap::new();
+      for (position, &pass) in order.iter().enumerate() {
+         let pass = &self.passes[pass];
+         for resource in pass.reads.iter().chain(&pass.writes) {
+            if let Resource::Texture(name) = resource {
+               if self.transients.contains_key(name) {
+                  let lifetime = lifetimes.entry(name).or_insert((position, position));
+                  lifetime.1 = position;
+               }
+            }
+         }
+      }
+
+      let (width, height) = size;
+      let assigned = targets.assign(device, &self.transients, &lifetimes, width, height);
+
+      let mut passes: Vec<Option<Pass>> = self.passes.drain(..).map(Some).collect();
+      for i in order {
+         let pass = passes[i].take().unwrap();
+
+         let mut context = PassContext {
+            device,
+            encoder,
+            swapchain,
+            views: assigned.iter().map(|(name, &target)| (*name, &targets.targets[target].view)).collect(),
+         };
+
+         (pass.run)(&mut context).map_err(|err| format!("pass `{}`: {err}", pass.name))?;
+      }
+      Ok(())
+   }
+
+   fn order(&self) -> Result<Vec<usize>, String> {
+      let mut dependencies: Vec<Vec<usize>> = vec![vec![]; self.passes.len()];
+
+      let mut resources: Vec<Resource> = self.passes.iter()
+          .flat_map(|pass| pass.reads.iter().chain(&pass.writes).copied())
+          .collect();
+      resources.sort();
+      resources.dedup();
+
+      for resource in resources {
+         let (mut producers, mut modifiers, mut consumers) = (vec![], vec![], vec![]);
+         for (i, pass) in self.passes.iter().enumerate() {
+            match (pass.reads.contains(&resource), pass.writes.contains(&resource)) {
+               (false, true) => producers.push(i),
+               (true, true) => modifiers.push(i),
+               (true, false) => consumers.push(i),
+               (false, false) => {}
+            }
+         }
+
+         if producers.is_empty() && modifiers.is_empty() {
+            if let Resource::Texture(name) = resource {
+               if self.transients.contains_key(name) {
+                  return Err(format!("transient texture `{name}` is read but never written"));
+               }
+            }
+         }
+
+         let writers: Vec<usize> = producers.iter().chain(&modifiers).copied().collect();
+         for pair in writers.windows(2) {
+            dependencies[pair[1]].push(pair[0]);
+         }
+         if let Some(&last) = writers.last() {
+            for &consumer in &consumers {
+               dependencies[consumer].push(last);
+            }
+         }
+      }
+
+      // kahn's algorithm, always picking the earliest added pass that is ready
+      let mut remaining: Vec<usize> = dependencies.iter().map(|d| d.len()).collect();
+      let mut dependents: Vec<Vec<usize>> = vec![vec![]; self.passes.len()];
+      for (pass, deps) in dependencies.iter().enumerate() {
+         for &dep in deps {
+            dependents[dep].push(pass);
+         }
+      }
+
+      let mut ready: BinaryHeap<Reverse<usize>> = (0..self.passes.len())
+          .filter(|&i| remaining[i] == 0)
+          .map(Reverse)
+          .collect();
+      let mut order = Vec::with_capacity(self.passes.len());
+
+      while let Some(Reverse(pass)) = ready.pop() {
+         order.push(pass);
+         for &dependent in &dependents[pass] {
+            remaining[dependent] -= 1;
+            if remaining[dependent] == 0 { ready.push(Reverse(dependent)); }
+         }
+      }
+
+      if order.len() < self.passes.len() {
+         let stuck: Vec<&str> = (0..self.passes.len())
+             .filter(|i| !order.contains(i))
+             .map(|i| self.passes[i].name)
+             .collect();
+         return Err(format!("render graph has a dependency cycle between {}", stuck.join(", ")));
+      }
+
+      Ok(order)
+   }
+}
+
+
+struct PooledTarget {
+   texture: TransientTexture,
+   size: (u32, u32),
+   view: TextureView,
+   #[allow(dead_code)]
+   raw: Texture,
+   used_this_frame: bool,
+}
+
+/// keeps transient textures alive between frames so they are only recreated when their size changes
+#[derive(Default)]
+pub struct RenderTargetPool {
+   targets: Vec<PooledTarget>,
+}
+impl RenderTargetPool {
+   pub fn new() -> Self {
+      Self { targets: vec![] }
+   }
+
+   fn assign(
+      &mut self,
+      device: &Device,
+      transients: &BTreeMap<&'static str, TransientTexture>,
+      lifetimes: &BTreeMap<&'static str, (usize, usize)>,
+      width: u32,
+      height: u32,
+   ) -> HashMap<&'static str, usize> {
+      for target in &mut self.targets { target.used_this_frame = false; }
+
+      let mut by_first_use: Vec<(&'static str, (usize, usize))> = lifetimes.iter().map(|(n, l)| (*n, *l)).collect();
+      by_first_use.sort_by_key(|(_, (first, _))| *first);
+
+      // last pass each pooled target is busy until this frame
+      let mut busy_until: HashMap<usize, usize> = HashMap::new();
+      let mut assigned = HashMap::new();
+
+      for (name, (first, last)) in by_first_use {
+         let texture = transients[name];
+         let size = texture.size.resolve(width, height);
+
+         let free = self.targets.iter().enumerate().position(|(i, target)| {
+            target.texture == texture && target.size == size
+                && busy_until.get(&i).is_none_or(|&until| until < first)
+         });
+
+         let index = match free {
+            Some(index) => index,
+            None => {
+               self.targets.push(Self::create(device, name, texture, size));
+               self.targets.len() - 1
+            }
+         };
+
+         self.targets[index].used_this_frame = true;
+         busy_until.insert(index, last);
+         assigned.insert(name, index);
+      }
+
+      // anything not needed this frame, e.g. after a resize, is dropped
+      let mut remap = HashMap::new();
+      let mut kept = vec![];
+      for (old, target) in self.targets.drain(..).enumerate() {
+         if target.used_this_frame {
+            remap.insert(old, kept.len());
+            kept.push(target);
+         }
+      }
+      self.targets = kept;
+
+      assigned.into_iter().map(|(name, index)| (name, remap[&index])).collect()
+   }
+
+   fn create(device: &Device, label: &str, texture: TransientTexture, size: (u32, u32)) -> PooledTarget {
+      let raw = device.create_texture(&wgpu::TextureDescriptor {
+         label: Some(label),
+         size: wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+         mip_level_count: 1,
+         sample_count: 1,
+         dimension: wgpu::TextureDimension::D2,
+         format: texture.format,
+         usage: texture.usage,
+         view_formats: &[],
+      });
+      let view = raw.create_view(&wgpu::TextureViewDescriptor::default());
+
+      PooledTarget { texture, size, view, raw, used_this_frame: false }
+   }
+}
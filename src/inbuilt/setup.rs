@@ -8,6 +8,7 @@ pub struct Setup<'a> {
    pub queue: Queue,
    pub config: SurfaceConfiguration,
    pub size: PhysicalSize<u32>,
+   pub sample_count: u32,
    pub window: &'a Window,
 }
 
@@ -73,6 +74,7 @@ impl<'a> Setup<'a> {
          queue,
          config,
          size,
+         sample_count: 1,
          window,
          device,
       }
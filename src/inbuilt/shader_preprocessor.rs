@@ -0,0 +1,240 @@
+use std::collections::{BTreeMap, HashSet};
+use std::fmt::{Display, Formatter};
+use wgpu::{Device, ShaderModule, ShaderModuleDescriptor};
+
+
+/// shared shader code, looked up by the path used in `#include`
+pub const EMBEDDED_INCLUDES: &[(&str, &str)] = &[
+   ("common/camera.wgsl", include_str!("../shaders/common/camera.wgsl")),
+   ("common/automata_texture.wgsl", include_str!("../shaders/common/automata_texture.wgsl")),
+   ("common/fullscreen.wgsl", include_str!("../shaders/common/fullscreen.wgsl")),
+   ("common/globals.wgsl", include_str!("../shaders/common/globals.wgsl")),
+   ("common/model.wgsl", include_str!("../shaders/common/model.wgsl")),
+];
+
+pub fn embedded_include(path: &str) -> Option<String> {
+   EMBEDDED_INCLUDES.iter()
+       .find(|(name, _)| *name == path)
+       .map(|(_, source)| source.to_string())
+}
+
+
+/// `#define`s passed in from rust, a name with an empty value only works as an `#ifdef` flag
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ShaderDefines(BTreeMap<String, String>);
+impl ShaderDefines {
+   pub fn new() -> Self {
+      Self(BTreeMap::new())
+   }
+
+   pub fn with(mut self, name: &str, value: impl ToString) -> Self {
+      self.0.insert(name.to_string(), value.to_string());
+      self
+   }
+
+   pub fn flag(self, name: &str) -> Self {
+      self.with(name, "")
+   }
+}
+
+
+#[derive(Debug, PartialEq)]
+pub struct PreprocessError {
+   pub file: String,
+   pub line: usize,
+   pub message: String,
+}
+impl Display for PreprocessError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      write!(f, "{}:{}: {}", self.file, self.line, self.message)
+   }
+}
+impl std::error::Error for PreprocessError {}
+
+
+pub struct Preprocessed {
+   pub source: String,
+   /// every file pulled in through `#include`, in the order they were first seen
+   pub includes: Vec<String>,
+   /// for every line of `source`, the index into `files` and the line in that file it came from
+   lines: Vec<(usize, usize)>,
+   /// the top file, then `includes`
+   files: Vec<String>,
+}
+impl Preprocessed {
+   /// the file and 1-based line a 1-based line of `source` came from
+   pub fn origin(&self, line: usize) -> Option<(&str, usize)> {
+      let (file, line) = *self.lines.get(line.checked_sub(1)?)?;
+      Some((&self.files[file], line))
+   }
+}
+
+
+/// supports `#include "path"`, `#define NAME value`, `#ifdef`, `#ifndef`, `#else` and `#endif`.
+/// every file is only included once and defined names are replaced as whole identifiers.
+/// directive lines are kept as blank lines, but includes are inlined and shift everything after them,
+/// so map lines of the result back to the file they came from with `Preprocessed::origin`
+pub fn preprocess(
+   path: &str,
+   source: &str,
+   defines: &ShaderDefines,
+   resolve: &mut dyn FnMut(&str) -> Option<String>,
+) -> Result<Preprocessed, PreprocessError> {
+   let mut state = State {
+      defines: defines.0.clone(),
+      included: HashSet::from([path.to_string()]),
+      includes: vec![],
+      output: String::with_capacity(source.len()),
+      lines: vec![],
+   };
+
+   state.process(0, path, source, resolve)?;
+
+   let mut files = vec![path.to_string()];
+   files.extend(state.includes.iter().cloned());
+   Ok(Preprocessed {
+      source: state.output,
+      includes: state.includes,
+      lines: state.lines,
+      files,
+   })
+}
+
+/// preprocesses with the embedded includes and builds the module, for shaders that aren't hot reloaded
+pub fn embedded_module(device: &Device, path: &str, source: &str, defines: &ShaderDefines) -> Result<ShaderModule, PreprocessError> {
+   let processed = preprocess(path, source, defines, &mut embedded_include)?;
+
+   Ok(device.create_shader_module(ShaderModuleDescriptor {
+      label: Some(path),
+      source: wgpu::ShaderSource::Wgsl(processed.source.into()),
+   }))
+}
+
+
+struct State {
+   defines: BTreeMap<String, String>,
+   included: HashSet<String>,
+   includes: Vec<String>,
+   output: String,
+   lines: Vec<(usize, usize)>,
+}
+impl State {
+   /// `index` is where `file` is in the top file followed by `includes`
+   fn process(&mut self, index: usize, file: &str, source: &str, resolve: &mut dyn FnMut(&str) -> Option<String>) -> Result<(), PreprocessError> {
+      // one entry per open #if block: (this branch is active, an #else was seen)
+      let mut conditions: Vec<(bool, bool)> = vec![];
+      let error = |line: usize, message: String| PreprocessError { file: file.to_string(), line, message };
+
+      for (i, line) in source.lines().enumerate() {
+         let line_number = i + 1;
+         let trimmed = line.trim_start();
+         let active = conditions.iter().all(|(active, _)| *active);
+
+         if !trimmed.starts_with('#') {
+            if active { self.output.push_str(&self.substitute(line)); }
+            self.output.push('\n');
+            self.lines.push((index, line_number));
+            continue;
+         }
+
+         let mut parts = trimmed[1..].splitn(2, char::is_whitespace);
+         let directive = parts.next().unwrap_or("");
+         let argument = parts.next().unwrap_or("").trim();
+
+         match directive {
+            "ifdef" | "ifndef" => {
+               let name = single_name(argument).ok_or_else(|| error(line_number, format!("#{directive} needs a single name")))?;
+               let defined = self.defines.contains_key(name);
+               conditions.push((defined == (directive == "ifdef"), false));
+            }
+            "else" => {
+               match conditions.last_mut() {
+                  Some((_, true)) => return Err(error(line_number, "second #else in the same block".to_string())),
+                  Some((active, seen_else)) => { *active = !*active; *seen_else = true; }
+                  None => return Err(error(line_number, "#else without #ifdef".to_string())),
+               }
+            }
+            "endif" => {
+               if conditions.pop().is_none() {
+                  return Err(error(line_number, "#endif without #ifdef".to_string()));
+               }
+            }
+            "define" if active => {
+               let mut parts = argument.splitn(2, char::is_whitespace);
+               let name = parts.next().filter(|name| is_identifier(name))
+                   .ok_or_else(|| error(line_number, "#define needs a name".to_string()))?;
+               let value = self.substitute(parts.next().unwrap_or("").trim());
+               self.defines.insert(name.to_string(), value);
+            }
+            "include" if active => {
+               let include = argument.strip_prefix('"').and_then(|a| a.strip_suffix('"'))
+                   .ok_or_else(|| error(line_number, format!("#include expects a quoted path, found `{argument}`")))?;
+
+               if self.included.insert(include.to_string()) {
+                  let included_source = resolve(include)
+                      .ok_or_else(|| error(line_number, format!("could not find include `{include}`")))?;
+                  self.includes.push(include.to_string());
+                  self.process(self.includes.len(), include, &included_source, resolve)?;
+               }
+            }
+            "define" | "include" => {}
+            other => return Err(error(line_number, format!("unknown directive `#{other}`"))),
+         }
+
+         self.output.push('\n');
+         self.lines.push((index, line_number));
+      }
+
+      if !conditions.is_empty() {
+         return Err(error(source.lines().count(), "missing #endif".to_string()));
+      }
+
+      Ok(())
+   }
+
+   fn substitute(&self, line: &str) -> String {
+      let mut result = String::with_capacity(line.len());
+      let mut chars = line.char_indices().peekable();
+
+      while let Some((start, c)) = chars.next() {
+         if c.is_ascii_alphabetic() || c == '_' {
+            let mut end = start + c.len_utf8();
+            while let Some(&(i, next)) = chars.peek() {
+               if !(next.is_ascii_alphanumeric() || next == '_') { break; }
+               end = i + next.len_utf8();
+               chars.next();
+            }
+
+            let word = &line[start..end];
+            match self.defines.get(word) {
+               Some(value) if !value.is_empty() => result.push_str(value),
+               _ => result.push_str(word),
+            }
+         }
+         else if c.is_ascii_digit() {
+            // keep literals like 1u or 2e3 intact
+            result.push(c);
+            while let Some(&(_, next)) = chars.peek() {
+               if !(next.is_ascii_alphanumeric() || next == '_' || next == '.') { break; }
+               result.push(next);
+               chars.next();
+            }
+         }
+         else {
+            result.push(c);
+         }
+      }
+
+      result
+   }
+}
+
+fn single_name(argument: &str) -> Option<&str> {
+   Some(argument).filter(|a| is_identifier(a))
+}
+
+fn is_identifier(name: &str) -> bool {
+   let mut chars = name.chars();
+   matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+       && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
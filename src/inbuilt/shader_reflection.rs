@@ -0,0 +1,355 @@
+use naga::{AddressSpace, Expression, GlobalVariable, Handle, ImageClass, ImageDimension, ScalarKind, ShaderStage, StorageAccess, StorageFormat, TypeInner};
+use naga::valid::{Capabilities, GlobalUse, ModuleInfo, ValidationFlags, Validator};
+use wgpu::{BindGroup, BindGroupLayout, BindGroupLayoutEntry, BindingResource, BindingType, Buffer, Device, PipelineLayout, Sampler, TextureView, BufferBindingType, BufferSize, SamplerBindingType, ShaderStages, StorageTextureAccess, TextureFormat, TextureSampleType, TextureViewDimension};
+
+
+/// a validated naga module plus the analysis needed to know which entry points use which bindings.
+/// float textures are filterable when the shader samples them, `non_filterable` overrides that
+pub struct ReflectedShader {
+   pub module: naga::Module,
+   pub info: ModuleInfo,
+   non_filterable: Vec<String>,
+}
+
+/// one `@group(g) @binding(b)` global, `entry.visibility` is every stage that touches it
+#[derive(Clone, Debug)]
+pub struct ReflectedBinding {
+   pub group: u32,
+   pub name: String,
+   pub entry: BindGroupLayoutEntry,
+}
+
+
+impl ReflectedShader {
+   /// `source` must already be preprocessed
+   pub fn from_wgsl(source: &str, path: &str) -> Result<Self, String> {
+      let module = naga::front::wgsl::parse_str(source)
+          .map_err(|err| err.emit_to_string_with_path(source, path))?;
+
+      let info = Validator::new(ValidationFlags::all(), Capabilities::all())
+          .validate(&module)
+          .map_err(|err| err.emit_to_string_with_path(source, path))?;
+
+      Ok(Self { module, info, non_filterable: vec![] })
+   }
+
+   /// for float textures that can't be filtered but are still sampled, like R32Float.
+   /// the texture becomes unfilterable and the samplers used on it non filtering
+   pub fn non_filterable(mut self, texture: &str) -> Self {
+      self.non_filterable.push(texture.to_string());
+      self
+   }
+
+   /// sorted by group then binding
+   pub fn bindings(&self) -> Vec<ReflectedBinding> {
+      let mut bindings = vec![];
+
+      for (handle, global) in self.module.global_variables.iter() {
+         let Some(resource) = &global.binding else { continue };
+
+         let mut visibility = ShaderStages::NONE;
+         for (i, entry_point) in self.module.entry_points.iter().enumerate() {
+            if self.info.get_entry_point(i)[handle] != GlobalUse::empty() {
+               visibility |= match entry_point.stage {
+                  ShaderStage::Vertex => ShaderStages::VERTEX,
+                  ShaderStage::Fragment => ShaderStages::FRAGMENT,
+                  ShaderStage::Compute => ShaderStages::COMPUTE,
+               };
+            }
+         }
+
+         let Some(ty) = self.binding_type(handle, global) else { continue };
+
+         bindings.push(ReflectedBinding {
+            group: resource.group,
+            name: global.name.clone().unwrap_or_default(),
+            entry: BindGroupLayoutEntry {
+               binding: resource.binding,
+               visibility,
+               ty,
+               count: None,
+            },
+         });
+      }
+
+      bindings.sort_by_key(|b| (b.group, b.entry.binding));
+      bindings
+   }
+
+   pub fn layout_entries(&self, group: u32) -> Vec<BindGroupLayoutEntry> {
+      self.bindings().into_iter()
+          .filter(|b| b.group == group)
+          .map(|b| b.entry)
+          .collect()
+   }
+
+   /// one past the highest group index used
+   pub fn group_count(&self) -> u32 {
+      self.bindings().iter().map(|b| b.group + 1).max().unwrap_or(0)
+   }
+
+   /// `@workgroup_size` of a compute entry point, after the preprocessor filled in any defines
+   pub fn workgroup_size(&self, entry_point: &str) -> Option<[u32; 3]> {
+      self.module.entry_points.iter()
+          .find(|entry| entry.name == entry_point && entry.stage == naga::ShaderStage::Compute)
+          .map(|entry| entry.workgroup_size)
+   }
+
+   /// layout for one group, with visibility limited to the stages that use each binding
+   pub fn bind_group_layout(&self, device: &Device, group: u32, label: Option<&str>) -> BindGroupLayout {
+      device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+         label,
+         entries: &self.layout_entries(group),
+      })
+   }
+
+   /// starts a bind group for `group`, `layout` must have been made from this shader
+   pub fn bind_group<'a>(&self, group: u32, layout: &'a BindGroupLayout) -> BindGroupBuilder<'a> {
+      BindGroupBuilder {
+         label: None,
+         layout,
+         group,
+         bindings: self.bindings().into_iter().filter(|b| b.group == group).collect(),
+         entries: vec![],
+         error: None,
+      }
+   }
+
+   /// what `BindGroupBuilder::build` checks, for pipelines that only get their resources later:
+   /// every name is a binding in `group` and every binding in `group` is named
+   pub fn check_bindings(&self, group: u32, names: &[&str]) -> Result<(), String> {
+      let bindings: Vec<ReflectedBinding> = self.bindings().into_iter().filter(|b| b.group == group).collect();
+
+      if let Some(name) = names.iter().find(|name| !bindings.iter().any(|b| b.name == **name)) {
+         return Err(format!("no binding named `{name}` in group {group}"));
+      }
+      match bindings.iter().find(|b| !names.contains(&b.name.as_str())) {
+         Some(binding) => Err(format!("bind group {group} is missing `{}` at binding {}", binding.name, binding.entry.binding)),
+         None => Ok(()),
+      }
+   }
+
+   /// every `(texture, sampler)` pair passed to a `textureSample*` call, as long as both are globals
+   fn sampled_pairs(&self) -> Vec<(Handle<GlobalVariable>, Handle<GlobalVariable>)> {
+      let functions = self.module.functions.iter().map(|(_, function)| function)
+          .chain(self.module.entry_points.iter().map(|entry| &entry.function));
+
+      let mut pairs = vec![];
+      for function in functions {
+         for (_, expression) in function.expressions.iter() {
+            let Expression::ImageSample { image, sampler, .. } = expression else { continue };
+
+            if let (Expression::GlobalVariable(image), Expression::GlobalVariable(sampler)) = (&function.expressions[*image], &function.expressions[*sampler]) {
+               pairs.push((*image, *sampler));
+            }
+         }
+      }
+      pairs
+   }
+
+   fn is_non_filterable(&self, handle: Handle<GlobalVariable>) -> bool {
+      self.module.global_variables[handle].name.as_ref().is_some_and(|name| self.non_filterable.contains(name))
+   }
+
+   fn binding_type(&self, handle: Handle<GlobalVariable>, global: &GlobalVariable) -> Option<BindingType> {
+      let inner = &self.module.types[global.ty].inner;
+
+      match global.space {
+         AddressSpace::Uniform => Some(BindingType::Buffer {
+            ty: BufferBindingType::Uniform,
+            has_dynamic_offset: false,
+            min_binding_size: BufferSize::new(inner.size(self.module.to_ctx()) as u64),
+         }),
+         AddressSpace::Storage { access } => Some(BindingType::Buffer {
+            ty: BufferBindingType::Storage { read_only: !access.contains(StorageAccess::STORE) },
+            has_dynamic_offset: false,
+            min_binding_size: BufferSize::new(inner.size(self.module.to_ctx()) as u64),
+         }),
+         AddressSpace::Handle => match inner {
+            TypeInner::Sampler { comparison: true } => Some(BindingType::Sampler(SamplerBindingType::Comparison)),
+            TypeInner::Sampler { comparison: false } => {
+               // filtering unless everything it samples was marked non filterable
+               let mut sampled = self.sampled_pairs().into_iter().filter(|(_, sampler)| *sampler == handle).peekable();
+               let non_filtering = sampled.peek().is_some() && sampled.all(|(image, _)| self.is_non_filterable(image));
+
+               Some(BindingType::Sampler(if non_filtering { SamplerBindingType::NonFiltering } else { SamplerBindingType::Filtering }))
+            }
+            TypeInner::Image { dim, arrayed, class } => {
+               let view_dimension = view_dimension(*dim, *arrayed);
+
+               Some(match class {
+                  ImageClass::Sampled { kind, multi } => BindingType::Texture {
+                     sample_type: match kind {
+                        ScalarKind::Sint => TextureSampleType::Sint,
+                        ScalarKind::Uint => TextureSampleType::Uint,
+                        _ => TextureSampleType::Float {
+                           // only read with `textureLoad`, so any float format can be bound
+                           filterable: !self.is_non_filterable(handle) && self.sampled_pairs().iter().any(|(image, _)| *image == handle),
+                        },
+                     },
+                     view_dimension,
+                     multisampled: *multi,
+                  },
+                  ImageClass::Depth { multi } => BindingType::Texture {
+                     sample_type: TextureSampleType::Depth,
+                     view_dimension,
+                     multisampled: *multi,
+                  },
+                  ImageClass::Storage { format, access } => BindingType::StorageTexture {
+                     access: match (access.contains(StorageAccess::LOAD), access.contains(StorageAccess::STORE)) {
+                        (true, true) => StorageTextureAccess::ReadWrite,
+                        (true, false) => StorageTextureAccess::ReadOnly,
+                        _ => StorageTextureAccess::WriteOnly,
+                     },
+                     format: texture_format(*format),
+                     view_dimension,
+                  },
+               })
+            }
+            _ => None,
+         },
+         _ => None,
+      }
+   }
+}
+
+
+/// every bind group layout of a shader plus the pipeline layout made from them,
+/// for pipelines that don't share bind groups with other shaders
+pub struct ShaderLayout {
+   pub bind_group_layouts: Vec<BindGroupLayout>,
+   pub pipeline_layout: PipelineLayout,
+}
+impl ShaderLayout {
+   pub fn new(device: &Device, shader: &ReflectedShader, label: &str) -> Self {
+      let bind_group_layouts: Vec<BindGroupLayout> = (0..shader.group_count())
+          .map(|group| shader.bind_group_layout(device, group, Some(label)))
+          .collect();
+
+      let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+         label: Some(label),
+         bind_group_layouts: &bind_group_layouts.iter().collect::<Vec<_>>(),
+         push_constant_ranges: &[],
+      });
+
+      Self { bind_group_layouts, pipeline_layout }
+   }
+}
+
+
+/// fills a bind group by the names the shader gives its bindings. a name that doesn't exist, a resource
+/// of the wrong kind or a binding left out is returned from `build`
+pub struct BindGroupBuilder<'a> {
+   label: Option<&'a str>,
+   layout: &'a BindGroupLayout,
+   group: u32,
+   bindings: Vec<ReflectedBinding>,
+   entries: Vec<wgpu::BindGroupEntry<'a>>,
+   /// the first problem, reported by `build`
+   error: Option<String>,
+}
+impl<'a> BindGroupBuilder<'a> {
+   pub fn label(mut self, label: &'a str) -> Self {
+      self.label = Some(label);
+      self
+   }
+
+   pub fn buffer(self, name: &str, buffer: &'a Buffer) -> Self {
+      self.resource(name, "buffer", buffer.as_entire_binding(), |ty| matches!(ty, BindingType::Buffer { .. }))
+   }
+
+   pub fn texture_view(self, name: &str, view: &'a TextureView) -> Self {
+      self.resource(name, "texture view", BindingResource::TextureView(view), |ty| {
+         matches!(ty, BindingType::Texture { .. } | BindingType::StorageTexture { .. })
+      })
+   }
+
+   pub fn sampler(self, name: &str, sampler: &'a Sampler) -> Self {
+      self.resource(name, "sampler", BindingResource::Sampler(sampler), |ty| matches!(ty, BindingType::Sampler(_)))
+   }
+
+   pub fn build(self, device: &Device) -> Result<BindGroup, String> {
+      if let Some(error) = self.error {
+         return Err(error);
+      }
+      if let Some(binding) = self.bindings.iter().find(|b| !self.entries.iter().any(|entry| entry.binding == b.entry.binding)) {
+         return Err(format!("bind group {} is missing `{}` at binding {}", self.group, binding.name, binding.entry.binding));
+      }
+
+      Ok(device.create_bind_group(&wgpu::BindGroupDescriptor {
+         label: self.label,
+         layout: self.layout,
+         entries: &self.entries,
+      }))
+   }
+
+   fn resource(mut self, name: &str, kind: &str, resource: BindingResource<'a>, accepts: impl Fn(&BindingType) -> bool) -> Self {
+      if self.error.is_some() { return self; }
+
+      match self.bindings.iter().find(|b| b.name == name) {
+         None => self.error = Some(format!("no binding named `{name}` in group {}", self.group)),
+         Some(binding) if !accepts(&binding.entry.ty) => {
+            self.error = Some(format!("`{name}` is {:?}, a {kind} can't be bound to it", binding.entry.ty));
+         }
+         Some(binding) => self.entries.push(wgpu::BindGroupEntry { binding: binding.entry.binding, resource }),
+      }
+      self
+   }
+}
+
+
+fn view_dimension(dim: ImageDimension, arrayed: bool) -> TextureViewDimension {
+   match (dim, arrayed) {
+      (ImageDimension::D1, _) => TextureViewDimension::D1,
+      (ImageDimension::D2, false) => TextureViewDimension::D2,
+      (ImageDimension::D2, true) => TextureViewDimension::D2Array,
+      (ImageDimension::D3, _) => TextureViewDimension::D3,
+      (ImageDimension::Cube, false) => TextureViewDimension::Cube,
+      (ImageDimension::Cube, true) => TextureViewDimension::CubeArray,
+   }
+}
+
+fn texture_format(format: StorageFormat) -> TextureFormat {
+   match format {
+      StorageFormat::R8Unorm => TextureFormat::R8Unorm,
+      StorageFormat::R8Snorm => TextureFormat::R8Snorm,
+      StorageFormat::R8Uint => TextureFormat::R8Uint,
+      StorageFormat::R8Sint => TextureFormat::R8Sint,
+      StorageFormat::R16Uint => TextureFormat::R16Uint,
+      StorageFormat::R16Sint => TextureFormat::R16Sint,
+      StorageFormat::R16Float => TextureFormat::R16Float,
+      StorageFormat::Rg8Unorm => TextureFormat::Rg8Unorm,
+      StorageFormat::Rg8Snorm => TextureFormat::Rg8Snorm,
+      StorageFormat::Rg8Uint => TextureFormat::Rg8Uint,
+      StorageFormat::Rg8Sint => TextureFormat::Rg8Sint,
+      StorageFormat::R32Uint => TextureFormat::R32Uint,
+      StorageFormat::R32Sint => TextureFormat::R32Sint,
+      StorageFormat::R32Float => TextureFormat::R32Float,
+      StorageFormat::Rg16Uint => TextureFormat::Rg16Uint,
+      StorageFormat::Rg16Sint => TextureFormat::Rg16Sint,
+      StorageFormat::Rg16Float => TextureFormat::Rg16Float,
+      StorageFormat::Rgba8Unorm => TextureFormat::Rgba8Unorm,
+      StorageFormat::Rgba8Snorm => TextureFormat::Rgba8Snorm,
+      StorageFormat::Rgba8Uint => TextureFormat::Rgba8Uint,
+      StorageFormat::Rgba8Sint => TextureFormat::Rgba8Sint,
+      StorageFormat::Bgra8Unorm => TextureFormat::Bgra8Unorm,
+      StorageFormat::Rgb10a2Uint => TextureFormat::Rgb10a2Uint,
+      StorageFormat::Rgb10a2Unorm => TextureFormat::Rgb10a2Unorm,
+      StorageFormat::Rg11b10Float => TextureFormat::Rg11b10Float,
+      StorageFormat::Rg32Uint => TextureFormat::Rg32Uint,
+      StorageFormat::Rg32Sint => TextureFormat::Rg32Sint,
+      StorageFormat::Rg32Float => TextureFormat::Rg32Float,
+      StorageFormat::Rgba16Uint => TextureFormat::Rgba16Uint,
+      StorageFormat::Rgba16Sint => TextureFormat::Rgba16Sint,
+      StorageFormat::Rgba16Float => TextureFormat::Rgba16Float,
+      StorageFormat::Rgba32Uint => TextureFormat::Rgba32Uint,
+      StorageFormat::Rgba32Sint => TextureFormat::Rgba32Sint,
+      StorageFormat::Rgba32Float => TextureFormat::Rgba32Float,
+      StorageFormat::R16Unorm => TextureFormat::R16Unorm,
+      StorageFormat::R16Snorm => TextureFormat::R16Snorm,
+      StorageFormat::Rg16Unorm => TextureFormat::Rg16Unorm,
+      StorageFormat::Rg16Snorm => TextureFormat::Rg16Snorm,
+      StorageFormat::Rgba16Unorm => TextureFormat::Rgba16Unorm,
+      StorageFormat::Rgba16Snorm => TextureFormat::Rgba16Snorm,
+   }
+}
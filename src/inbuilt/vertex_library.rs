@@ -1,4 +1,6 @@
-use crate::inbuilt::vertex_package::Vertex;
+use std::collections::HashMap;
+use std::f32::consts::{PI, TAU};
+use crate::inbuilt::vertex_package::{LitVertex, TexturedVertex, Vertex};
 
 pub const SQUARE_VERTICES: &[Vertex] = &[
    Vertex { position: [1.0, 1.0, 0.0] }, // Vertex 0: top-right
@@ -11,6 +13,13 @@ pub const SQUARE_INDICES: &[u16] = &[
    3, 1, 0,
 ];
 
+pub const TEXTURED_SQUARE_VERTICES: &[TexturedVertex] = &[
+   TexturedVertex { position: [1.0, 1.0, 0.0], uv: [1.0, 1.0] }, // Vertex 0: top-right
+   TexturedVertex { position: [-1.0, -1.0, 0.0], uv: [0.0, 0.0] }, // Vertex 1: bottom-left
+   TexturedVertex { position: [1.0, -1.0, 0.0], uv: [1.0, 0.0] }, // Vertex 2: bottom-right
+   TexturedVertex { position: [-1.0, 1.0, 0.0], uv: [0.0, 1.0] }, // Vertex 3: top-left
+];
+
 pub const CUBE_VERTICES: &[Vertex] = &[
    Vertex { position: [-1.0, -1.0, -1.0] }, // Vertex 0: bottom-left-back
    Vertex { position: [1.0, -1.0, -1.0] },  // Vertex 1: bottom-right-back
@@ -41,4 +50,253 @@ pub const CUBE_INDICES: &[u16] = &[
    // Top face
    3, 7, 6,
    3, 6, 2,
-];
\ No newline at end of file
+];
+
+
+// procedural meshes, every triangle is counter clockwise when seen from the side its normal points to
+
+/// sphere around the origin, `sectors` slices around the y axis and `stacks` rings from pole to pole
+pub fn uv_sphere(radius: f32, sectors: u32, stacks: u32) -> (Vec<LitVertex>, Vec<u32>) {
+   let sectors = sectors.max(3);
+   let stacks = stacks.max(2);
+
+   let mut vertices = vec![];
+   for i in 0..=stacks {
+      let phi = PI * i as f32 / stacks as f32;
+      let (ring, y) = (phi.sin(), phi.cos());
+
+      for j in 0..=sectors {
+         let theta = TAU * j as f32 / sectors as f32;
+         let normal = [ring * theta.cos(), y, -ring * theta.sin()];
+
+         vertices.push(LitVertex {
+            position: scale(normal, radius),
+            normal,
+            uv: [j as f32 / sectors as f32, 1.0 - i as f32 / stacks as f32],
+         });
+      }
+   }
+
+   let mut indices = vec![];
+   for i in 0..stacks {
+      for j in 0..sectors {
+         let k1 = i * (sectors + 1) + j;
+         let k2 = k1 + sectors + 1;
+
+         // the first and last stack meet at a pole and only need one triangle per sector
+         if i != 0 { indices.extend_from_slice(&[k1, k2, k1 + 1]); }
+         if i != stacks - 1 { indices.extend_from_slice(&[k1 + 1, k2, k2 + 1]); }
+      }
+   }
+
+   (vertices, indices)
+}
+
+/// subdivided icosahedron, every subdivision quadruples the 20 base triangles
+pub fn icosphere(radius: f32, subdivisions: u32) -> (Vec<LitVertex>, Vec<u32>) {
+   let t = (1.0 + 5f32.sqrt()) / 2.0;
+
+   let mut positions: Vec<[f32; 3]> = [
+      [-1.0, t, 0.0], [1.0, t, 0.0], [-1.0, -t, 0.0], [1.0, -t, 0.0],
+      [0.0, -1.0, t], [0.0, 1.0, t], [0.0, -1.0, -t], [0.0, 1.0, -t],
+      [t, 0.0, -1.0], [t, 0.0, 1.0], [-t, 0.0, -1.0], [-t, 0.0, 1.0],
+   ].into_iter().map(normalize).collect();
+
+   let mut indices: Vec<u32> = vec![
+      0, 11, 5, 0, 5, 1, 0, 1, 7, 0, 7, 10, 0, 10, 11,
+      1, 5, 9, 5, 11, 4, 11, 10, 2, 10, 7, 6, 7, 1, 8,
+      3, 9, 4, 3, 4, 2, 3, 2, 6, 3, 6, 8, 3, 8, 9,
+      4, 9, 5, 2, 4, 11, 6, 2, 10, 8, 6, 7, 9, 8, 1,
+   ];
+
+   for _ in 0..subdivisions {
+      let mut midpoints: HashMap<(u32, u32), u32> = HashMap::new();
+      let mut midpoint = |a: u32, b: u32, positions: &mut Vec<[f32; 3]>| -> u32 {
+         let key = (a.min(b), a.max(b));
+         *midpoints.entry(key).or_insert_with(|| {
+            let (pa, pb) = (positions[a as usize], positions[b as usize]);
+            positions.push(normalize([(pa[0] + pb[0]) / 2.0, (pa[1] + pb[1]) / 2.0, (pa[2] + pb[2]) / 2.0]));
+            positions.len() as u32 - 1
+         })
+      };
+
+      let mut subdivided = Vec::with_capacity(indices.len() * 4);
+      for tri in indices.chunks(3) {
+         let (a, b, c) = (tri[0], tri[1], tri[2]);
+         let ab = midpoint(a, b, &mut positions);
+         let bc = midpoint(b, c, &mut positions);
+         let ca = midpoint(c, a, &mut positions);
+
+         subdivided.extend_from_slice(&[a, ab, ca, b, bc, ab, c, ca, bc, ab, bc, ca]);
+      }
+      indices = subdivided;
+   }
+
+   let vertices = positions.into_iter()
+       .map(|normal| LitVertex {
+          position: scale(normal, radius),
+          normal,
+          uv: [0.5 - normal[2].atan2(normal[0]) / TAU, 0.5 + normal[1].asin() / PI],
+       })
+       .collect();
+
+   (vertices, indices)
+}
+
+/// flat grid on the xy plane facing +z, split into `columns` x `rows` quads
+pub fn plane(width: f32, height: f32, columns: u32, rows: u32) -> (Vec<LitVertex>, Vec<u32>) {
+   let columns = columns.max(1);
+   let rows = rows.max(1);
+
+   let mut vertices = vec![];
+   for r in 0..=rows {
+      for c in 0..=columns {
+         let u = c as f32 / columns as f32;
+         let v = r as f32 / rows as f32;
+
+         vertices.push(LitVertex {
+            position: [(u - 0.5) * width, (v - 0.5) * height, 0.0],
+            normal: [0.0, 0.0, 1.0],
+            uv: [u, v],
+         });
+      }
+   }
+
+   let mut indices = vec![];
+   for r in 0..rows {
+      for c in 0..columns {
+         let bottom_left = r * (columns + 1) + c;
+         let bottom_right = bottom_left + 1;
+         let top_left = bottom_left + columns + 1;
+         let top_right = top_left + 1;
+
+         indices.extend_from_slice(&[bottom_left, bottom_right, top_right, bottom_left, top_right, top_left]);
+      }
+   }
+
+   (vertices, indices)
+}
+
+/// filled disc on the xy plane facing +z
+pub fn circle(radius: f32, segments: u32) -> (Vec<LitVertex>, Vec<u32>) {
+   let segments = segments.max(3);
+
+   let mut vertices = vec![LitVertex { position: [0.0, 0.0, 0.0], normal: [0.0, 0.0, 1.0], uv: [0.5, 0.5] }];
+   for j in 0..segments {
+      let theta = TAU * j as f32 / segments as f32;
+      let (x, y) = (theta.cos(), theta.sin());
+
+      vertices.push(LitVertex {
+         position: [x * radius, y * radius, 0.0],
+         normal: [0.0, 0.0, 1.0],
+         uv: [x * 0.5 + 0.5, y * 0.5 + 0.5],
+      });
+   }
+
+   let mut indices = vec![];
+   for j in 0..segments {
+      indices.extend_from_slice(&[0, 1 + j, 1 + (j + 1) % segments]);
+   }
+
+   (vertices, indices)
+}
+
+/// capped cylinder along the y axis, centered on the origin
+pub fn cylinder(radius: f32, height: f32, segments: u32) -> (Vec<LitVertex>, Vec<u32>) {
+   let segments = segments.max(3);
+   let half = height / 2.0;
+
+   let mut vertices = vec![];
+   let mut indices = vec![];
+
+   // side, the seam column is duplicated so the uvs wrap cleanly
+   for j in 0..=segments {
+      let u = j as f32 / segments as f32;
+      let theta = TAU * u;
+      let normal = [theta.cos(), 0.0, -theta.sin()];
+
+      vertices.push(LitVertex { position: [normal[0] * radius, -half, normal[2] * radius], normal, uv: [u, 0.0] });
+      vertices.push(LitVertex { position: [normal[0] * radius, half, normal[2] * radius], normal, uv: [u, 1.0] });
+   }
+   for j in 0..segments {
+      let bottom = j * 2;
+      let top = bottom + 1;
+
+      indices.extend_from_slice(&[bottom, bottom + 2, top + 2, bottom, top + 2, top]);
+   }
+
+   // caps
+   for (y, normal_y) in [(half, 1.0), (-half, -1.0)] {
+      let center = vertices.len() as u32;
+      vertices.push(LitVertex { position: [0.0, y, 0.0], normal: [0.0, normal_y, 0.0], uv: [0.5, 0.5] });
+
+      for j in 0..segments {
+         let theta = TAU * j as f32 / segments as f32;
+         let (x, z) = (theta.cos(), -theta.sin());
+
+         vertices.push(LitVertex {
+            position: [x * radius, y, z * radius],
+            normal: [0.0, normal_y, 0.0],
+            uv: [x * 0.5 + 0.5, z * 0.5 + 0.5],
+         });
+      }
+
+      for j in 0..segments {
+         let a = center + 1 + j;
+         let b = center + 1 + (j + 1) % segments;
+
+         if normal_y > 0.0 { indices.extend_from_slice(&[center, a, b]); }
+         else { indices.extend_from_slice(&[center, b, a]); }
+      }
+   }
+
+   (vertices, indices)
+}
+
+/// ring around the y axis, `major_radius` to the center of the tube and `minor_radius` for the tube itself
+pub fn torus(major_radius: f32, minor_radius: f32, major_segments: u32, minor_segments: u32) -> (Vec<LitVertex>, Vec<u32>) {
+   let major_segments = major_segments.max(3);
+   let minor_segments = minor_segments.max(3);
+
+   let mut vertices = vec![];
+   for i in 0..=major_segments {
+      let theta = TAU * i as f32 / major_segments as f32;
+      let (cos_theta, sin_theta) = (theta.cos(), theta.sin());
+
+      for j in 0..=minor_segments {
+         let phi = TAU * j as f32 / minor_segments as f32;
+         let normal = [phi.cos() * cos_theta, phi.sin(), -phi.cos() * sin_theta];
+
+         vertices.push(LitVertex {
+            position: [
+               major_radius * cos_theta + minor_radius * normal[0],
+               minor_radius * normal[1],
+               -major_radius * sin_theta + minor_radius * normal[2],
+            ],
+            normal,
+            uv: [i as f32 / major_segments as f32, j as f32 / minor_segments as f32],
+         });
+      }
+   }
+
+   let mut indices = vec![];
+   for i in 0..major_segments {
+      for j in 0..minor_segments {
+         let a = i * (minor_segments + 1) + j;
+         let b = a + minor_segments + 1;
+
+         indices.extend_from_slice(&[a, b, b + 1, a, b + 1, a + 1]);
+      }
+   }
+
+   (vertices, indices)
+}
+
+fn scale(v: [f32; 3], s: f32) -> [f32; 3] {
+   [v[0] * s, v[1] * s, v[2] * s]
+}
+
+fn normalize(v: [f32; 3]) -> [f32; 3] {
+   let length = (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+   scale(v, 1.0 / length)
+}
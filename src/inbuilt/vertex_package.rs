@@ -1,62 +1,176 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
 use bytemuck::{Pod, Zeroable};
-use wgpu::{Buffer, Device};
+use wgpu::{Buffer, BufferUsages, COPY_BUFFER_ALIGNMENT, Device, IndexFormat, Queue, VertexAttribute};
 use wgpu::util::DeviceExt;
+use crate::utility::functions::{padded_len, write_or_grow_buffer};
 
 
-#[repr(C)]
-#[derive(Copy, Clone, Debug, Pod, Zeroable)]
-pub struct Vertex {
-   pub position: [f32; 3],
-}
-impl Vertex {
-   pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+/// implemented by any vertex type that can be put in a `VertexPackage`
+pub trait VertexLayout: Pod + Zeroable {
+   const ATTRIBUTES: &'static [VertexAttribute];
+
+   fn desc() -> wgpu::VertexBufferLayout<'static> {
       wgpu::VertexBufferLayout {
-         array_stride: std::mem::size_of::<Vertex>() as wgpu::BufferAddress,
+         array_stride: std::mem::size_of::<Self>() as wgpu::BufferAddress,
          step_mode: wgpu::VertexStepMode::Vertex,
-         attributes: &[
-            wgpu::VertexAttribute {
-               offset: 0,
-               shader_location: 0,
-               format: wgpu::VertexFormat::Float32x3,
-            },
-         ]
+         attributes: Self::ATTRIBUTES,
       }
    }
 }
 
 
-pub struct VertexPackage {
+/// @location(0) position
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct Vertex {
+   pub position: [f32; 3],
+}
+impl VertexLayout for Vertex {
+   const ATTRIBUTES: &'static [VertexAttribute] = &wgpu::vertex_attr_array![
+      0 => Float32x3,
+   ];
+}
+
+
+/// @location(0) position | @location(1) uv
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct TexturedVertex {
+   pub position: [f32; 3],
+   pub uv: [f32; 2],
+}
+impl VertexLayout for TexturedVertex {
+   const ATTRIBUTES: &'static [VertexAttribute] = &wgpu::vertex_attr_array![
+      0 => Float32x3,
+      1 => Float32x2,
+   ];
+}
+
+
+/// @location(0) position | @location(1) color
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct ColoredVertex {
+   pub position: [f32; 3],
+   pub color: [f32; 4],
+}
+impl VertexLayout for ColoredVertex {
+   const ATTRIBUTES: &'static [VertexAttribute] = &wgpu::vertex_attr_array![
+      0 => Float32x3,
+      1 => Float32x4,
+   ];
+}
+
+
+/// @location(0) position | @location(1) normal | @location(2) uv
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq, Pod, Zeroable)]
+pub struct LitVertex {
+   pub position: [f32; 3],
+   pub normal: [f32; 3],
+   pub uv: [f32; 2],
+}
+impl VertexLayout for LitVertex {
+   const ATTRIBUTES: &'static [VertexAttribute] = &wgpu::vertex_attr_array![
+      0 => Float32x3,
+      1 => Float32x3,
+      2 => Float32x2,
+   ];
+}
+
+
+/// implemented by the index types wgpu can draw with
+pub trait IndexLayout: Pod + Zeroable {
+   const FORMAT: IndexFormat;
+}
+impl IndexLayout for u16 {
+   const FORMAT: IndexFormat = IndexFormat::Uint16;
+}
+impl IndexLayout for u32 {
+   const FORMAT: IndexFormat = IndexFormat::Uint32;
+}
+
+
+pub struct VertexPackage<V: VertexLayout = Vertex> {
    pub vertex_buffer: Buffer,
    pub index_buffer: Buffer,
+   pub index_format: IndexFormat,
    pub num_indices: u32,
    pub num_vertices: u32,
+   vertex_type: PhantomData<V>,
 }
-impl VertexPackage {
-   pub fn new(device: &Device, vertices: &[Vertex], indices: &[u16]) -> Self {
-      let vertex_buffer = device.create_buffer_init(
-         &wgpu::util::BufferInitDescriptor {
-            label: Some("Vertex Buffer"),
-            contents: bytemuck::cast_slice(vertices),
-            usage: wgpu::BufferUsages::VERTEX,
-         }
-      );
-      let num_vertices = vertices.len() as u32;
-
-      let index_buffer = device.create_buffer_init(
-         &wgpu::util::BufferInitDescriptor {
-            label: Some("Index Buffer"),
-            contents: bytemuck::cast_slice(indices),
-            usage: wgpu::BufferUsages::INDEX,
-         }
-      );
-      let num_indices = indices.len() as u32;
+impl<V: VertexLayout> VertexPackage<V> {
+   pub fn new<I: IndexLayout>(device: &Device, vertices: &[V], indices: &[I]) -> Self {
+      Self::create(device, vertices, indices, false)
+   }
 
+   fn create<I: IndexLayout>(device: &Device, vertices: &[V], indices: &[I], dynamic: bool) -> Self {
+      let vertex_buffer = Self::create_buffer(device, "Vertex Buffer", bytemuck::cast_slice(vertices), BufferUsages::VERTEX, dynamic);
+      let index_buffer = Self::create_buffer(device, "Index Buffer", bytemuck::cast_slice(indices), BufferUsages::INDEX, dynamic);
 
       Self {
          vertex_buffer,
-         num_indices,
-         num_vertices,
          index_buffer,
+         index_format: I::FORMAT,
+         num_indices: indices.len() as u32,
+         num_vertices: vertices.len() as u32,
+         vertex_type: PhantomData,
       }
    }
-}
\ No newline at end of file
+
+   fn create_buffer(device: &Device, label: &str, contents: &[u8], usage: BufferUsages, dynamic: bool) -> Buffer {
+      if !dynamic {
+         return device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some(label),
+            contents,
+            usage,
+         });
+      }
+
+      let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+         label: Some(label),
+         size: padded_len(contents.len()).max(COPY_BUFFER_ALIGNMENT),
+         usage: usage | BufferUsages::COPY_DST,
+         mapped_at_creation: true,
+      });
+      buffer.slice(..).get_mapped_range_mut()[..contents.len()].copy_from_slice(contents);
+      buffer.unmap();
+
+      buffer
+   }
+
+   pub fn desc() -> wgpu::VertexBufferLayout<'static> {
+      V::desc()
+   }
+}
+
+
+/// a `VertexPackage` whose buffers are created with COPY_DST, so the contents can be changed with `update`
+pub struct DynamicVertexPackage<V: VertexLayout = Vertex> {
+   package: VertexPackage<V>,
+}
+impl<V: VertexLayout> DynamicVertexPackage<V> {
+   pub fn new<I: IndexLayout>(device: &Device, vertices: &[V], indices: &[I]) -> Self {
+      Self { package: VertexPackage::create(device, vertices, indices, true) }
+   }
+
+   /// rewrites the buffers, growing them when the new data does not fit
+   pub fn update<I: IndexLayout>(&mut self, device: &Device, queue: &Queue, vertices: &[V], indices: &[I]) {
+      let package = &mut self.package;
+      write_or_grow_buffer(device, queue, &mut package.vertex_buffer, "Vertex Buffer", bytemuck::cast_slice(vertices), BufferUsages::VERTEX);
+      write_or_grow_buffer(device, queue, &mut package.index_buffer, "Index Buffer", bytemuck::cast_slice(indices), BufferUsages::INDEX);
+
+      package.index_format = I::FORMAT;
+      package.num_vertices = vertices.len() as u32;
+      package.num_indices = indices.len() as u32;
+   }
+}
+impl<V: VertexLayout> Deref for DynamicVertexPackage<V> {
+   type Target = VertexPackage<V>;
+
+   fn deref(&self) -> &Self::Target {
+      &self.package
+   }
+}
+
@@ -3,23 +3,44 @@ pub mod state;
 pub mod egui {
    pub mod gui;
    pub mod gui_example;
+   pub mod ui_settings_gui;
 }
 
 pub mod inbuilt {
    pub mod setup;
    pub mod vertex_library;
    pub mod vertex_package;
+   pub mod obj_loader;
+   pub mod instance_package;
+   pub mod pipeline_builder;
+   pub mod shader_preprocessor;
+   pub mod shader_reflection;
+   pub mod render_graph;
+   pub mod readback;
    pub mod event_loop;
 }
 
 pub mod packages {
    pub mod time_package;
+   pub mod uniform_package;
+   pub mod texture_package;
    pub mod camera_package;
+   pub mod scene_package;
+   pub mod globals_package;
    pub mod input_manager_package;
+   pub mod shader_package;
+   pub mod compute_package;
+   pub mod screenshot_package;
+   pub mod recorder_package;
+   pub mod persistence_package;
+   pub mod viewport_package;
+   pub mod log_package;
 }
 
 pub mod pipelines {
    pub mod test_render_pipeline;
+   pub mod instanced_render_pipeline;
+   pub mod textured_quad_pipeline;
 }
 
 pub mod bundles {
@@ -31,6 +52,24 @@ pub mod bundles {
       pub mod automata_bundle;
       pub mod automata_gui;
    }
+
+   pub mod post_process {
+      pub mod color_grading;
+      pub mod post_process_pipeline;
+      pub mod post_process_bundle;
+      pub mod post_process_gui;
+   }
+
+   pub mod debug_draw {
+      pub mod debug_draw_pipeline;
+      pub mod debug_draw_bundle;
+      pub mod debug_draw_gui;
+   }
+
+   pub mod showcase {
+      pub mod showcase_bundle;
+      pub mod showcase_gui;
+   }
 }
 
 pub mod utility {
@@ -1,8 +1,8 @@
 use cgmath::{SquareMatrix, Transform, Vector2};
 use wgpu::{Device, Queue};
-use wgpu::util::DeviceExt;
 use winit::keyboard::KeyCode::{KeyA, KeyD, KeyS, KeyW, KeyX, KeyZ};
 use crate::packages::input_manager_package::InputManager;
+use crate::packages::uniform_package::UniformPackage;
 
 pub struct OrthographicCamera {
    pub eye: cgmath::Point3<f32>,
@@ -96,10 +96,7 @@ impl OrthographicCameraController {
 
 pub struct CameraPackage {
    pub(crate) camera: OrthographicCamera,
-   camera_uniform: CameraUniform,
-   camera_buffer: wgpu::Buffer,
-   pub(crate) camera_bind_group_layout: wgpu::BindGroupLayout,
-   pub(crate) camera_bind_group: wgpu::BindGroup,
+   uniform: UniformPackage<CameraUniform>,
    pub(crate) camera_controller: OrthographicCameraController,
 }
 
@@ -108,56 +105,44 @@ impl CameraPackage {
       let mut camera_uniform = CameraUniform::new();
       camera_uniform.update_view_proj(&camera);
 
-      let camera_buffer = device.create_buffer_init(
-         &wgpu::util::BufferInitDescriptor {
-            label: Some("Camera Buffer"),
-            contents: bytemuck::cast_slice(&[camera_uniform]),
-            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
-         }
-      );
-
-      let camera_bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
-         entries: &[
-            wgpu::BindGroupLayoutEntry {
-               binding: 0,
-               visibility: wgpu::ShaderStages::VERTEX,
-               ty: wgpu::BindingType::Buffer {
-                  ty: wgpu::BufferBindingType::Uniform,
-                  has_dynamic_offset: false,
-                  min_binding_size: None,
-               },
-               count: None,
-            }
-         ],
-         label: Some("camera_bind_group_layout"),
-      });
-
-      let camera_bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
-         layout: &camera_bind_group_layout,
-         entries: &[
-            wgpu::BindGroupEntry {
-               binding: 0,
-               resource: camera_buffer.as_entire_binding(),
-            }
-         ],
-         label: Some("camera_bind_group"),
-      });
-
+      let uniform = UniformPackage::new(device, "camera", wgpu::ShaderStages::VERTEX, camera_uniform);
       let camera_controller = OrthographicCameraController::new(1.0);
 
       Self {
          camera,
-         camera_uniform,
-         camera_buffer,
-         camera_bind_group_layout,
-         camera_bind_group,
+         uniform,
          camera_controller,
       }
    }
 
+   /// shared by every shader that includes `common/camera.wgsl`, so not reflected from any one of them.
+   /// tests/shader_validation.rs checks it against each
+   pub fn bind_group_layout_entries() -> [wgpu::BindGroupLayoutEntry; 1] {
+      [UniformPackage::<CameraUniform>::layout_entry(wgpu::ShaderStages::VERTEX, false)]
+   }
+
+   pub fn bind_group_layout(&self) -> &wgpu::BindGroupLayout {
+      &self.uniform.bind_group_layout
+   }
+
+   pub fn bind_group(&self) -> &wgpu::BindGroup {
+      &self.uniform.bind_group
+   }
+
+   /// writes the current view with another aspect ratio, for drawing into a target that isn't window sized.
+   /// the next `update` writes the window's view again
+   pub fn write_with_aspect(&mut self, queue: &Queue, aspect: f32) {
+      let camera = OrthographicCamera { aspect, ..self.camera };
+      self.uniform.get_mut().update_view_proj(&camera);
+      self.uniform.upload(queue);
+   }
+
    pub fn update(&mut self, queue: &mut Queue, delta_time: f32, input_manager: &InputManager) {
       self.camera_controller.update_camera(&mut self.camera, delta_time, input_manager);
-      self.camera_uniform.update_view_proj(&self.camera);
-      queue.write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[self.camera_uniform]));
+
+      let mut camera_uniform = *self.uniform.get();
+      camera_uniform.update_view_proj(&self.camera);
+      self.uniform.set(camera_uniform);
+      self.uniform.upload(queue);
    }
-}
\ No newline at end of file
+}
@@ -0,0 +1,99 @@
+use wgpu::{BindGroupLayout, Buffer, CommandEncoder, ComputePass, ComputePipeline, Device, ShaderModule};
+use wgpu::util::{DeviceExt, DispatchIndirectArgs};
+use crate::inbuilt::pipeline_builder::ComputePipelineBuilder;
+use crate::inbuilt::shader_reflection::ReflectedShader;
+
+
+/// workgroups needed to cover `size` invocations, unused dimensions are 1.
+/// fails when a dimension needs more than `max_per_dimension` workgroups
+pub fn workgroup_count(size: [u32; 3], workgroup_size: [u32; 3], max_per_dimension: u32) -> Result<[u32; 3], String> {
+   let mut count = [0; 3];
+   for axis in 0..3 {
+      count[axis] = size[axis].div_ceil(workgroup_size[axis].max(1));
+
+      if count[axis] > max_per_dimension {
+         return Err(format!(
+            "{size:?} needs {} workgroups along axis {axis}, the device allows {max_per_dimension}",
+            count[axis],
+         ));
+      }
+   }
+   Ok(count)
+}
+
+/// a compute pipeline that knows its `@workgroup_size`, so dispatches are given in invocations
+/// and can't go out of sync with the shader
+pub struct ComputePackage {
+   pub pipeline: ComputePipeline,
+   label: String,
+   workgroup_size: [u32; 3],
+   max_per_dimension: u32,
+}
+impl ComputePackage {
+   /// `layouts` are bound in order, the first one is @group(0). fails when the entry point is missing or
+   /// its workgroup size is over the device limits
+   pub fn new(device: &Device, label: &str, shader: &ShaderModule, reflected: &ReflectedShader, entry_point: &str, layouts: &[&BindGroupLayout]) -> Result<Self, String> {
+      let workgroup_size = reflected.workgroup_size(entry_point)
+          .ok_or_else(|| format!("{label} has no compute entry point `{entry_point}`"))?;
+
+      let limits = device.limits();
+      let max_size = [limits.max_compute_workgroup_size_x, limits.max_compute_workgroup_size_y, limits.max_compute_workgroup_size_z];
+      let invocations: u32 = workgroup_size.iter().product();
+      if workgroup_size.iter().zip(max_size).any(|(size, max)| *size > max) || invocations > limits.max_compute_invocations_per_workgroup {
+         return Err(format!("{label}: workgroup size {workgroup_size:?} is over the device limits"));
+      }
+
+      let pipeline = layouts.iter()
+          .fold(ComputePipelineBuilder::new(shader).label(label).entry_point(entry_point), |builder, layout| builder.bind_group_layout(layout))
+          .build(device);
+
+      Ok(Self {
+         pipeline,
+         label: label.to_string(),
+         workgroup_size,
+         max_per_dimension: limits.max_compute_workgroups_per_dimension,
+      })
+   }
+
+   pub fn workgroup_size(&self) -> [u32; 3] {
+      self.workgroup_size
+   }
+
+   pub fn workgroups(&self, size: [u32; 3]) -> Result<[u32; 3], String> {
+      workgroup_count(size, self.workgroup_size, self.max_per_dimension)
+          .map_err(|err| format!("{}: {err}", self.label))
+   }
+
+   /// begins a compute pass with this pipeline set, bind groups are up to the caller
+   pub fn begin<'p>(&'p self, encoder: &'p mut CommandEncoder) -> ComputePass<'p> {
+      let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor {
+         label: Some(&self.label),
+         timestamp_writes: None,
+      });
+      pass.set_pipeline(&self.pipeline);
+      pass
+   }
+
+   /// one invocation per element of `size`, e.g. `[width, height, 1]`. nothing is dispatched when it needs too many workgroups
+   pub fn dispatch(&self, pass: &mut ComputePass, size: [u32; 3]) -> Result<(), String> {
+      let [x, y, z] = self.workgroups(size)?;
+      pass.dispatch_workgroups(x, y, z);
+      Ok(())
+   }
+
+   /// workgroup counts come from `buffer` at `offset`, laid out as `DispatchIndirectArgs`
+   pub fn dispatch_indirect<'p>(&self, pass: &mut ComputePass<'p>, buffer: &'p Buffer, offset: wgpu::BufferAddress) {
+      pass.dispatch_workgroups_indirect(buffer, offset);
+   }
+
+   /// an indirect args buffer covering `size`, a shader can write other counts into it as storage
+   pub fn indirect_buffer(&self, device: &Device, size: [u32; 3]) -> Result<Buffer, String> {
+      let [x, y, z] = self.workgroups(size)?;
+
+      Ok(device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+         label: Some(&self.label),
+         contents: DispatchIndirectArgs { x, y, z }.as_bytes(),
+         usage: wgpu::BufferUsages::INDIRECT | wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_DST,
+      }))
+   }
+}
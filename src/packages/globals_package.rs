@@ -0,0 +1,75 @@
+use cgmath::Vector2;
+use wgpu::{BindGroup, BindGroupLayout, BindGroupLayoutEntry, Device, ShaderStages};
+use crate::inbuilt::setup::Setup;
+use crate::packages::camera_package::CameraPackage;
+use crate::packages::input_manager_package::InputManager;
+use crate::packages::time_package::TimePackage;
+use crate::packages::uniform_package::UniformPackage;
+
+
+/// matches `FrameGlobals` in `common/globals.wgsl`
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Default, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct FrameGlobals {
+   pub time: f32,
+   pub delta_time: f32,
+   pub frame: u32,
+   pub _padding: u32,
+   pub resolution: [f32; 2],
+   pub mouse: [f32; 2],
+   pub mouse_world: [f32; 2],
+   pub _padding1: [f32; 2],
+}
+
+/// time, viewport, cursor and frame index for every shader that includes `common/globals.wgsl`.
+/// the include binds it at group 3 unless `GLOBALS_GROUP` is defined, bind `bind_group()` there.
+/// with a docked viewport, resolution and cursor are the viewport's rather than the window's
+pub struct GlobalsPackage {
+   uniform: UniformPackage<FrameGlobals>,
+   frame: u32,
+}
+impl GlobalsPackage {
+   pub const VISIBILITY: ShaderStages = ShaderStages::VERTEX_FRAGMENT.union(ShaderStages::COMPUTE);
+
+   pub fn new(device: &Device) -> Self {
+      Self {
+         uniform: UniformPackage::new(device, "frame globals", Self::VISIBILITY, FrameGlobals::default()),
+         frame: 0,
+      }
+   }
+
+   pub fn bind_group_layout_entries() -> [BindGroupLayoutEntry; 1] {
+      [UniformPackage::<FrameGlobals>::layout_entry(Self::VISIBILITY, false)]
+   }
+
+   pub fn bind_group_layout(&self) -> &BindGroupLayout {
+      &self.uniform.bind_group_layout
+   }
+
+   pub fn bind_group(&self) -> &BindGroup {
+      &self.uniform.bind_group
+   }
+
+   pub fn globals(&self) -> &FrameGlobals {
+      self.uniform.get()
+   }
+
+   /// call once per frame, after `InputManager::viewport` is set and before anything is recorded
+   pub fn update(&mut self, setup: &Setup, time_package: &TimePackage, input_manager: &InputManager, camera_package: &CameraPackage) {
+      let mouse_world = input_manager.pull_world_pos_2d(camera_package, setup);
+      let (mouse, resolution) = input_manager.viewport_mouse(Vector2::new(setup.config.width as f32, setup.config.height as f32));
+
+      self.uniform.set(FrameGlobals {
+         time: time_package.elapsed() as f32,
+         delta_time: time_package.delta_time as f32,
+         frame: self.frame,
+         resolution: resolution.into(),
+         mouse: mouse.into(),
+         mouse_world: mouse_world.into(),
+         ..Default::default()
+      });
+      self.uniform.upload(&setup.queue);
+
+      self.frame = self.frame.wrapping_add(1);
+   }
+}
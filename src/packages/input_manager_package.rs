@@ -5,6 +5,25 @@ use winit::keyboard::{KeyCode, PhysicalKey};
 use crate::inbuilt::setup::Setup;
 use crate::packages::camera_package::CameraPackage;
 
+
+/// part of the window in physical pixels, like `mouse_screen_pos`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ScreenRegion {
+   pub min: Vector2<f32>,
+   pub size: Vector2<f32>,
+}
+impl ScreenRegion {
+   pub fn contains(&self, position: Vector2<f32>) -> bool {
+      let local = self.to_local(position);
+      local.x >= 0.0 && local.y >= 0.0 && local.x < self.size.x && local.y < self.size.y
+   }
+
+   pub fn to_local(&self, position: Vector2<f32>) -> Vector2<f32> {
+      position - self.min
+   }
+}
+
+
 pub struct InputManager {
    currently_pressed: HashSet<KeyCode>,
    just_pressed: HashSet<KeyCode>,
@@ -13,6 +32,13 @@ pub struct InputManager {
    mouse_just_pressed: HashSet<MouseButton>,
 
    pub mouse_screen_pos: Vector2<f32>,
+   /// where the scene is shown, `None` for the whole window. world positions are mapped through it
+   pub viewport: Option<ScreenRegion>,
+}
+impl Default for InputManager {
+   fn default() -> Self {
+      Self::new()
+   }
 }
 impl InputManager {
    pub fn new() -> Self {
@@ -22,6 +48,7 @@ impl InputManager {
          mouse_screen_pos: Vector2::new(0.0, 0.0),
          mouse_currently_pressed: HashSet::new(),
          mouse_just_pressed: HashSet::new(),
+         viewport: None,
       }
    }
 
@@ -77,14 +104,24 @@ impl InputManager {
       self.mouse_just_pressed.contains(&button)
    }
 
+   /// always true without a viewport
+   pub fn is_mouse_in_viewport(&self) -> bool {
+      self.viewport.is_none_or(|viewport| viewport.contains(self.mouse_screen_pos))
+   }
+
+   /// the cursor relative to the viewport and the viewport's size, in pixels. `window_size` is used without a viewport
+   pub fn viewport_mouse(&self, window_size: Vector2<f32>) -> (Vector2<f32>, Vector2<f32>) {
+      match self.viewport {
+         Some(viewport) => (viewport.to_local(self.mouse_screen_pos), viewport.size),
+         None => (self.mouse_screen_pos, window_size),
+      }
+   }
+
    pub fn pull_world_pos_2d(&self, camera_package: &CameraPackage, setup: &Setup) -> Vector2<f32> {
       let screen_size = setup.window.inner_size();
+      let (position, size) = self.viewport_mouse(Vector2::new(screen_size.width as f32, screen_size.height as f32));
 
-      camera_package.camera_controller.screen_to_world_pos(
-         self.mouse_screen_pos,
-         Vector2::new(screen_size.width as f32, screen_size.height as f32),
-         &camera_package.camera,
-      )
+      camera_package.camera_controller.screen_to_world_pos(position, size, &camera_package.camera)
    }
 
    pub fn reset(&mut self) {
@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use egui::{Color32, Context};
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use wgpu::Device;
+
+
+/// records the console keeps before the oldest are dropped
+pub const LOG_CAPACITY: usize = 2000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogRecord {
+   pub level: Level,
+   pub target: String,
+   pub message: String,
+   /// since the logger was set up
+   pub time: Duration,
+}
+impl LogRecord {
+   /// how a record is copied to the clipboard
+   pub fn line(&self) -> String {
+      format!("[{:>9.3}s {:<5} {}] {}", self.time.as_secs_f64(), self.level, self.target, self.message)
+   }
+}
+
+
+/// the last `capacity` records, shared between the logger and the console
+#[derive(Clone)]
+pub struct LogBuffer {
+   records: Arc<Mutex<VecDeque<LogRecord>>>,
+   capacity: usize,
+   start: Instant,
+}
+impl LogBuffer {
+   pub fn new(capacity: usize) -> Self {
+      Self {
+         records: Arc::new(Mutex::new(VecDeque::with_capacity(capacity))),
+         capacity,
+         start: Instant::now(),
+      }
+   }
+
+   pub fn push(&self, level: Level, target: &str, message: String) {
+      let record = LogRecord { level, target: target.to_string(), message, time: self.start.elapsed() };
+
+      // a panic while the lock was held shouldn't stop the logging
+      let mut records = self.records.lock().unwrap_or_else(|err| err.into_inner());
+      if records.len() == self.capacity {
+         records.pop_front();
+      }
+      records.push_back(record);
+   }
+
+   /// a copy, so nothing that logs while the console is drawn can deadlock on the lock
+   pub fn filtered(&self, filter: &LogFilter) -> Vec<LogRecord> {
+      let records = self.records.lock().unwrap_or_else(|err| err.into_inner());
+      records.iter().filter(|record| filter.matches(record)).cloned().collect()
+   }
+
+   pub fn len(&self) -> usize {
+      self.records.lock().unwrap_or_else(|err| err.into_inner()).len()
+   }
+
+   pub fn is_empty(&self) -> bool {
+      self.len() == 0
+   }
+
+   pub fn clear(&self) {
+      self.records.lock().unwrap_or_else(|err| err.into_inner()).clear();
+   }
+}
+
+
+/// which records the console shows
+#[derive(Clone, Debug, PartialEq)]
+pub struct LogFilter {
+   pub level: LevelFilter,
+   /// part of the target, like `wgpu` or `automata`
+   pub target: String,
+   /// part of the message, case insensitive
+   pub search: String,
+}
+impl Default for LogFilter {
+   fn default() -> Self {
+      Self {
+         level: LevelFilter::Info,
+         target: String::new(),
+         search: String::new(),
+      }
+   }
+}
+impl LogFilter {
+   pub fn matches(&self, record: &LogRecord) -> bool {
+      record.level <= self.level
+          && record.target.contains(self.target.trim())
+          && record.message.to_lowercase().contains(&self.search.trim().to_lowercase())
+   }
+}
+
+
+/// logs to stderr like `env_logger`, configured through `RUST_LOG` as before, and keeps everything
+/// at `capture` or above for the console no matter what `RUST_LOG` says
+pub struct ConsoleLogger {
+   stderr: env_logger::Logger,
+   buffer: LogBuffer,
+   capture: LevelFilter,
+}
+impl ConsoleLogger {
+   pub fn new(stderr: env_logger::Logger, buffer: LogBuffer, capture: LevelFilter) -> Self {
+      Self { stderr, buffer, capture }
+   }
+
+   pub fn max_level(&self) -> LevelFilter {
+      self.stderr.filter().max(self.capture)
+   }
+}
+impl Log for ConsoleLogger {
+   fn enabled(&self, metadata: &Metadata) -> bool {
+      metadata.level() <= self.capture || self.stderr.enabled(metadata)
+   }
+
+   fn log(&self, record: &Record) {
+      if self.stderr.matches(record) {
+         self.stderr.log(record);
+      }
+      if record.level() <= self.capture {
+         self.buffer.push(record.level(), record.target(), record.args().to_string());
+      }
+   }
+
+   fn flush(&self) {
+      self.stderr.flush();
+   }
+}
+
+/// replaces `env_logger::init`, the returned buffer goes to the `LogPackage`
+pub fn init(capture: LevelFilter) -> LogBuffer {
+   let buffer = LogBuffer::new(LOG_CAPACITY);
+   let logger = ConsoleLogger::new(env_logger::Builder::from_default_env().build(), buffer.clone(), capture);
+
+   let max_level = logger.max_level();
+   match log::set_boxed_logger(Box::new(logger)) {
+      Ok(()) => log::set_max_level(max_level),
+      Err(err) => eprintln!("the log console stays empty: {err}"),
+   }
+   buffer
+}
+
+/// wgpu panics on a validation error no error scope caught, this logs it instead so it shows up
+/// in the console and the app keeps running
+pub fn capture_uncaptured_errors(device: &Device) {
+   device.on_uncaptured_error(Box::new(|err| log::error!(target: "wgpu", "{err}")));
+}
+
+
+fn level_color(level: Level) -> Color32 {
+   match level {
+      Level::Error => Color32::from_rgb(235, 90, 80),
+      Level::Warn => Color32::from_rgb(230, 190, 70),
+      Level::Info => Color32::from_rgb(120, 190, 240),
+      Level::Debug => Color32::from_rgb(150, 150, 150),
+      Level::Trace => Color32::from_rgb(110, 110, 110),
+   }
+}
+
+/// the console window, showing what `init` captured
+pub struct LogPackage {
+   pub buffer: LogBuffer,
+   pub filter: LogFilter,
+}
+impl LogPackage {
+   pub fn new(buffer: LogBuffer) -> Self {
+      Self { buffer, filter: LogFilter::default() }
+   }
+
+   pub fn gui(&mut self, ctx: &Context) {
+      egui::Window::new("Console")
+          .default_open(false)
+          .default_width(600.0)
+          .default_height(300.0)
+          .resizable(true)
+          .show(ctx, |ui| {
+             let records = self.buffer.filtered(&self.filter);
+
+             ui.horizontal(|ui| {
+                egui::ComboBox::from_id_source("console level")
+                    .selected_text(self.filter.level.to_string())
+                    .show_ui(ui, |ui| {
+                       for level in LevelFilter::iter().skip(1) {
+                          ui.selectable_value(&mut self.filter.level, level, level.to_string());
+                       }
+                    });
+                ui.add(egui::TextEdit::singleline(&mut self.filter.target).hint_text("target").desired_width(100.0));
+                ui.add(egui::TextEdit::singleline(&mut self.filter.search).hint_text("search").desired_width(160.0));
+
+                if ui.button("copy").clicked() {
+                   let text = records.iter().map(LogRecord::line).collect::<Vec<_>>().join("\n");
+                   ui.output_mut(|o| o.copied_text = text);
+                }
+                if ui.button("clear").clicked() {
+                   self.buffer.clear();
+                }
+             });
+             ui.label(format!("{} of {} records", records.len(), self.buffer.len()));
+             ui.separator();
+
+             // no `show_rows`, wgpu's validation errors span several lines
+             egui::ScrollArea::both()
+                 .auto_shrink(false)
+                 .stick_to_bottom(true)
+                 .show(ui, |ui| {
+                    for record in &records {
+                       let text = egui::RichText::new(record.line()).monospace().color(level_color(record.level));
+                       ui.add(egui::Label::new(text).wrap(false));
+                    }
+                 });
+          });
+   }
+}
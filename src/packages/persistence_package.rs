@@ -0,0 +1,214 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use egui::Context;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use crate::bundles::automata::automata_bundle::AutomataSettings;
+use crate::egui::gui::UiSettings;
+use crate::packages::recorder_package::RecordingSettings;
+
+
+#[derive(Debug)]
+pub enum PersistenceError {
+   Io(std::io::Error),
+   Parse(ron::error::SpannedError),
+   Serialize(ron::Error),
+}
+impl Display for PersistenceError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         PersistenceError::Io(err) => write!(f, "failed to access the settings: {err}"),
+         PersistenceError::Parse(err) => write!(f, "failed to parse the settings: {err}"),
+         PersistenceError::Serialize(err) => write!(f, "failed to write the settings: {err}"),
+      }
+   }
+}
+impl std::error::Error for PersistenceError {}
+impl From<std::io::Error> for PersistenceError {
+   fn from(err: std::io::Error) -> Self {
+      PersistenceError::Io(err)
+   }
+}
+impl From<ron::error::SpannedError> for PersistenceError {
+   fn from(err: ron::error::SpannedError) -> Self {
+      PersistenceError::Parse(err)
+   }
+}
+impl From<ron::Error> for PersistenceError {
+   fn from(err: ron::Error) -> Self {
+      PersistenceError::Serialize(err)
+   }
+}
+
+
+/// everything besides the ui layout that survives a restart. missing fields take their default,
+/// so files from older versions still load
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+   pub automata: AutomataSettings,
+   pub recording: RecordingSettings,
+   pub screenshot_include_ui: bool,
+   pub debug_draw: bool,
+   pub ui: UiSettings,
+}
+impl Default for AppSettings {
+   fn default() -> Self {
+      Self {
+         automata: AutomataSettings::default(),
+         recording: RecordingSettings::default(),
+         screenshot_include_ui: true,
+         debug_draw: false,
+         ui: UiSettings::default(),
+      }
+   }
+}
+
+
+/// `None` if there is no file yet
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<Option<T>, PersistenceError> {
+   let text = match std::fs::read_to_string(path) {
+      Ok(text) => text,
+      Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+      Err(err) => return Err(err.into()),
+   };
+   Ok(Some(ron::from_str(&text)?))
+}
+
+/// written next to `path` first and renamed over it, so a crash mid write can't leave half a file
+pub fn save<T: Serialize>(path: &Path, value: &T) -> Result<(), PersistenceError> {
+   if let Some(parent) = path.parent() {
+      std::fs::create_dir_all(parent)?;
+   }
+
+   let text = ron::ser::to_string_pretty(value, ron::ser::PrettyConfig::default())?;
+   let temporary = path.with_extension("ron.tmp");
+   std::fs::write(&temporary, text)?;
+   std::fs::rename(&temporary, path)?;
+   Ok(())
+}
+
+/// the default when the file is missing or unreadable. a file that fails to parse is moved to
+/// `<name>.corrupt` so the next save doesn't overwrite it
+pub fn load_or_default<T: DeserializeOwned + Default>(path: &Path) -> T {
+   match load(path) {
+      Ok(value) => value.unwrap_or_default(),
+      Err(err) => {
+         log::warn!("{}: {err}, using the defaults", path.display());
+
+         if let PersistenceError::Parse(_) = err {
+            let backup = path.with_extension("ron.corrupt");
+            if let Err(err) = std::fs::rename(path, &backup) {
+               log::warn!("failed to move {} aside: {err}", path.display());
+            }
+         }
+         T::default()
+      }
+   }
+}
+
+
+/// the style lives in the memory but isn't saved with it, it is carried over so the theme stays
+fn replace_memory(ctx: &Context, memory: egui::Memory) {
+   let style = ctx.style();
+   ctx.memory_mut(|m| *m = memory);
+   ctx.set_style(style);
+}
+
+
+/// saves the app settings and egui's memory, window positions and collapsed headers, to the user's
+/// config directory and restores them on startup
+pub struct PersistencePackage {
+   pub directory: PathBuf,
+   /// whether `save` is called on exit
+   pub save_on_exit: bool,
+   reset_requested: bool,
+   status: Option<String>,
+}
+impl PersistencePackage {
+   pub const SETTINGS_FILE: &'static str = "settings.ron";
+   pub const MEMORY_FILE: &'static str = "egui_memory.ron";
+
+   pub fn new() -> Self {
+      let directory = directories::ProjectDirs::from("", "", "wgpu_template")
+          .map(|dirs| dirs.config_dir().to_path_buf())
+          .unwrap_or_else(|| PathBuf::from("config"));
+
+      Self::with_directory(directory)
+   }
+
+   pub fn with_directory(directory: impl Into<PathBuf>) -> Self {
+      Self {
+         directory: directory.into(),
+         save_on_exit: true,
+         reset_requested: false,
+         status: None,
+      }
+   }
+
+   pub fn load_settings(&self) -> AppSettings {
+      load_or_default(&self.directory.join(Self::SETTINGS_FILE))
+   }
+
+   /// call before the first frame, egui reads window positions when a window is first shown
+   pub fn restore_memory(&self, ctx: &Context) {
+      let memory: Option<egui::Memory> = load(&self.directory.join(Self::MEMORY_FILE))
+          .unwrap_or_else(|err| {
+             log::warn!("{err}, the ui layout is reset");
+             None
+          });
+
+      if let Some(memory) = memory {
+         replace_memory(ctx, memory);
+      }
+   }
+
+   pub fn save(&self, ctx: &Context, settings: &AppSettings) -> Result<(), PersistenceError> {
+      save(&self.directory.join(Self::SETTINGS_FILE), settings)?;
+      save(&self.directory.join(Self::MEMORY_FILE), &ctx.memory(|m| m.clone()))
+   }
+
+   /// true once after "reset to defaults" was clicked, the app puts `AppSettings::default()` back
+   pub fn take_reset(&mut self) -> bool {
+      std::mem::take(&mut self.reset_requested)
+   }
+
+   pub fn reset_memory(&self, ctx: &Context) {
+      replace_memory(ctx, egui::Memory::default());
+   }
+
+   pub fn gui(&mut self, ctx: &Context, settings: &AppSettings) {
+      egui::Window::new("Settings")
+          .default_open(false)
+          .resizable(false)
+          .show(ctx, |ui| {
+             ui.label(format!("stored in {}", self.directory.display()));
+             ui.add(egui::Checkbox::new(&mut self.save_on_exit, "save on exit"));
+
+             ui.horizontal(|ui| {
+                if ui.add(egui::Button::new("save now")).clicked() {
+                   self.status = Some(match self.save(ctx, settings) {
+                      Ok(()) => "saved".to_string(),
+                      Err(err) => {
+                         log::error!("{err}");
+                         err.to_string()
+                      }
+                   });
+                }
+                if ui.add(egui::Button::new("reset to defaults")).clicked() {
+                   self.reset_requested = true;
+                   self.status = None;
+                }
+             });
+
+             if let Some(status) = &self.status {
+                ui.label(status);
+             }
+          });
+   }
+}
+impl Default for PersistencePackage {
+   fn default() -> Self {
+      Self::new()
+   }
+}
@@ -0,0 +1,386 @@
+use std::fmt::{Display, Formatter};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::SystemTime;
+use egui::Context;
+use serde::{Deserialize, Serialize};
+use wgpu::{Texture, TextureView};
+use crate::inbuilt::render_graph::RenderTargetPool;
+use crate::inbuilt::setup::Setup;
+use crate::packages::screenshot_package::{read_texture_then, timestamp, CapturedImage, ScreenshotError};
+
+
+#[derive(Debug)]
+pub enum RecordingError {
+   Capture(ScreenshotError),
+   Io(std::io::Error),
+   Gif(gif::EncodingError),
+   TooLarge(u32, u32),
+}
+impl Display for RecordingError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         RecordingError::Capture(err) => write!(f, "{err}"),
+         RecordingError::Io(err) => write!(f, "failed to write the recording: {err}"),
+         RecordingError::Gif(err) => write!(f, "failed to encode the gif: {err}"),
+         RecordingError::TooLarge(width, height) => write!(f, "{width}x{height} is too large for a gif"),
+      }
+   }
+}
+impl std::error::Error for RecordingError {}
+impl From<ScreenshotError> for RecordingError {
+   fn from(err: ScreenshotError) -> Self {
+      RecordingError::Capture(err)
+   }
+}
+impl From<std::io::Error> for RecordingError {
+   fn from(err: std::io::Error) -> Self {
+      RecordingError::Io(err)
+   }
+}
+impl From<gif::EncodingError> for RecordingError {
+   fn from(err: gif::EncodingError) -> Self {
+      RecordingError::Gif(err)
+   }
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RecordingFormat {
+   PngSequence,
+   Gif,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecordingSettings {
+   pub format: RecordingFormat,
+   pub frame_count: u32,
+   pub width: u32,
+   pub height: u32,
+   /// simulation generations between two captured frames
+   pub generations_per_frame: u32,
+   /// playback rate written into the gif
+   pub gif_fps: u32,
+}
+impl Default for RecordingSettings {
+   fn default() -> Self {
+      Self {
+         format: RecordingFormat::Gif,
+         frame_count: 120,
+         width: 512,
+         height: 512,
+         generations_per_frame: 1,
+         gif_fps: 30,
+      }
+   }
+}
+
+
+/// what the app has to do for one recorded frame
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct RecordedFrame {
+   /// advance the simulation by one generation before drawing
+   pub step: bool,
+   /// draw the result into the recording
+   pub capture: bool,
+}
+
+/// decides per app frame whether to step and capture, so a recording only depends on generations
+/// and not on how fast frames are rendered. the first frame is the state recording started from
+pub struct FrameSchedule {
+   frame_count: u32,
+   generations_per_frame: u32,
+   generation: u32,
+   captured: u32,
+   started: bool,
+}
+impl FrameSchedule {
+   pub fn new(frame_count: u32, generations_per_frame: u32) -> Self {
+      Self { frame_count, generations_per_frame: generations_per_frame.max(1), generation: 0, captured: 0, started: false }
+   }
+
+   /// `None` once every frame has been captured
+   pub fn next_frame(&mut self) -> Option<RecordedFrame> {
+      if self.is_finished() { return None; }
+
+      let step = std::mem::replace(&mut self.started, true);
+      if step { self.generation += 1; }
+
+      let capture = self.generation.is_multiple_of(self.generations_per_frame);
+      if capture { self.captured += 1; }
+
+      Some(RecordedFrame { step, capture })
+   }
+
+   pub fn is_finished(&self) -> bool {
+      self.captured >= self.frame_count
+   }
+
+   pub fn captured(&self) -> u32 {
+      self.captured
+   }
+}
+
+
+/// where captured frames end up, `path` is used without its extension
+pub enum FrameSink {
+   PngSequence { directory: PathBuf, written: u32 },
+   Gif { path: PathBuf, encoder: gif::Encoder<BufWriter<File>>, delay: u16 },
+}
+impl FrameSink {
+   pub fn create(format: RecordingFormat, path: &Path, width: u32, height: u32, gif_fps: u32) -> Result<Self, RecordingError> {
+      match format {
+         RecordingFormat::PngSequence => {
+            std::fs::create_dir_all(path)?;
+            Ok(FrameSink::PngSequence { directory: path.to_path_buf(), written: 0 })
+         }
+         RecordingFormat::Gif => {
+            let (Ok(gif_width), Ok(gif_height)) = (u16::try_from(width), u16::try_from(height)) else {
+               return Err(RecordingError::TooLarge(width, height));
+            };
+
+            let path = path.with_extension("gif");
+            if let Some(parent) = path.parent() {
+               std::fs::create_dir_all(parent)?;
+            }
+
+            let mut encoder = gif::Encoder::new(BufWriter::new(File::create(&path)?), gif_width, gif_height, &[])?;
+            encoder.set_repeat(gif::Repeat::Infinite)?;
+
+            // gif delays are in hundredths of a second
+            let delay = (100 / gif_fps.max(1)).max(1) as u16;
+            Ok(FrameSink::Gif { path, encoder, delay })
+         }
+      }
+   }
+
+   pub fn write(&mut self, mut image: CapturedImage) -> Result<(), RecordingError> {
+      match self {
+         FrameSink::PngSequence { directory, written } => {
+            image.save_png(directory.join(format!("frame_{:05}.png", written)))?;
+            *written += 1;
+         }
+         FrameSink::Gif { encoder, delay, .. } => {
+            let mut frame = gif::Frame::from_rgba_speed(image.width as u16, image.height as u16, &mut image.rgba, 10);
+            frame.delay = *delay;
+            encoder.write_frame(&frame)?;
+         }
+      }
+      Ok(())
+   }
+
+   /// finishes the file and returns where the recording is
+   pub fn finish(self) -> Result<PathBuf, RecordingError> {
+      match self {
+         FrameSink::PngSequence { directory, .. } => Ok(directory),
+         FrameSink::Gif { path, encoder, .. } => {
+            encoder.into_inner()?;
+            Ok(path)
+         }
+      }
+   }
+}
+
+
+struct Recording {
+   texture: Texture,
+   view: TextureView,
+   targets: RenderTargetPool,
+   size: (u32, u32),
+   schedule: FrameSchedule,
+   /// captures in the order they were submitted, a failed one stops the worker
+   frames: Sender<Result<CapturedImage, ScreenshotError>>,
+}
+
+/// renders generations into a fixed size target and hands them to a sink on a background thread.
+/// while recording the app drives the simulation through `next_frame` instead of the real clock
+pub struct RecorderPackage {
+   pub settings: RecordingSettings,
+   pub directory: PathBuf,
+
+   recording: Option<Recording>,
+   capturing: bool,
+   finished_sender: Sender<Result<PathBuf, String>>,
+   finished_receiver: Receiver<Result<PathBuf, String>>,
+   status: Option<String>,
+}
+impl Default for RecorderPackage {
+   fn default() -> Self {
+      Self::new()
+   }
+}
+impl RecorderPackage {
+   pub fn new() -> Self {
+      let (finished_sender, finished_receiver) = channel();
+
+      Self {
+         settings: RecordingSettings::default(),
+         directory: PathBuf::from("recordings"),
+
+         recording: None,
+         capturing: false,
+         finished_sender,
+         finished_receiver,
+         status: None,
+      }
+   }
+
+   pub fn is_recording(&self) -> bool {
+      self.recording.is_some()
+   }
+
+   pub fn start(&mut self, setup: &Setup) {
+      let settings = &self.settings;
+      let path = self.directory.join(format!("recording_{}", timestamp(SystemTime::now())));
+
+      let mut sink = match FrameSink::create(settings.format, &path, settings.width, settings.height, settings.gif_fps) {
+         Ok(sink) => sink,
+         Err(err) => {
+            log::error!("{err}");
+            self.status = Some(err.to_string());
+            return;
+         }
+      };
+
+      // encoding, gifs especially, is far slower than rendering, so frames queue up for a worker
+      let (frames, receiver) = channel::<Result<CapturedImage, ScreenshotError>>();
+      let finished = self.finished_sender.clone();
+      std::thread::spawn(move || {
+         let written = receiver.iter().try_for_each(|image| sink.write(image?));
+         let result = written.and_then(|_| sink.finish()).map_err(|err| err.to_string());
+         let _ = finished.send(result);
+      });
+
+      let texture = setup.device.create_texture(&wgpu::TextureDescriptor {
+         label: Some("recording target"),
+         size: wgpu::Extent3d { width: settings.width, height: settings.height, depth_or_array_layers: 1 },
+         mip_level_count: 1,
+         sample_count: 1,
+         dimension: wgpu::TextureDimension::D2,
+         format: setup.config.format,
+         usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+         view_formats: &[],
+      });
+      let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+      self.recording = Some(Recording {
+         texture,
+         view,
+         targets: RenderTargetPool::new(),
+         size: (settings.width, settings.height),
+         schedule: FrameSchedule::new(settings.frame_count, settings.generations_per_frame),
+         frames,
+      });
+      self.status = Some("recording".to_string());
+   }
+
+   /// stops early, the frames captured so far are still written
+   pub fn stop(&mut self) {
+      // dropping the sender lets the worker finish the file
+      self.recording = None;
+      self.capturing = false;
+   }
+
+   /// stops because a frame couldn't be captured, the frames so far are still written
+   pub fn abort(&mut self, err: String) {
+      log::error!("{err}");
+      self.status = Some(err);
+      self.stop();
+   }
+
+   /// what to do this frame, `None` when not recording
+   pub fn next_frame(&mut self) -> Option<RecordedFrame> {
+      let frame = self.recording.as_mut()?.schedule.next_frame()?;
+      self.capturing = frame.capture;
+      Some(frame)
+   }
+
+   /// the size the recording graph has to be executed at, when this frame is captured
+   pub fn frame_size(&self) -> Option<(u32, u32)> {
+      self.recording.as_ref().filter(|_| self.capturing).map(|recording| recording.size)
+   }
+
+   /// the view to draw the captured frame into and a target pool sized for it
+   pub fn target(&mut self) -> Option<(&TextureView, &mut RenderTargetPool)> {
+      let recording = self.recording.as_mut().filter(|_| self.capturing)?;
+      Some((&recording.view, &mut recording.targets))
+   }
+
+   /// starts reading the frame back once it has been submitted, the worker gets it when a later poll finds it done.
+   /// stopping drops only this sender, the worker finishes the file after the frames still being read back
+   pub fn end_frame(&mut self, setup: &Setup) {
+      if !std::mem::take(&mut self.capturing) { return; }
+      let Some(recording) = &self.recording else { return };
+
+      let frames = recording.frames.clone();
+      let started = read_texture_then(&setup.device, &setup.queue, &recording.texture, move |image| {
+         // a worker that already stopped has reported why
+         let _ = frames.send(image);
+      });
+
+      if let Err(err) = started {
+         self.abort(err.to_string());
+      }
+      else if recording.schedule.is_finished() {
+         self.stop();
+      }
+   }
+
+   pub fn gui(&mut self, ctx: &Context, setup: &Setup) {
+      while let Ok(result) = self.finished_receiver.try_recv() {
+         self.status = Some(match result {
+            Ok(path) => {
+               log::info!("saved recording to {}", path.display());
+               format!("saved {}", path.display())
+            }
+            Err(err) => {
+               log::error!("{err}");
+               err
+            }
+         });
+      }
+
+      egui::Window::new("Recorder")
+          .default_open(false)
+          .resizable(false)
+          .show(ctx, |ui| {
+             let recording = self.is_recording();
+
+             ui.add_enabled_ui(!recording, |ui| {
+                let settings = &mut self.settings;
+                ui.horizontal(|ui| {
+                   ui.radio_value(&mut settings.format, RecordingFormat::Gif, "gif");
+                   ui.radio_value(&mut settings.format, RecordingFormat::PngSequence, "png sequence");
+                });
+                ui.add(egui::Slider::new(&mut settings.frame_count, 1..=2000).text("frames"));
+                ui.add(egui::Slider::new(&mut settings.generations_per_frame, 1..=100).text("generations per frame"));
+                ui.add(egui::Slider::new(&mut settings.width, 16..=4096).text("width"));
+                ui.add(egui::Slider::new(&mut settings.height, 16..=4096).text("height"));
+                if settings.format == RecordingFormat::Gif {
+                   ui.add(egui::Slider::new(&mut settings.gif_fps, 1..=100).text("gif fps"));
+                }
+             });
+
+             match &self.recording {
+                Some(recording) => {
+                   ui.label(format!("frame {} / {}", recording.schedule.captured(), self.settings.frame_count));
+                   if ui.add(egui::Button::new("stop")).clicked() {
+                      self.stop();
+                   }
+                }
+                None => {
+                   if ui.add(egui::Button::new("record")).clicked() {
+                      self.start(setup);
+                   }
+                }
+             }
+
+             if let Some(status) = &self.status {
+                ui.label(status);
+             }
+          });
+   }
+}
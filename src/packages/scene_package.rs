@@ -0,0 +1,297 @@
+use std::fmt::{Display, Formatter};
+use cgmath::{Deg, Euler, Matrix4, One, Quaternion, Rad, SquareMatrix, Vector3, Vector4};
+use egui::Context;
+use wgpu::{BindGroup, BindGroupLayout, BindGroupLayoutEntry, Device, Queue, ShaderStages};
+use crate::packages::uniform_package::UniformPackage;
+
+
+/// nodes a `ScenePackage` has room for
+pub const MAX_NODES: usize = 256;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+   pub position: Vector3<f32>,
+   pub rotation: Quaternion<f32>,
+   pub scale: Vector3<f32>,
+}
+impl Default for Transform {
+   fn default() -> Self {
+      Self {
+         position: Vector3::new(0.0, 0.0, 0.0),
+         rotation: Quaternion::one(),
+         scale: Vector3::new(1.0, 1.0, 1.0),
+      }
+   }
+}
+impl Transform {
+   pub fn from_position(x: f32, y: f32) -> Self {
+      Self { position: Vector3::new(x, y, 0.0), ..Self::default() }
+   }
+
+   /// turned counter clockwise around the camera axis
+   pub fn with_rotation_z(mut self, angle: impl Into<Rad<f32>>) -> Self {
+      self.rotation = Quaternion::from(Euler::new(Rad(0.0), Rad(0.0), angle.into()));
+      self
+   }
+
+   pub fn with_scale(mut self, x: f32, y: f32) -> Self {
+      self.scale = Vector3::new(x, y, 1.0);
+      self
+   }
+
+   /// scale, then rotation, then translation, same order as `Instance`
+   pub fn matrix(&self) -> Matrix4<f32> {
+      Matrix4::from_translation(self.position)
+          * Matrix4::from(self.rotation)
+          * Matrix4::from_nonuniform_scale(self.scale.x, self.scale.y, self.scale.z)
+   }
+}
+
+
+/// matches `ModelUniform` in `common/model.wgsl`
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct ModelUniform {
+   pub model: [[f32; 4]; 4],
+}
+impl Default for ModelUniform {
+   fn default() -> Self {
+      Self { model: Matrix4::identity().into() }
+   }
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId(usize);
+impl NodeId {
+   /// also the node's element in the model uniform array
+   pub fn index(&self) -> usize {
+      self.0
+   }
+}
+
+pub struct SceneNode {
+   pub name: String,
+   pub transform: Transform,
+   parent: Option<NodeId>,
+   children: Vec<NodeId>,
+}
+impl SceneNode {
+   pub fn parent(&self) -> Option<NodeId> {
+      self.parent
+   }
+
+   pub fn children(&self) -> &[NodeId] {
+      &self.children
+   }
+}
+
+
+/// transforms in a parent child hierarchy, a node's world matrix is its parent's times its own
+#[derive(Default)]
+pub struct SceneGraph {
+   nodes: Vec<SceneNode>,
+}
+impl SceneGraph {
+   pub fn new() -> Self {
+      Self::default()
+   }
+
+   pub fn add(&mut self, name: &str, transform: Transform) -> NodeId {
+      let id = NodeId(self.nodes.len());
+      self.nodes.push(SceneNode { name: name.to_string(), transform, parent: None, children: vec![] });
+      id
+   }
+
+   pub fn add_child(&mut self, parent: NodeId, name: &str, transform: Transform) -> NodeId {
+      let id = self.add(name, transform);
+      self.nodes[id.0].parent = Some(parent);
+      self.nodes[parent.0].children.push(id);
+      id
+   }
+
+   /// moves `node` under `parent`, or to the top with `None`. a node can't go under its own subtree
+   pub fn set_parent(&mut self, node: NodeId, parent: Option<NodeId>) -> Result<(), String> {
+      if let Some(parent) = parent {
+         if self.ancestors(parent).any(|ancestor| ancestor == node) {
+            return Err(format!("`{}` can't be moved under its own child `{}`", self.nodes[node.0].name, self.nodes[parent.0].name));
+         }
+      }
+
+      if let Some(old) = self.nodes[node.0].parent.take() {
+         self.nodes[old.0].children.retain(|&child| child != node);
+      }
+      if let Some(parent) = parent {
+         self.nodes[parent.0].children.push(node);
+      }
+      self.nodes[node.0].parent = parent;
+      Ok(())
+   }
+
+   /// `node` itself, then its parent, up to the root
+   pub fn ancestors(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+      std::iter::successors(Some(node), |&id| self.nodes[id.0].parent)
+   }
+
+   pub fn node(&self, id: NodeId) -> &SceneNode {
+      &self.nodes[id.0]
+   }
+
+   pub fn transform_mut(&mut self, id: NodeId) -> &mut Transform {
+      &mut self.nodes[id.0].transform
+   }
+
+   pub fn world_matrix(&self, id: NodeId) -> Matrix4<f32> {
+      self.ancestors(id).fold(Matrix4::identity(), |world, ancestor| self.nodes[ancestor.0].transform.matrix() * world)
+   }
+
+   /// a world position in the node's own space, `None` if a scale of zero flattened it
+   pub fn world_to_local(&self, id: NodeId, position: Vector3<f32>) -> Option<Vector3<f32>> {
+      let local = self.world_matrix(id).invert()? * position.extend(1.0);
+      Some(local.truncate())
+   }
+
+   pub fn local_to_world(&self, id: NodeId, position: Vector3<f32>) -> Vector3<f32> {
+      (self.world_matrix(id) * Vector4::new(position.x, position.y, position.z, 1.0)).truncate()
+   }
+
+   pub fn len(&self) -> usize {
+      self.nodes.len()
+   }
+
+   pub fn is_empty(&self) -> bool {
+      self.nodes.is_empty()
+   }
+
+   pub fn roots(&self) -> impl Iterator<Item = NodeId> + '_ {
+      (0..self.nodes.len()).map(NodeId).filter(|&id| self.nodes[id.0].parent.is_none())
+   }
+}
+
+
+/// `ScenePackage` already holds `MAX_NODES` nodes
+#[derive(Debug, PartialEq)]
+pub struct SceneFull;
+impl Display for SceneFull {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      write!(f, "a scene holds at most {MAX_NODES} nodes")
+   }
+}
+impl std::error::Error for SceneFull {}
+
+
+/// the scene graph and every node's world matrix in one uniform array. shaders that include
+/// `common/model.wgsl` get it at group 2 unless `MODEL_GROUP` is defined, bind it with `bind_group()`
+/// and `offset(node)` as the dynamic offset. nodes only go in through `add` and `add_child`,
+/// which keep the graph within the `MAX_NODES` the uniform array holds
+pub struct ScenePackage {
+   graph: SceneGraph,
+   models: UniformPackage<ModelUniform>,
+}
+impl ScenePackage {
+   pub const VISIBILITY: ShaderStages = ShaderStages::VERTEX;
+
+   pub fn new(device: &Device) -> Self {
+      Self {
+         graph: SceneGraph::new(),
+         models: UniformPackage::new_array(device, "scene models", Self::VISIBILITY, vec![ModelUniform::default(); MAX_NODES]),
+      }
+   }
+
+   pub fn add(&mut self, name: &str, transform: Transform) -> Result<NodeId, SceneFull> {
+      if self.graph.len() >= MAX_NODES { return Err(SceneFull); }
+      Ok(self.graph.add(name, transform))
+   }
+
+   pub fn add_child(&mut self, parent: NodeId, name: &str, transform: Transform) -> Result<NodeId, SceneFull> {
+      if self.graph.len() >= MAX_NODES { return Err(SceneFull); }
+      Ok(self.graph.add_child(parent, name, transform))
+   }
+
+   pub fn graph(&self) -> &SceneGraph {
+      &self.graph
+   }
+
+   pub fn node(&self, id: NodeId) -> &SceneNode {
+      self.graph.node(id)
+   }
+
+   pub fn transform_mut(&mut self, id: NodeId) -> &mut Transform {
+      self.graph.transform_mut(id)
+   }
+
+   pub fn set_parent(&mut self, node: NodeId, parent: Option<NodeId>) -> Result<(), String> {
+      self.graph.set_parent(node, parent)
+   }
+
+   pub fn bind_group_layout_entries() -> [BindGroupLayoutEntry; 1] {
+      [UniformPackage::<ModelUniform>::layout_entry(Self::VISIBILITY, true)]
+   }
+
+   pub fn bind_group_layout(&self) -> &BindGroupLayout {
+      &self.models.bind_group_layout
+   }
+
+   pub fn bind_group(&self) -> &BindGroup {
+      &self.models.bind_group
+   }
+
+   pub fn offset(&self, node: NodeId) -> u32 {
+      self.models.offset(node.index())
+   }
+
+   /// call once per frame after the transforms are changed and before anything is recorded
+   pub fn update(&mut self, queue: &Queue) {
+      for i in 0..self.graph.len() {
+         let model = self.graph.world_matrix(NodeId(i)).into();
+         self.models.set_at(i, ModelUniform { model });
+      }
+      self.models.upload(queue);
+   }
+
+   /// only the rotation around the camera axis is editable, the camera is 2d
+   pub fn gui(&mut self, ctx: &Context) {
+      egui::Window::new("Scene")
+          .default_open(false)
+          .show(ctx, |ui| {
+             let roots: Vec<NodeId> = self.graph.roots().collect();
+             for root in roots {
+                self.node_gui(ui, root);
+             }
+          });
+   }
+
+   fn node_gui(&mut self, ui: &mut egui::Ui, id: NodeId) {
+      let name = self.graph.nodes[id.0].name.clone();
+
+      egui::CollapsingHeader::new(name)
+          .id_source(id.0)
+          .default_open(true)
+          .show(ui, |ui| {
+             let transform = self.graph.transform_mut(id);
+             ui.horizontal(|ui| {
+                ui.label("position");
+                ui.add(egui::DragValue::new(&mut transform.position.x).speed(0.01));
+                ui.add(egui::DragValue::new(&mut transform.position.y).speed(0.01));
+             });
+
+             let mut angle = Deg::from(Euler::from(transform.rotation).z).0;
+             ui.horizontal(|ui| {
+                ui.label("rotation");
+                if ui.add(egui::DragValue::new(&mut angle).speed(1.0).suffix("°")).changed() {
+                   *transform = transform.with_rotation_z(Deg(angle));
+                }
+             });
+
+             ui.horizontal(|ui| {
+                ui.label("scale");
+                ui.add(egui::DragValue::new(&mut transform.scale.x).speed(0.01));
+                ui.add(egui::DragValue::new(&mut transform.scale.y).speed(0.01));
+             });
+
+             for child in self.graph.nodes[id.0].children.clone() {
+                self.node_gui(ui, child);
+             }
+          });
+   }
+}
@@ -0,0 +1,318 @@
+use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::time::{SystemTime, UNIX_EPOCH};
+use egui::Context;
+use wgpu::{Device, Queue, Sampler, Texture, TextureFormat, TextureView};
+use crate::bundles::post_process::post_process_pipeline::PostProcessPipeline;
+use crate::inbuilt::readback::{Readback, ReadbackError};
+use crate::inbuilt::render_graph::{RenderGraph, Resource};
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
+use crate::packages::shader_package::ShaderPackage;
+
+
+#[derive(Debug)]
+pub enum ScreenshotError {
+   UnsupportedFormat(TextureFormat),
+   Readback(ReadbackError),
+   Io(std::io::Error),
+   Encode(png::EncodingError),
+}
+impl Display for ScreenshotError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         ScreenshotError::UnsupportedFormat(format) => write!(f, "can't save a {format:?} texture as png"),
+         ScreenshotError::Readback(err) => write!(f, "{err}"),
+         ScreenshotError::Io(err) => write!(f, "failed to write the screenshot: {err}"),
+         ScreenshotError::Encode(err) => write!(f, "failed to encode the png: {err}"),
+      }
+   }
+}
+impl std::error::Error for ScreenshotError {}
+impl From<ReadbackError> for ScreenshotError {
+   fn from(err: ReadbackError) -> Self {
+      ScreenshotError::Readback(err)
+   }
+}
+impl From<std::io::Error> for ScreenshotError {
+   fn from(err: std::io::Error) -> Self {
+      ScreenshotError::Io(err)
+   }
+}
+impl From<png::EncodingError> for ScreenshotError {
+   fn from(err: png::EncodingError) -> Self {
+      ScreenshotError::Encode(err)
+   }
+}
+
+
+/// tightly packed rgba8 rows, top row first
+pub struct CapturedImage {
+   pub width: u32,
+   pub height: u32,
+   pub rgba: Vec<u8>,
+}
+impl CapturedImage {
+   pub fn save_png(&self, path: impl AsRef<Path>) -> Result<(), ScreenshotError> {
+      if let Some(parent) = path.as_ref().parent() {
+         std::fs::create_dir_all(parent)?;
+      }
+
+      let file = std::io::BufWriter::new(std::fs::File::create(path)?);
+      let mut encoder = png::Encoder::new(file, self.width, self.height);
+      encoder.set_color(png::ColorType::Rgba);
+      encoder.set_depth(png::BitDepth::Eight);
+      encoder.set_source_srgb(png::SrgbRenderingIntent::Perceptual);
+
+      let mut writer = encoder.write_header()?;
+      writer.write_image_data(&self.rgba)?;
+      Ok(())
+   }
+}
+
+
+/// swizzles bgra data in place, rgba is left alone. the srgb formats already hold encoded bytes
+pub fn to_rgba8(format: TextureFormat, data: &mut [u8]) -> Result<(), ScreenshotError> {
+   match format {
+      TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => Ok(()),
+      TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => {
+         for pixel in data.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+         }
+         Ok(())
+      }
+      other => Err(ScreenshotError::UnsupportedFormat(other)),
+   }
+}
+
+/// copies `texture` back and blocks until it is there, the texture needs `COPY_SRC`.
+/// it only needs a device and queue, so it works the same for offscreen and headless renders
+pub fn read_texture(device: &Device, queue: &Queue, texture: &Texture) -> Result<CapturedImage, ScreenshotError> {
+   let format = texture.format();
+
+   // checked up front so nothing is copied for a format that can't be saved
+   to_rgba8(format, &mut [])?;
+
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("screenshot encoder") });
+   let readback = Readback::<u8>::texture(device, &mut encoder, texture)?;
+   queue.submit(std::iter::once(encoder.finish()));
+
+   let mut rgba = readback.map().wait(device)?;
+   to_rgba8(format, &mut rgba)?;
+
+   Ok(CapturedImage { width: texture.width(), height: texture.height(), rgba })
+}
+
+/// like `read_texture` without blocking, `callback` gets the image once the copy is done and the device is
+/// polled, like `Readback::then`. it runs on whatever thread polls, so anything slow belongs on another one
+pub fn read_texture_then(
+   device: &Device,
+   queue: &Queue,
+   texture: &Texture,
+   callback: impl FnOnce(Result<CapturedImage, ScreenshotError>) + Send + 'static,
+) -> Result<(), ScreenshotError> {
+   let (format, width, height) = (texture.format(), texture.width(), texture.height());
+   to_rgba8(format, &mut [])?;
+
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: Some("screenshot encoder") });
+   let readback = Readback::<u8>::texture(device, &mut encoder, texture)?;
+   queue.submit(std::iter::once(encoder.finish()));
+
+   readback.then(move |data| {
+      callback(data.map_err(ScreenshotError::from).and_then(|mut rgba| {
+         to_rgba8(format, &mut rgba)?;
+         Ok(CapturedImage { width, height, rgba })
+      }));
+   });
+   Ok(())
+}
+
+/// e.g. `screenshot_2024-05-01_13-37-00-123.png`, in utc
+pub fn timestamped_name(prefix: &str, time: SystemTime) -> String {
+   format!("{prefix}_{}.png", timestamp(time))
+}
+
+/// `2024-05-01_13-37-00-123` in utc, sorts the same as the time it was taken
+pub fn timestamp(time: SystemTime) -> String {
+   let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+   let seconds = since_epoch.as_secs() as i64;
+   let (year, month, day) = civil_from_days(seconds.div_euclid(86_400));
+   let of_day = seconds.rem_euclid(86_400);
+
+   format!(
+      "{year:04}-{month:02}-{day:02}_{:02}-{:02}-{:02}-{:03}",
+      of_day / 3600, of_day % 3600 / 60, of_day % 60, since_epoch.subsec_millis(),
+   )
+}
+
+/// days since 1970-01-01 to a (year, month, day) date, from Howard Hinnant's date algorithms
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+   let z = days + 719_468;
+   let era = z.div_euclid(146_097);
+   let day_of_era = z.rem_euclid(146_097);
+   let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+   let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+   let shifted_month = (5 * day_of_year + 2) / 153;
+   let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+   let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+
+   (year_of_era + era * 400 + (month <= 2) as i64, month as u32, day as u32)
+}
+
+
+struct CaptureTarget {
+   texture: Texture,
+   view: TextureView,
+}
+
+/// on request, draws one frame into an offscreen copy of the swapchain, puts it on screen with a blit
+/// and saves it as a png on a background thread
+pub struct ScreenshotPackage {
+   pub include_ui: bool,
+   pub directory: PathBuf,
+
+   requested: bool,
+   capture: Option<CaptureTarget>,
+   blit_pipeline: PostProcessPipeline,
+   sampler: Sampler,
+
+   saved_sender: Sender<Result<PathBuf, String>>,
+   saved_receiver: Receiver<Result<PathBuf, String>>,
+   status: Option<String>,
+}
+impl ScreenshotPackage {
+   pub fn new(setup: &Setup, shaders: &mut ShaderPackage) -> Self {
+      let blit_pipeline = shaders.create_or_embedded(&setup.device, "post/blit.wgsl", |shaders| PostProcessPipeline::new(
+         setup, shaders,
+         "post/blit.wgsl", include_str!("../shaders/post/blit.wgsl"),
+         ShaderDefines::new(), setup.config.format, &[],
+      ));
+
+      let sampler = setup.device.create_sampler(&wgpu::SamplerDescriptor {
+         label: Some("screenshot sampler"),
+         ..Default::default()
+      });
+
+      let (saved_sender, saved_receiver) = channel();
+
+      Self {
+         include_ui: true,
+         directory: PathBuf::from("screenshots"),
+
+         requested: false,
+         capture: None,
+         blit_pipeline,
+         sampler,
+
+         saved_sender,
+         saved_receiver,
+         status: None,
+      }
+   }
+
+   pub fn reload_shaders(&mut self, setup: &Setup, shaders: &mut ShaderPackage, changed: &[String]) {
+      let path = self.blit_pipeline.path;
+      if !changed.iter().any(|p| p == path) { return; }
+
+      match ShaderPackage::try_create(&setup.device, || self.blit_pipeline.rebuild(setup, shaders)) {
+         Ok(rebuilt) => self.blit_pipeline = rebuilt,
+         Err(err) => shaders.report_error(path, err),
+      }
+   }
+
+   pub fn request(&mut self) {
+      self.requested = true;
+   }
+
+   /// when a screenshot was requested, creates the texture this frame is drawn into instead of the swapchain
+   pub fn begin_frame(&mut self, setup: &Setup) -> bool {
+      if !std::mem::take(&mut self.requested) { return false; }
+
+      let texture = setup.device.create_texture(&wgpu::TextureDescriptor {
+         label: Some("screenshot target"),
+         size: wgpu::Extent3d { width: setup.config.width, height: setup.config.height, depth_or_array_layers: 1 },
+         mip_level_count: 1,
+         sample_count: 1,
+         dimension: wgpu::TextureDimension::D2,
+         format: setup.config.format,
+         usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC | wgpu::TextureUsages::TEXTURE_BINDING,
+         view_formats: &[],
+      });
+      let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+      self.capture = Some(CaptureTarget { texture, view });
+      true
+   }
+
+   pub fn target(&self) -> Option<&TextureView> {
+      self.capture.as_ref().map(|capture| &capture.view)
+   }
+
+   /// drops a capture whose frame wasn't submitted, the screenshot is taken on the next frame instead
+   pub fn cancel(&mut self) {
+      if self.capture.take().is_some() { self.requested = true; }
+   }
+
+   /// copies the captured frame onto the swapchain so it is still shown
+   pub fn add_present_pass<'a>(&'a self, graph: &mut RenderGraph<'a>) {
+      let Some(capture) = &self.capture else { return };
+
+      graph.add_pass("screenshot blit")
+          .write(Resource::Swapchain)
+          .run(move |ctx| {
+             let target = ctx.view(Resource::Swapchain)?;
+             self.blit_pipeline.pass(ctx, &capture.view, &[], &self.sampler, target);
+             Ok(())
+          });
+   }
+
+   /// starts reading the captured frame back once it has been submitted, it is saved when a later poll finds it done
+   pub fn end_frame(&mut self, setup: &Setup) {
+      let Some(capture) = self.capture.take() else { return };
+
+      let path = self.directory.join(timestamped_name("screenshot", SystemTime::now()));
+      let sender = self.saved_sender.clone();
+      let started = read_texture_then(&setup.device, &setup.queue, &capture.texture, move |image| {
+         // png encoding of a full frame is slow enough to notice, so it happens off the render thread
+         std::thread::spawn(move || {
+            let result = image.and_then(|image| image.save_png(&path)).map(|_| path).map_err(|err| err.to_string());
+            let _ = sender.send(result);
+         });
+      });
+
+      if let Err(err) = started {
+         log::error!("{err}");
+         self.status = Some(err.to_string());
+      }
+   }
+
+   pub fn gui(&mut self, ctx: &Context) {
+      while let Ok(result) = self.saved_receiver.try_recv() {
+         self.status = Some(match result {
+            Ok(path) => {
+               log::info!("saved screenshot to {}", path.display());
+               format!("saved {}", path.display())
+            }
+            Err(err) => {
+               log::error!("{err}");
+               err
+            }
+         });
+      }
+
+      egui::Window::new("Screenshot")
+          .default_open(false)
+          .resizable(false)
+          .show(ctx, |ui| {
+             if ui.add(egui::Button::new("screenshot (F12)")).clicked() {
+                self.request();
+             }
+             ui.add(egui::Checkbox::new(&mut self.include_ui, "include ui"));
+
+             if let Some(status) = &self.status {
+                ui.label(status);
+             }
+          });
+   }
+}
@@ -0,0 +1,301 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime};
+use egui::{Context, RichText};
+use wgpu::{Device, ShaderModule, ShaderModuleDescriptor};
+use naga::valid::{Capabilities, ValidationFlags, Validator};
+use crate::inbuilt::shader_preprocessor::{embedded_include, preprocess, Preprocessed, ShaderDefines};
+use crate::inbuilt::shader_reflection::ReflectedShader;
+
+
+const POLL_INTERVAL: f64 = 0.5;
+
+
+/// parses and validates wgsl with naga, errors are rendered with the file path and line numbers
+pub fn validate_wgsl(source: &str, path: &str) -> Result<naga::Module, String> {
+   ReflectedShader::from_wgsl(source, path).map(|shader| shader.module)
+}
+
+/// like `validate_wgsl`, but errors are reported as `file:line:column` of the file the code was written in
+/// rather than of the expanded source, followed by the offending line as it was expanded
+pub fn validate_preprocessed(processed: &Preprocessed, path: &str) -> Result<naga::Module, String> {
+   let source = &processed.source;
+   let located = |location: Option<naga::SourceLocation>, message: String| {
+      let origin = location.and_then(|location| {
+         let (file, line) = processed.origin(location.line_number as usize)?;
+         let expanded = source.lines().nth(location.line_number as usize - 1)?;
+         Some(format!("{file}:{line}:{}: {message}\n{}", location.line_position, expanded.trim_end()))
+      });
+      origin.unwrap_or_else(|| format!("{path}: {message}"))
+   };
+
+   let module = naga::front::wgsl::parse_str(source)
+       .map_err(|err| located(err.location(source), err.message().to_string()))?;
+
+   Validator::new(ValidationFlags::all(), Capabilities::all())
+       .validate(&module)
+       .map_err(|err| {
+          // the inner error only says what failed, the reason is further down the chain
+          let mut message = err.as_inner().to_string();
+          let mut cause = std::error::Error::source(err.as_inner());
+          while let Some(err) = cause {
+             message.push_str(&format!(": {err}"));
+             cause = err.source();
+          }
+          located(err.location(source), message)
+       })?;
+
+   Ok(module)
+}
+
+
+struct WatchedFile {
+   modified: Option<SystemTime>,
+   /// last version from disk that every shader using it compiled with
+   source: Option<String>,
+   previous: Option<String>,
+}
+
+/// what a top level shader was last built with, so it can be rebuilt when one of its files changes
+struct ShaderEntry {
+   embedded: &'static str,
+   defines: ShaderDefines,
+   files: Vec<String>,
+}
+
+
+/// hands out preprocessed shader sources, in dev mode files are read from `src/shaders` and polled for changes,
+/// otherwise (or when a file can't be read) the copies baked in with `include_str!` are used
+pub struct ShaderPackage {
+   pub dev_mode: bool,
+   root: PathBuf,
+   files: HashMap<String, WatchedFile>,
+   shaders: HashMap<String, ShaderEntry>,
+   errors: BTreeMap<String, String>,
+   last_poll: Instant,
+}
+impl ShaderPackage {
+   pub fn new(dev_mode: bool) -> Self {
+      Self {
+         dev_mode,
+         root: PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/shaders"),
+         files: HashMap::new(),
+         shaders: HashMap::new(),
+         errors: BTreeMap::new(),
+         last_poll: Instant::now(),
+      }
+   }
+
+   /// `path` is relative to `src/shaders`, e.g. "compute/game_of_life.wgsl". a file on disk that doesn't compile
+   /// is reported and the embedded copy is used, it is still watched so fixing it rebuilds the shader.
+   /// only fails when the embedded copy doesn't preprocess either
+   pub fn source(&mut self, path: &str, embedded: &'static str, defines: &ShaderDefines) -> Result<String, String> {
+      if !self.dev_mode {
+         return Self::embedded_source(path, embedded, defines);
+      }
+
+      let (built, files) = self.build(path, embedded, defines);
+      self.shaders.insert(path.to_string(), ShaderEntry { embedded, defines: defines.clone(), files });
+
+      match built {
+         Ok(source) => Ok(source),
+         Err(err) => {
+            self.errors.insert(path.to_string(), err);
+            Self::embedded_source(path, embedded, defines)
+         }
+      }
+   }
+
+   pub fn module(&mut self, device: &Device, path: &str, embedded: &'static str, defines: &ShaderDefines) -> Result<ShaderModule, String> {
+      let source = self.source(path, embedded, defines)?;
+
+      Ok(device.create_shader_module(ShaderModuleDescriptor {
+         label: Some(path),
+         source: wgpu::ShaderSource::Wgsl(source.into()),
+      }))
+   }
+
+   /// the module plus its reflection, for building bind group layouts from the shader itself
+   pub fn reflected_module(&mut self, device: &Device, path: &str, embedded: &'static str, defines: &ShaderDefines) -> Result<(ShaderModule, ReflectedShader), String> {
+      let source = self.source(path, embedded, defines)?;
+      let reflected = ReflectedShader::from_wgsl(&source, path)?;
+
+      let module = device.create_shader_module(ShaderModuleDescriptor {
+         label: Some(path),
+         source: wgpu::ShaderSource::Wgsl(source.into()),
+      });
+
+      Ok((module, reflected))
+   }
+
+   /// returns the top level shaders that need rebuilding because one of their files changed on disk
+   /// and everything using that file still compiles
+   pub fn poll(&mut self) -> Vec<String> {
+      if !self.dev_mode || self.last_poll.elapsed().as_secs_f64() < POLL_INTERVAL { return vec![]; }
+      self.last_poll = Instant::now();
+
+      let mut changed: Vec<String> = vec![];
+      let paths: Vec<String> = self.files.keys().cloned().collect();
+
+      for path in paths {
+         let modified = self.modified(&path);
+         if modified == self.files[&path].modified { continue; }
+         self.files.get_mut(&path).unwrap().modified = modified;
+
+         let source = match std::fs::read_to_string(self.root.join(&path)) {
+            Ok(source) => source,
+            Err(err) => {
+               self.errors.insert(path.clone(), format!("failed to read {path}: {err}"));
+               continue;
+            }
+         };
+
+         let file = self.files.get_mut(&path).unwrap();
+         file.previous = file.source.replace(source);
+
+         let dependents: Vec<String> = self.shaders.iter()
+             .filter(|(_, entry)| entry.files.contains(&path))
+             .map(|(shader, _)| shader.clone())
+             .collect();
+
+         let mut result = Ok(());
+         for shader in &dependents {
+            let entry = &self.shaders[shader];
+            let (built, files) = self.build(shader, entry.embedded, &entry.defines.clone());
+            // the includes can change with the file
+            self.shaders.get_mut(shader).unwrap().files = files;
+
+            if let Err(err) = built {
+               result = Err(err);
+               break;
+            }
+         }
+
+         match result {
+            Ok(()) => {
+               log::info!("reloaded shader {path}");
+               self.errors.remove(&path);
+               for shader in dependents {
+                  self.errors.remove(&shader);
+                  if !changed.contains(&shader) { changed.push(shader); }
+               }
+            }
+            Err(err) => {
+               log::warn!("shader {path} failed to compile, keeping the last good version");
+               self.rollback(&path);
+               self.errors.insert(path, err);
+            }
+         }
+      }
+
+      changed
+   }
+
+   /// for errors only found when wgpu builds the pipeline, e.g. a layout mismatch
+   pub fn report_error(&mut self, path: &str, error: String) {
+      log::warn!("pipeline for {path} failed to build, keeping the last good version");
+      self.errors.insert(path.to_string(), error);
+
+      // roll back so pipelines rebuilt later don't pick up the broken source
+      let files = self.shaders.get(path).map(|entry| entry.files.clone()).unwrap_or_default();
+      for file in files {
+         self.rollback(&file);
+      }
+   }
+
+   /// runs `create` inside a validation error scope so a bad pipeline is reported instead of panicking
+   pub fn try_create<T>(device: &Device, create: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+      device.push_error_scope(wgpu::ErrorFilter::Validation);
+      let created = create();
+
+      match pollster::block_on(device.pop_error_scope()) {
+         None => created,
+         Some(err) => Err(err.to_string()),
+      }
+   }
+
+   /// for pipelines built at startup, which have no last good version to keep. when the files on disk
+   /// don't build, that is reported and the pipeline is built from the embedded copies instead
+   pub fn create_or_embedded<T>(&mut self, device: &Device, path: &str, mut create: impl FnMut(&mut ShaderPackage) -> Result<T, String>) -> T {
+      let error = match Self::try_create(device, || create(self)) {
+         Ok(created) => return created,
+         Err(err) => err,
+      };
+      self.report_error(path, error);
+
+      let dev_mode = std::mem::replace(&mut self.dev_mode, false);
+      let created = Self::try_create(device, || create(self));
+      self.dev_mode = dev_mode;
+
+      created.unwrap_or_else(|err| panic!("embedded shader {path} failed to build: {err}"))
+   }
+
+   pub fn gui(&self, ctx: &Context) {
+      if self.errors.is_empty() { return; }
+
+      egui::Window::new("Shader errors")
+          .default_open(true)
+          .resizable(true)
+          .show(ctx, |ui| {
+             egui::ScrollArea::vertical().show(ui, |ui| {
+                for (path, error) in &self.errors {
+                   ui.label(RichText::new(path).strong());
+                   ui.label(RichText::new(error).monospace());
+                   ui.separator();
+                }
+             });
+          });
+   }
+
+   /// preprocesses and validates `path` with the current files, returns the source and every file it used.
+   /// the files are returned when it fails too, as far as the preprocessor got
+   fn build(&mut self, path: &str, embedded: &'static str, defines: &ShaderDefines) -> (Result<String, String>, Vec<String>) {
+      let top = self.file_source(path).unwrap_or_else(|| embedded.to_string());
+
+      let mut files = vec![path.to_string()];
+      let processed = preprocess(path, &top, defines, &mut |include| {
+         if !files.iter().any(|file| file == include) { files.push(include.to_string()); }
+         self.file_source(include).or_else(|| embedded_include(include))
+      });
+
+      let built = processed
+          .map_err(|err| err.to_string())
+          .and_then(|processed| {
+             validate_preprocessed(&processed, path)?;
+             Ok(processed.source)
+          });
+      (built, files)
+   }
+
+   fn embedded_source(path: &str, embedded: &'static str, defines: &ShaderDefines) -> Result<String, String> {
+      preprocess(path, embedded, defines, &mut embedded_include)
+          .map(|processed| processed.source)
+          .map_err(|err| format!("embedded shader failed to preprocess: {err}"))
+   }
+
+   /// the current good copy of a file on disk, starts watching it the first time it is asked for
+   fn file_source(&mut self, path: &str) -> Option<String> {
+      if !self.files.contains_key(path) {
+         let file = WatchedFile {
+            modified: self.modified(path),
+            source: std::fs::read_to_string(self.root.join(path)).ok(),
+            previous: None,
+         };
+         self.files.insert(path.to_string(), file);
+      }
+
+      self.files[path].source.clone()
+   }
+
+   fn rollback(&mut self, path: &str) {
+      if let Some(file) = self.files.get_mut(path) {
+         if file.previous.is_some() {
+            file.source = file.previous.take();
+         }
+      }
+   }
+
+   fn modified(&self, path: &str) -> Option<SystemTime> {
+      std::fs::metadata(self.root.join(path)).and_then(|m| m.modified()).ok()
+   }
+}
@@ -0,0 +1,277 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use wgpu::{AddressMode, BindGroup, BindGroupLayout, Device, FilterMode, Queue, Sampler, Texture, TextureView};
+use zune_jpeg::zune_core::colorspace::ColorSpace;
+use zune_jpeg::zune_core::options::DecoderOptions;
+
+
+#[derive(Debug)]
+pub enum TextureError {
+   Io(std::io::Error),
+   Png(png::DecodingError),
+   Jpeg(zune_jpeg::errors::DecodeErrors),
+   UnknownFormat,
+   /// width and height of the image, and the most the device allows on either side
+   TooLarge(u32, u32, u32),
+}
+impl Display for TextureError {
+   fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+      match self {
+         TextureError::Io(err) => write!(f, "failed to read the image: {err}"),
+         TextureError::Png(err) => write!(f, "failed to decode the png: {err}"),
+         TextureError::Jpeg(err) => write!(f, "failed to decode the jpeg: {err}"),
+         TextureError::UnknownFormat => write!(f, "only png and jpeg images are supported"),
+         TextureError::TooLarge(width, height, max) => write!(f, "a {width}x{height} image doesn't fit in a texture, at most {max} pixels per side"),
+      }
+   }
+}
+impl std::error::Error for TextureError {}
+impl From<std::io::Error> for TextureError {
+   fn from(err: std::io::Error) -> Self {
+      TextureError::Io(err)
+   }
+}
+impl From<png::DecodingError> for TextureError {
+   fn from(err: png::DecodingError) -> Self {
+      TextureError::Png(err)
+   }
+}
+impl From<zune_jpeg::errors::DecodeErrors> for TextureError {
+   fn from(err: zune_jpeg::errors::DecodeErrors) -> Self {
+      TextureError::Jpeg(err)
+   }
+}
+
+
+/// 8 bit srgb rgba, top row first
+#[derive(Clone, Debug, PartialEq)]
+pub struct DecodedImage {
+   pub width: u32,
+   pub height: u32,
+   pub rgba: Vec<u8>,
+}
+
+/// png or jpeg, told apart by their signature rather than the file name
+pub fn decode_image(bytes: &[u8]) -> Result<DecodedImage, TextureError> {
+   if bytes.starts_with(b"\x89PNG\r\n\x1a\n") { return decode_png(bytes); }
+   if bytes.starts_with(&[0xff, 0xd8, 0xff]) { return decode_jpeg(bytes); }
+   Err(TextureError::UnknownFormat)
+}
+
+fn decode_png(bytes: &[u8]) -> Result<DecodedImage, TextureError> {
+   let mut decoder = png::Decoder::new(bytes);
+   decoder.set_transformations(png::Transformations::EXPAND | png::Transformations::STRIP_16);
+
+   let mut reader = decoder.read_info()?;
+   let mut data = vec![0; reader.output_buffer_size()];
+   let info = reader.next_frame(&mut data)?;
+   data.truncate(info.buffer_size());
+
+   let rgba = match info.color_type {
+      png::ColorType::Rgba => data,
+      png::ColorType::Rgb => data.chunks_exact(3).flat_map(|p| [p[0], p[1], p[2], 255]).collect(),
+      png::ColorType::GrayscaleAlpha => data.chunks_exact(2).flat_map(|p| [p[0], p[0], p[0], p[1]]).collect(),
+      // indexed is expanded to rgb(a) by the EXPAND transformation
+      png::ColorType::Grayscale | png::ColorType::Indexed => data.iter().flat_map(|&v| [v, v, v, 255]).collect(),
+   };
+
+   Ok(DecodedImage { width: info.width, height: info.height, rgba })
+}
+
+fn decode_jpeg(bytes: &[u8]) -> Result<DecodedImage, TextureError> {
+   let options = DecoderOptions::default().jpeg_set_out_colorspace(ColorSpace::RGBA);
+   let mut decoder = zune_jpeg::JpegDecoder::new_with_options(bytes, options);
+
+   let rgba = decoder.decode()?;
+   let (width, height) = decoder.dimensions().ok_or(TextureError::UnknownFormat)?;
+
+   Ok(DecodedImage { width: width as u32, height: height as u32, rgba })
+}
+
+
+pub fn mip_level_count(width: u32, height: u32) -> u32 {
+   32 - width.max(height).max(1).leading_zeros()
+}
+
+/// every mip level from the full image down to 1x1. texels are averaged in linear space so
+/// the smaller levels don't get darker than the image
+pub fn mip_chain(image: &DecodedImage) -> Vec<DecodedImage> {
+   let to_linear: Vec<f32> = (0..=255u8).map(|v| srgb_to_linear(v as f32 / 255.0)).collect();
+
+   let mut levels = vec![image.clone()];
+   while let Some(last) = levels.last().filter(|last| last.width > 1 || last.height > 1) {
+      let (width, height) = ((last.width / 2).max(1), (last.height / 2).max(1));
+      let mut rgba = Vec::with_capacity((width * height * 4) as usize);
+
+      for y in 0..height {
+         for x in 0..width {
+            // odd sizes clamp, so the last row or column is counted twice instead of read past
+            let xs = [(x * 2).min(last.width - 1), (x * 2 + 1).min(last.width - 1)];
+            let ys = [(y * 2).min(last.height - 1), (y * 2 + 1).min(last.height - 1)];
+
+            let mut sum = [0.0f32; 4];
+            for sy in ys {
+               for sx in xs {
+                  let i = ((sy * last.width + sx) * 4) as usize;
+                  for c in 0..3 { sum[c] += to_linear[last.rgba[i + c] as usize]; }
+                  sum[3] += last.rgba[i + 3] as f32 / 255.0;
+               }
+            }
+
+            for linear in &sum[..3] { rgba.push((linear_to_srgb(linear / 4.0) * 255.0).round() as u8); }
+            rgba.push((sum[3] / 4.0 * 255.0).round() as u8);
+         }
+      }
+
+      levels.push(DecodedImage { width, height, rgba });
+   }
+   levels
+}
+
+fn srgb_to_linear(c: f32) -> f32 {
+   if c <= 0.04045 { c / 12.92 } else { ((c + 0.055) / 1.055).powf(2.4) }
+}
+
+fn linear_to_srgb(c: f32) -> f32 {
+   if c <= 0.0031308 { c * 12.92 } else { 1.055 * c.powf(1.0 / 2.4) - 0.055 }
+}
+
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SamplerSettings {
+   pub filter: FilterMode,
+   pub address_mode: AddressMode,
+}
+impl Default for SamplerSettings {
+   fn default() -> Self {
+      Self::linear()
+   }
+}
+impl SamplerSettings {
+   /// blocky, for pixel art and cell grids
+   pub fn nearest() -> Self {
+      Self { filter: FilterMode::Nearest, address_mode: AddressMode::ClampToEdge }
+   }
+
+   pub fn linear() -> Self {
+      Self { filter: FilterMode::Linear, address_mode: AddressMode::ClampToEdge }
+   }
+
+   pub fn repeat(mut self) -> Self {
+      self.address_mode = AddressMode::Repeat;
+      self
+   }
+
+   pub fn create(&self, device: &Device, label: &str) -> Sampler {
+      device.create_sampler(&wgpu::SamplerDescriptor {
+         label: Some(label),
+         address_mode_u: self.address_mode,
+         address_mode_v: self.address_mode,
+         address_mode_w: self.address_mode,
+         mag_filter: self.filter,
+         min_filter: self.filter,
+         mipmap_filter: self.filter,
+         ..Default::default()
+      })
+   }
+}
+
+
+/// a sampled srgb texture with mipmaps and its sampler, bound as `t_diffuse` at 0 and `s_diffuse` at 1
+pub struct TexturePackage {
+   pub texture: Texture,
+   pub view: TextureView,
+   pub sampler: Sampler,
+   pub bind_group: BindGroup,
+   pub width: u32,
+   pub height: u32,
+}
+impl TexturePackage {
+   pub fn load(device: &Device, queue: &Queue, layout: &BindGroupLayout, path: impl AsRef<Path>, sampler: SamplerSettings) -> Result<Self, TextureError> {
+      let path = path.as_ref();
+      let image = decode_image(&std::fs::read(path)?)?;
+      Self::from_image(device, queue, layout, &path.to_string_lossy(), &image, sampler)
+   }
+
+   /// fails when the image is bigger than the device's `max_texture_dimension_2d`
+   pub fn from_image(device: &Device, queue: &Queue, layout: &BindGroupLayout, label: &str, image: &DecodedImage, sampler: SamplerSettings) -> Result<Self, TextureError> {
+      let max = device.limits().max_texture_dimension_2d;
+      if image.width > max || image.height > max {
+         return Err(TextureError::TooLarge(image.width, image.height, max));
+      }
+
+      let levels = mip_chain(image);
+
+      let texture = device.create_texture(&wgpu::TextureDescriptor {
+         label: Some(label),
+         size: wgpu::Extent3d { width: image.width, height: image.height, depth_or_array_layers: 1 },
+         mip_level_count: levels.len() as u32,
+         sample_count: 1,
+         dimension: wgpu::TextureDimension::D2,
+         format: wgpu::TextureFormat::Rgba8UnormSrgb,
+         usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+         view_formats: &[],
+      });
+
+      for (mip_level, level) in levels.iter().enumerate() {
+         queue.write_texture(
+            wgpu::ImageCopyTexture {
+               texture: &texture,
+               mip_level: mip_level as u32,
+               origin: wgpu::Origin3d::ZERO,
+               aspect: wgpu::TextureAspect::All,
+            },
+            &level.rgba,
+            wgpu::ImageDataLayout {
+               offset: 0,
+               bytes_per_row: Some(level.width * 4),
+               rows_per_image: Some(level.height),
+            },
+            wgpu::Extent3d { width: level.width, height: level.height, depth_or_array_layers: 1 },
+         );
+      }
+
+      let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+      let sampler = sampler.create(device, label);
+
+      let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+         layout,
+         entries: &[
+            wgpu::BindGroupEntry { binding: 0, resource: wgpu::BindingResource::TextureView(&view) },
+            wgpu::BindGroupEntry { binding: 1, resource: wgpu::BindingResource::Sampler(&sampler) },
+         ],
+         label: Some(label),
+      });
+
+      Ok(Self { texture, view, sampler, bind_group, width: image.width, height: image.height })
+   }
+
+   pub fn bind_group_layout_entries() -> [wgpu::BindGroupLayoutEntry; 2] {
+      [
+         wgpu::BindGroupLayoutEntry {
+            binding: 0,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Texture {
+               sample_type: wgpu::TextureSampleType::Float { filterable: true },
+               view_dimension: wgpu::TextureViewDimension::D2,
+               multisampled: false,
+            },
+            count: None,
+         },
+         wgpu::BindGroupLayoutEntry {
+            binding: 1,
+            visibility: wgpu::ShaderStages::FRAGMENT,
+            ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+            count: None,
+         },
+      ]
+   }
+
+   /// one layout works for every texture package, create it once and share it
+   pub fn bind_group_layout(device: &Device) -> BindGroupLayout {
+      device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+         entries: &Self::bind_group_layout_entries(),
+         label: Some("texture_bind_group_layout"),
+      })
+   }
+}
@@ -3,7 +3,6 @@ use egui::Ui;
 
 
 const UPDATE_INTERVAL: f64 = 0.5;
-const PAST_FPS_AMOUNT: usize = 10;
 const PAST_FPS_LIMIT: usize = 1000;
 
 
@@ -18,6 +17,11 @@ pub struct TimePackage {
 
    timers: Vec<Timer>,
 }
+impl Default for TimePackage {
+   fn default() -> Self {
+      Self::new()
+   }
+}
 impl TimePackage {
    pub fn new() -> Self {
       Self {
@@ -49,6 +53,11 @@ impl TimePackage {
       self.last_frame = Instant::now();
    }
 
+   /// seconds since the package was created
+   pub fn elapsed(&self) -> f64 {
+      self.start_time.elapsed().as_secs_f64()
+   }
+
    pub fn add_timer(&mut self, timer: Timer) {
       self.timers.push(timer);
    }
@@ -0,0 +1,146 @@
+use std::mem::size_of;
+use std::num::NonZeroU64;
+use bytemuck::Pod;
+use wgpu::{BindGroup, BindGroupLayout, BindGroupLayoutEntry, Buffer, Device, Queue, ShaderStages};
+
+
+/// size of one element in an array of dynamic offset uniforms, offsets have to be a multiple of `alignment`
+pub fn aligned_stride(size: usize, alignment: u32) -> u64 {
+   (size as u64).next_multiple_of(alignment as u64)
+}
+
+/// one uniform, or an array of them bound with dynamic offsets, in its own buffer and bind group.
+/// the cpu copy is only uploaded when it changed
+pub struct UniformPackage<T: Pod> {
+   values: Vec<T>,
+   dirty: bool,
+   stride: u64,
+
+   buffer: Buffer,
+   pub bind_group_layout: BindGroupLayout,
+   pub bind_group: BindGroup,
+}
+impl<T: Pod> UniformPackage<T> {
+   pub fn new(device: &Device, label: &str, visibility: ShaderStages, value: T) -> Self {
+      Self::create(device, label, visibility, vec![value], false)
+   }
+
+   /// `values.len()` uniforms in one buffer, pick one per draw with `offset(index)` in `set_bind_group`
+   pub fn new_array(device: &Device, label: &str, visibility: ShaderStages, values: Vec<T>) -> Self {
+      assert!(!values.is_empty(), "{label} needs at least one element");
+      Self::create(device, label, visibility, values, true)
+   }
+
+   fn create(device: &Device, label: &str, visibility: ShaderStages, values: Vec<T>, dynamic: bool) -> Self {
+      let stride = match dynamic {
+         true => aligned_stride(size_of::<T>(), device.limits().min_uniform_buffer_offset_alignment),
+         false => size_of::<T>() as u64,
+      };
+
+      let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+         label: Some(label),
+         size: stride * values.len() as u64,
+         usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+         mapped_at_creation: false,
+      });
+
+      let bind_group_layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+         entries: &[Self::layout_entry(visibility, dynamic)],
+         label: Some(label),
+      });
+
+      let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+         layout: &bind_group_layout,
+         entries: &[
+            wgpu::BindGroupEntry {
+               binding: 0,
+               resource: wgpu::BindingResource::Buffer(wgpu::BufferBinding {
+                  buffer: &buffer,
+                  offset: 0,
+                  size: NonZeroU64::new(size_of::<T>() as u64),
+               }),
+            }
+         ],
+         label: Some(label),
+      });
+
+      Self { values, dirty: true, stride, buffer, bind_group_layout, bind_group }
+   }
+
+   /// the entry at binding 0 this package creates its layout from
+   pub fn layout_entry(visibility: ShaderStages, dynamic: bool) -> BindGroupLayoutEntry {
+      BindGroupLayoutEntry {
+         binding: 0,
+         visibility,
+         ty: wgpu::BindingType::Buffer {
+            ty: wgpu::BufferBindingType::Uniform,
+            has_dynamic_offset: dynamic,
+            min_binding_size: NonZeroU64::new(size_of::<T>() as u64),
+         },
+         count: None,
+      }
+   }
+
+   pub fn get(&self) -> &T {
+      &self.values[0]
+   }
+
+   pub fn get_at(&self, index: usize) -> &T {
+      &self.values[index]
+   }
+
+   /// marks the value as changed, `set` only does when the bytes differ
+   pub fn get_mut(&mut self) -> &mut T {
+      self.get_mut_at(0)
+   }
+
+   pub fn get_mut_at(&mut self, index: usize) -> &mut T {
+      self.dirty = true;
+      &mut self.values[index]
+   }
+
+   pub fn set(&mut self, value: T) {
+      self.set_at(0, value);
+   }
+
+   pub fn set_at(&mut self, index: usize, value: T) {
+      if bytemuck::bytes_of(&self.values[index]) != bytemuck::bytes_of(&value) {
+         self.values[index] = value;
+         self.dirty = true;
+      }
+   }
+
+   pub fn len(&self) -> usize {
+      self.values.len()
+   }
+
+   pub fn is_empty(&self) -> bool {
+      self.values.is_empty()
+   }
+
+   /// the dynamic offset of element `index`
+   pub fn offset(&self, index: usize) -> u32 {
+      (self.stride * index as u64) as u32
+   }
+
+   pub fn is_dirty(&self) -> bool {
+      self.dirty
+   }
+
+   /// writes the buffer if anything changed since the last upload, returns whether it did
+   pub fn upload(&mut self, queue: &Queue) -> bool {
+      if !std::mem::take(&mut self.dirty) { return false; }
+
+      if self.values.len() == 1 {
+         queue.write_buffer(&self.buffer, 0, bytemuck::bytes_of(&self.values[0]));
+      }
+      else {
+         let mut bytes = vec![0u8; (self.stride * self.values.len() as u64) as usize];
+         for (value, chunk) in self.values.iter().zip(bytes.chunks_mut(self.stride as usize)) {
+            chunk[..size_of::<T>()].copy_from_slice(bytemuck::bytes_of(value));
+         }
+         queue.write_buffer(&self.buffer, 0, &bytes);
+      }
+      true
+   }
+}
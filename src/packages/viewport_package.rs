@@ -0,0 +1,167 @@
+use cgmath::{Vector2, Vector3};
+use egui::{Context, Rect, Sense, TextureId, Ui, Vec2};
+use wgpu::{FilterMode, Texture, TextureView};
+use crate::egui::gui::EguiRenderer;
+use crate::inbuilt::render_graph::{RenderGraph, Resource};
+use crate::inbuilt::setup::Setup;
+use crate::packages::camera_package::{CameraPackage, OrthographicCamera};
+use crate::packages::input_manager_package::ScreenRegion;
+
+
+/// a panel of `size` points as a texture, at least a pixel each way
+pub fn pixel_size(size: Vec2, pixels_per_point: f32) -> (u32, u32) {
+   let pixels = size * pixels_per_point;
+   (pixels.x.round().max(1.0) as u32, pixels.y.round().max(1.0) as u32)
+}
+
+/// a rect in points as the window pixels it covers
+pub fn screen_region(rect: Rect, pixels_per_point: f32) -> ScreenRegion {
+   ScreenRegion {
+      min: Vector2::new(rect.min.x, rect.min.y) * pixels_per_point,
+      size: Vector2::new(rect.width(), rect.height()) * pixels_per_point,
+   }
+}
+
+
+struct ViewportTarget {
+   #[allow(dead_code)]
+   texture: Texture,
+   view: TextureView,
+   size: (u32, u32),
+   id: TextureId,
+}
+
+/// the docked layout, controls in a side panel and the scene in the central one. the scene is drawn
+/// into an offscreen texture in the swapchain's format that egui shows, any graph executed into
+/// `target()` ends up there. the viewport has its own camera, its aspect follows the panel
+pub struct ViewportPackage {
+   pub enabled: bool,
+   pub camera: CameraPackage,
+   target: Option<ViewportTarget>,
+   /// where the panel was laid out this frame, in points
+   rect: Option<Rect>,
+   pixels_per_point: f32,
+}
+impl ViewportPackage {
+   pub fn new(setup: &Setup) -> Self {
+      let camera = CameraPackage::new(&setup.device, OrthographicCamera {
+         eye: (0.0, 0.0, 1.0).into(),
+         target: (0.0, 0.0, 0.0).into(),
+         up: Vector3::unit_y(),
+         aspect: 1.0,
+         zoom: 1.0,
+      });
+
+      Self {
+         enabled: false,
+         camera,
+         target: None,
+         rect: None,
+         pixels_per_point: 1.0,
+      }
+   }
+
+   /// `None` while the layout isn't docked or before the panel was shown once
+   pub fn target(&self) -> Option<(&TextureView, (u32, u32))> {
+      self.target.as_ref().map(|target| (&target.view, target.size))
+   }
+
+   /// where the scene is on screen, in points
+   pub fn rect(&self) -> Option<Rect> {
+      self.rect
+   }
+
+   /// the same in pixels, for `InputManager::viewport`
+   pub fn region(&self) -> Option<ScreenRegion> {
+      self.rect.map(|rect| screen_region(rect, self.pixels_per_point))
+   }
+
+   /// lays out the docked layout while enabled, `sidebar` fills the side panel. windows still float above it
+   pub fn gui(&mut self, ctx: &Context, sidebar: impl FnOnce(&mut Ui)) {
+      self.rect = None;
+      if !self.enabled { return; }
+
+      egui::SidePanel::left("viewport sidebar")
+          .resizable(true)
+          .default_width(320.0)
+          .show(ctx, |ui| {
+             egui::ScrollArea::vertical().show(ui, sidebar);
+          });
+
+      egui::CentralPanel::default()
+          .frame(egui::Frame::none())
+          .show(ctx, |ui| {
+             let (rect, _) = ui.allocate_exact_size(ui.available_size(), Sense::hover());
+
+             // last frame's texture, stretched for the one frame after a resize
+             if let Some(target) = &self.target {
+                egui::Image::new((target.id, rect.size())).paint_at(ui, rect);
+             }
+             self.rect = Some(rect);
+          });
+
+      self.pixels_per_point = ctx.pixels_per_point();
+   }
+
+   /// recreates the texture when the panel changed size, and frees it when the layout isn't docked.
+   /// call after the ui ran and before anything is rendered
+   pub fn prepare(&mut self, setup: &Setup, egui: &mut EguiRenderer) {
+      let Some(rect) = self.rect else {
+         if let Some(target) = self.target.take() {
+            egui.free_native_texture(target.id);
+         }
+         return;
+      };
+
+      let size = pixel_size(rect.size(), self.pixels_per_point);
+      if self.target.as_ref().is_some_and(|target| target.size == size) { return; }
+
+      let texture = setup.device.create_texture(&wgpu::TextureDescriptor {
+         label: Some("viewport target"),
+         size: wgpu::Extent3d { width: size.0, height: size.1, depth_or_array_layers: 1 },
+         mip_level_count: 1,
+         sample_count: 1,
+         dimension: wgpu::TextureDimension::D2,
+         format: setup.config.format,
+         usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::TEXTURE_BINDING,
+         view_formats: &[],
+      });
+      let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+
+      let id = match self.target.take() {
+         Some(old) => {
+            egui.update_native_texture(&setup.device, &view, FilterMode::Linear, old.id);
+            old.id
+         }
+         None => egui.register_native_texture(&setup.device, &view, FilterMode::Linear),
+      };
+
+      let aspect = size.0 as f32 / size.1 as f32;
+      self.camera.camera.aspect = aspect;
+      self.camera.write_with_aspect(&setup.queue, aspect);
+      self.target = Some(ViewportTarget { texture, view, size, id });
+   }
+
+   /// clears the window under the docked ui, nothing else draws onto it
+   pub fn add_clear_pass(graph: &mut RenderGraph) {
+      graph.add_pass("viewport clear")
+          .write(Resource::Swapchain)
+          .run(|ctx| {
+             ctx.encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+                label: Some("viewport clear"),
+                color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                   view: ctx.view(Resource::Swapchain)?,
+                   resolve_target: None,
+                   ops: wgpu::Operations {
+                      load: wgpu::LoadOp::Clear(wgpu::Color::BLACK),
+                      store: wgpu::StoreOp::Store,
+                   },
+                })],
+                depth_stencil_attachment: None,
+                timestamp_writes: None,
+                occlusion_query_set: None,
+             });
+             Ok(())
+          });
+   }
+}
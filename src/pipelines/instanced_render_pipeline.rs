@@ -0,0 +1,116 @@
+use cgmath::{Quaternion, Rotation3, Vector3};
+use wgpu::{CommandEncoder, RenderPipeline, TextureFormat, TextureView};
+use crate::inbuilt::instance_package::{Instance, InstancePackage, InstanceRaw};
+use crate::inbuilt::pipeline_builder::RenderPipelineBuilder;
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
+use crate::inbuilt::vertex_library::{SQUARE_INDICES, TEXTURED_SQUARE_VERTICES};
+use crate::inbuilt::vertex_package::{TexturedVertex, VertexLayout, VertexPackage};
+use crate::packages::camera_package::CameraPackage;
+use crate::packages::globals_package::GlobalsPackage;
+use crate::packages::shader_package::ShaderPackage;
+
+/// one quad drawn once per instance, alpha blended over what is already in the target
+pub struct InstancedRenderPipeline {
+   vertex_package: VertexPackage<TexturedVertex>,
+   pub instance_package: InstancePackage,
+   render_pipeline: RenderPipeline,
+}
+impl InstancedRenderPipeline {
+   pub const SHADER_PATH: &'static str = "render/instanced_render.wgsl";
+
+   /// the globals are bound at group 1
+   pub fn new(
+      setup: &Setup,
+      camera_package: &CameraPackage,
+      globals_package: &GlobalsPackage,
+      format: TextureFormat,
+      instances: &[Instance],
+      shaders: &mut ShaderPackage,
+   ) -> Result<Self, String> {
+      let vertex_package = VertexPackage::new(&setup.device, TEXTURED_SQUARE_VERTICES, SQUARE_INDICES);
+      let instance_package = InstancePackage::new(&setup.device, instances);
+
+      let shader = shaders.module(&setup.device, Self::SHADER_PATH, include_str!("../shaders/render/instanced_render.wgsl"), &ShaderDefines::new().with("GLOBALS_GROUP", 1))?;
+
+      let render_pipeline = RenderPipelineBuilder::new(setup, &shader)
+          .label(Self::SHADER_PATH)
+          .bind_group_layout(camera_package.bind_group_layout())
+          .bind_group_layout(globals_package.bind_group_layout())
+          .vertex_layout(TexturedVertex::desc())
+          .vertex_layout(InstanceRaw::desc())
+          .format(format)
+          .blend(Some(wgpu::BlendState::ALPHA_BLENDING))
+          .cull_mode(None)
+          .sample_count(1)
+          .build(&setup.device);
+
+
+      Ok(Self {
+         vertex_package,
+         instance_package,
+         render_pipeline,
+      })
+   }
+
+   /// `columns` x `rows` small quads centered on the origin, colored by their position in the grid
+   pub fn grid_instances(columns: u32, rows: u32, spacing: f32) -> Vec<Instance> {
+      let mut instances = Vec::with_capacity((columns * rows) as usize);
+
+      for y in 0..rows {
+         for x in 0..columns {
+            let u = x as f32 / columns.max(1) as f32;
+            let v = y as f32 / rows.max(1) as f32;
+
+            instances.push(Instance {
+               position: Vector3::new(
+                  (x as f32 - columns as f32 / 2.0) * spacing,
+                  (y as f32 - rows as f32 / 2.0) * spacing,
+                  0.0,
+               ),
+               rotation: Quaternion::from_angle_z(cgmath::Rad(u * std::f32::consts::TAU)),
+               scale: Vector3::new(spacing * 0.4, spacing * 0.4, 1.0),
+               color: [u, v, 1.0 - u, 1.0],
+            });
+         }
+      }
+
+      instances
+   }
+
+   pub fn render_pass(
+      &self, encoder: &mut CommandEncoder,
+      view: &TextureView,
+      camera_package: &CameraPackage,
+      globals_package: &GlobalsPackage,
+   ) {
+      let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+         label: Some("Instanced Render Pass"),
+         color_attachments: &[
+            Some(wgpu::RenderPassColorAttachment {
+               view,
+               resolve_target: None,
+               ops: wgpu::Operations {
+                  load: wgpu::LoadOp::Load,
+                  store: wgpu::StoreOp::Store,
+               }
+            })
+         ],
+         depth_stencil_attachment: None,
+         occlusion_query_set: None,
+         timestamp_writes: None,
+      });
+
+      render_pass.set_pipeline(&self.render_pipeline);
+
+      render_pass.set_bind_group(0, camera_package.bind_group(), &[]);
+      render_pass.set_bind_group(1, globals_package.bind_group(), &[]);
+
+      render_pass.set_vertex_buffer(0, self.vertex_package.vertex_buffer.slice(..));
+      render_pass.set_vertex_buffer(1, self.instance_package.instance_buffer.slice(..));
+      render_pass.set_index_buffer(self.vertex_package.index_buffer.slice(..), self.vertex_package.index_format);
+
+      // every instance in one call
+      render_pass.draw_indexed(0..self.vertex_package.num_indices, 0, 0..self.instance_package.num_instances);
+   }
+}
@@ -1,78 +1,31 @@
-use wgpu::{Color, CommandEncoder, IndexFormat, RenderPipeline, TextureView};
+use wgpu::{Color, CommandEncoder, RenderPipeline, TextureView};
+use crate::inbuilt::pipeline_builder::RenderPipelineBuilder;
 use crate::inbuilt::setup::Setup;
-use crate::inbuilt::vertex_library::{SQUARE_INDICES, SQUARE_VERTICES};
-use crate::inbuilt::vertex_package::{Vertex, VertexPackage};
+use crate::inbuilt::shader_preprocessor::{embedded_module, PreprocessError, ShaderDefines};
+use crate::inbuilt::vertex_library::{SQUARE_INDICES, TEXTURED_SQUARE_VERTICES};
+use crate::inbuilt::vertex_package::{TexturedVertex, VertexLayout, VertexPackage};
 use crate::packages::camera_package::{CameraPackage};
 
 pub struct TestRenderPipeline {
-   vertex_package: VertexPackage,
+   vertex_package: VertexPackage<TexturedVertex>,
    render_pipeline: RenderPipeline,
 }
 impl TestRenderPipeline {
-   pub fn new(setup: &Setup, camera_package: &CameraPackage, ) -> Self {
-      let vertex_package = VertexPackage::new(&setup.device, SQUARE_VERTICES, SQUARE_INDICES);
+   pub fn new(setup: &Setup, camera_package: &CameraPackage, ) -> Result<Self, PreprocessError> {
+      let vertex_package = VertexPackage::new(&setup.device, TEXTURED_SQUARE_VERTICES, SQUARE_INDICES);
 
-      // Render pipeline
-      let render_pipeline_layout = setup.device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-         label: Some("Render Pipeline Layout"),
-         bind_group_layouts: &[
-            &camera_package.camera_bind_group_layout,
-         ],
-         push_constant_ranges: &[],
-      });
-
-      let shader = setup.device.create_shader_module(wgpu::include_wgsl!("../shaders/render/test_render_pipeline.wgsl"));
-
-      let render_pipeline = setup.device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-         label: Some("Render Pipeline"),
-         layout: Some(&render_pipeline_layout),
-
-         vertex: wgpu::VertexState {
-            module: &shader,
-            entry_point: "vs_main", // 1.
-            buffers: &[
-               Vertex::desc(),
-            ], // 2.
-         },
+      let shader = embedded_module(&setup.device, "render/test_render_pipeline.wgsl", include_str!("../shaders/render/test_render_pipeline.wgsl"), &ShaderDefines::new())?;
 
-         fragment: Some(wgpu::FragmentState { // 3.
-            module: &shader,
-            entry_point: "fs_main",
-            targets: &[Some(wgpu::ColorTargetState { // 4.
-               format: setup.config.format,
-               blend: Some(wgpu::BlendState::REPLACE),
-               write_mask: wgpu::ColorWrites::ALL,
-            })],
-         }),
-
-         primitive: wgpu::PrimitiveState {
-            topology: wgpu::PrimitiveTopology::TriangleList, // 1.
-            strip_index_format: None,
-            front_face: wgpu::FrontFace::Ccw, // 2.
-            cull_mode: Some(wgpu::Face::Back),
-            // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-            polygon_mode: wgpu::PolygonMode::Fill,
-            // Requires Features::DEPTH_CLIP_CONTROL
-            unclipped_depth: false,
-            // Requires Features::CONSERVATIVE_RASTERIZATION
-            conservative: false,
-         },
-
-         depth_stencil: None, // 1.
-         multisample: wgpu::MultisampleState {
-            count: 1, // 2.
-            mask: !0, // 3. returns a bit array of all ones to select all possible masks 0x1111...
-            alpha_to_coverage_enabled: false, // 4.
-         },
-
-         multiview: None, // 5.
-      });
+      let render_pipeline = RenderPipelineBuilder::new(setup, &shader)
+          .bind_group_layout(camera_package.bind_group_layout())
+          .vertex_layout(TexturedVertex::desc())
+          .build(&setup.device);
 
 
-      Self {
+      Ok(Self {
          vertex_package,
          render_pipeline,
-      }
+      })
    }
 
    pub fn render_pass(
@@ -85,7 +38,7 @@ impl TestRenderPipeline {
          color_attachments: &[
             // This is what @location(0) in the fragment shader targets
             Some(wgpu::RenderPassColorAttachment {
-               view: &view,
+               view,
                resolve_target: None,
                ops: wgpu::Operations {
                   load: wgpu::LoadOp::Clear(Color {
@@ -106,11 +59,11 @@ impl TestRenderPipeline {
       render_pass.set_pipeline(&self.render_pipeline);
 
       // bind groups
-      render_pass.set_bind_group(0, &camera_package.camera_bind_group, &[]);
+      render_pass.set_bind_group(0, camera_package.bind_group(), &[]);
 
 
       render_pass.set_vertex_buffer(0, self.vertex_package.vertex_buffer.slice(..));
-      render_pass.set_index_buffer(self.vertex_package.index_buffer.slice(..), IndexFormat::Uint16);
+      render_pass.set_index_buffer(self.vertex_package.index_buffer.slice(..), self.vertex_package.index_format);
 
       render_pass.draw_indexed(0..self.vertex_package.num_indices, 0, 0..1);
    }
@@ -0,0 +1,132 @@
+use wgpu::{BindGroupLayout, CommandEncoder, Queue, RenderPipeline, TextureFormat, TextureView};
+use crate::inbuilt::pipeline_builder::RenderPipelineBuilder;
+use crate::inbuilt::setup::Setup;
+use crate::inbuilt::shader_preprocessor::ShaderDefines;
+use crate::inbuilt::vertex_library::{SQUARE_INDICES, TEXTURED_SQUARE_VERTICES};
+use crate::inbuilt::vertex_package::{TexturedVertex, VertexLayout, VertexPackage};
+use crate::packages::camera_package::CameraPackage;
+use crate::packages::shader_package::ShaderPackage;
+use crate::packages::texture_package::TexturePackage;
+use crate::packages::uniform_package::UniformPackage;
+
+
+/// quads drawn between two `begin_frame`s, over all `render_pass` calls
+pub const MAX_QUADS: usize = 256;
+
+/// where a texture is drawn, matches `Quad` in textured_quad.wgsl
+#[repr(C)]
+#[derive(Debug, Copy, Clone, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TexturedQuad {
+   /// center in world units
+   pub position: [f32; 2],
+   /// full width and height in world units
+   pub size: [f32; 2],
+   pub tint: [f32; 4],
+}
+impl TexturedQuad {
+   pub fn new(position: [f32; 2], size: [f32; 2]) -> Self {
+      Self { position, size, tint: [1.0; 4] }
+   }
+}
+
+
+/// draws `TexturePackage`s as alpha blended quads in world space under the camera. every `render_pass`
+/// gets its own slots in the quad uniform, the writes only land when the encoder is submitted
+pub struct TexturedQuadPipeline {
+   vertex_package: VertexPackage<TexturedVertex>,
+   render_pipeline: RenderPipeline,
+   pub texture_layout: BindGroupLayout,
+   quads: UniformPackage<TexturedQuad>,
+   /// slots taken since `begin_frame`
+   used: usize,
+}
+impl TexturedQuadPipeline {
+   pub const SHADER_PATH: &'static str = "render/textured_quad.wgsl";
+
+   pub fn new(setup: &Setup, camera_package: &CameraPackage, format: TextureFormat, shaders: &mut ShaderPackage) -> Result<Self, String> {
+      let vertex_package = VertexPackage::new(&setup.device, TEXTURED_SQUARE_VERTICES, SQUARE_INDICES);
+      let shader = shaders.module(&setup.device, Self::SHADER_PATH, include_str!("../shaders/render/textured_quad.wgsl"), &ShaderDefines::new())?;
+
+      let texture_layout = TexturePackage::bind_group_layout(&setup.device);
+      let quads = UniformPackage::new_array(
+         &setup.device, "textured quads", wgpu::ShaderStages::VERTEX_FRAGMENT,
+         vec![TexturedQuad::new([0.0; 2], [1.0; 2]); MAX_QUADS],
+      );
+
+      let render_pipeline = RenderPipelineBuilder::new(setup, &shader)
+          .label(Self::SHADER_PATH)
+          .bind_group_layout(camera_package.bind_group_layout())
+          .bind_group_layout(&texture_layout)
+          .bind_group_layout(&quads.bind_group_layout)
+          .vertex_layout(TexturedVertex::desc())
+          .format(format)
+          .blend(Some(wgpu::BlendState::ALPHA_BLENDING))
+          .cull_mode(None)
+          .sample_count(1)
+          .build(&setup.device);
+
+      Ok(Self {
+         vertex_package,
+         render_pipeline,
+         texture_layout,
+         quads,
+         used: 0,
+      })
+   }
+
+   /// frees the quad slots, call it once per submitted encoder before the first `render_pass`
+   pub fn begin_frame(&mut self) {
+      self.used = 0;
+   }
+
+   /// draws on top of what is in `view`, in order. quads past `MAX_QUADS` since `begin_frame` are skipped
+   pub fn render_pass(
+      &mut self,
+      encoder: &mut CommandEncoder,
+      view: &TextureView,
+      queue: &Queue,
+      camera_package: &CameraPackage,
+      quads: &[(&TexturePackage, TexturedQuad)],
+   ) {
+      let first = self.used;
+      let count = quads.len().min(MAX_QUADS - first);
+      if count < quads.len() {
+         log::warn!("only {MAX_QUADS} textured quads fit in a frame, skipping {}", quads.len() - count);
+      }
+      let quads = &quads[..count];
+      self.used += count;
+
+      // earlier passes keep their slots, so uploading everything again doesn't change what they draw
+      for (i, (_, quad)) in quads.iter().enumerate() {
+         self.quads.set_at(first + i, *quad);
+      }
+      self.quads.upload(queue);
+
+      let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+         label: Some("Textured Quad Pass"),
+         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+               load: wgpu::LoadOp::Load,
+               store: wgpu::StoreOp::Store,
+            },
+         })],
+         depth_stencil_attachment: None,
+         occlusion_query_set: None,
+         timestamp_writes: None,
+      });
+
+      render_pass.set_pipeline(&self.render_pipeline);
+      render_pass.set_bind_group(0, camera_package.bind_group(), &[]);
+
+      render_pass.set_vertex_buffer(0, self.vertex_package.vertex_buffer.slice(..));
+      render_pass.set_index_buffer(self.vertex_package.index_buffer.slice(..), self.vertex_package.index_format);
+
+      for (i, (texture, _)) in quads.iter().enumerate() {
+         render_pass.set_bind_group(1, &texture.bind_group, &[]);
+         render_pass.set_bind_group(2, &self.quads.bind_group, &[self.quads.offset(first + i)]);
+         render_pass.draw_indexed(0..self.vertex_package.num_indices, 0, 0..1);
+      }
+   }
+}
@@ -0,0 +1,41 @@
+// ping pong textures shared by the automata compute shaders
+//
+// boundary modes, pick one with a define:
+//   BOUNDARY_WRAP  cells wrap around to the opposite edge
+//   BOUNDARY_DEAD  everything outside the grid is dead and writes are dropped
+//   (neither)      positions are passed to the texture unchecked
+
+@group(0) @binding(0)
+var read_texture: texture_storage_2d<r32float, read_write>;
+
+@group(1) @binding(0)
+var write_texture: texture_storage_2d<r32float, read_write>;
+
+
+fn resolve(pos: vec2<i32>) -> vec2<i32> {
+#ifdef BOUNDARY_WRAP
+    let size = vec2<i32>(textureDimensions(read_texture));
+    return ((pos % size) + size) % size;
+#else
+    return pos;
+#endif
+}
+
+fn within_bounds(pos: vec2<i32>) -> bool {
+#ifdef BOUNDARY_DEAD
+    let size = vec2<i32>(textureDimensions(read_texture));
+    return all(pos >= vec2<i32>(0, 0)) && all(pos < size);
+#else
+    return true;
+#endif
+}
+
+fn pull(pos: vec2<i32>) -> f32 {
+    if !within_bounds(pos) { return 0.0; }
+    return textureLoad(read_texture, resolve(pos)).r;
+}
+
+fn put(pos: vec2<i32>, val: f32) {
+    if !within_bounds(pos) { return; }
+    textureStore(write_texture, resolve(pos), vec4<f32>(val, 0.0, 0.0, 1.0));
+}
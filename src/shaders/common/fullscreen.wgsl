@@ -0,0 +1,26 @@
+// one triangle that covers the screen, draw it with 3 vertices and no vertex buffer.
+// also declares the input every post effect reads from
+
+struct FullscreenOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+@group(0) @binding(0)
+var input: texture_2d<f32>;
+@group(0) @binding(1)
+var linear_sampler: sampler;
+
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> FullscreenOutput {
+    var out: FullscreenOutput;
+
+    let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+    out.clip_position = vec4<f32>(uv * vec2<f32>(2.0, -2.0) + vec2<f32>(-1.0, 1.0), 0.0, 1.0);
+    out.uv = uv;
+    return out;
+}
+
+fn sample_input(uv: vec2<f32>) -> vec3<f32> {
+    return textureSampleLevel(input, linear_sampler, uv, 0.0).rgb;
+}
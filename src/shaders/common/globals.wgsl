@@ -0,0 +1,21 @@
+// per frame values written once by `GlobalsPackage`, pass GLOBALS_GROUP to put them in another group
+
+#ifndef GLOBALS_GROUP
+#define GLOBALS_GROUP 3
+#endif
+
+struct FrameGlobals {
+    // seconds since start
+    time: f32,
+    delta_time: f32,
+    frame: u32,
+    _padding: u32,
+    // viewport size in pixels, the docked panel's when docked
+    resolution: vec2<f32>,
+    // cursor in pixels, origin top left of the viewport
+    mouse: vec2<f32>,
+    mouse_world: vec2<f32>,
+    _padding1: vec2<f32>,
+};
+@group(GLOBALS_GROUP) @binding(0)
+var<uniform> globals: FrameGlobals;
@@ -0,0 +1,12 @@
+// the world matrix of the scene node being drawn, written by `ScenePackage`.
+// pass MODEL_GROUP to put it in another group
+
+#ifndef MODEL_GROUP
+#define MODEL_GROUP 2
+#endif
+
+struct ModelUniform {
+    model: mat4x4<f32>,
+};
+@group(MODEL_GROUP) @binding(0)
+var<uniform> node: ModelUniform;
@@ -1,12 +1,11 @@
+#include "common/automata_texture.wgsl"
 
-@group(0) @binding(0)
-var read_texture: texture_storage_2d<r32float, read_write>;
+#ifndef WORKGROUP_SIZE
+#define WORKGROUP_SIZE 16
+#endif
 
-@group(1) @binding(0)
-var write_texture: texture_storage_2d<r32float, read_write>;
 
-
-@compute @workgroup_size(16, 16, 1)
+@compute @workgroup_size(WORKGROUP_SIZE, WORKGROUP_SIZE, 1)
 fn cs_main(
     @builtin(global_invocation_id) global_id: vec3<u32>,
 ) {
@@ -15,21 +14,6 @@ fn cs_main(
     game_of_life(uv);
 }
 
-fn within_bounds(pos: vec2<i32>) -> bool {
-    return true;
-}
-
-fn pull(pos: vec2<i32>) -> f32 {
-    if !within_bounds(pos) { return 0.0; }
-    return textureLoad(read_texture, pos).r;
-}
-
-fn put(pos: vec2<i32>, val: f32) {
-    if !within_bounds(pos) { return; }
-    textureStore(write_texture, pos, vec4<f32>(val, 0.0, 0.0, 1.0));
-}
-
-
 // game of life
 fn game_of_life(uv: vec2<i32>) {
     let nearby = count_directly_nearby(uv, 1);
@@ -1,8 +1,8 @@
-@group(0) @binding(0)
-var read_texture: texture_storage_2d<r32float, read_write>;
+#include "common/automata_texture.wgsl"
 
-@group(1) @binding(0)
-var write_texture: texture_storage_2d<r32float, read_write>;
+#ifndef WORKGROUP_SIZE
+#define WORKGROUP_SIZE 8
+#endif
 
 /// x = posx | y = posy | z = shape (1 = sq, 2 = sph) | w = range
 
@@ -12,7 +12,7 @@ struct Queue {
 @group(2) @binding(0)
 var<uniform> queue: Queue;
 
-@compute @workgroup_size(8, 1, 1)
+@compute @workgroup_size(WORKGROUP_SIZE, 1, 1)
 fn cs_main(
     @builtin(global_invocation_id) global_id: vec3<u32>,
 ) {
@@ -162,12 +162,4 @@ fn cs_main(
         }
 
 
-}
-
-fn pull(pos: vec2<i32>) -> f32 {
-    return textureLoad(read_texture, pos).r;
-}
-
-fn put(pos: vec2<i32>, val: f32) {
-    textureStore(write_texture, pos, vec4<f32>(val, 0.0, 0.0, 1.0));
 }
\ No newline at end of file
@@ -0,0 +1,8 @@
+#include "common/fullscreen.wgsl"
+
+// copies the input as is, used to put an offscreen frame on the swapchain
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    return textureSampleLevel(input, linear_sampler, in.uv, 0.0);
+}
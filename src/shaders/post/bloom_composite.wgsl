@@ -0,0 +1,17 @@
+#include "common/fullscreen.wgsl"
+
+struct BloomComposite {
+    intensity: f32,
+    _padding0: f32,
+    _padding1: vec2<f32>,
+};
+@group(0) @binding(2)
+var<uniform> params: BloomComposite;
+@group(0) @binding(3)
+var bloom: texture_2d<f32>;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let glow = textureSampleLevel(bloom, linear_sampler, in.uv, 0.0).rgb;
+    return vec4<f32>(sample_input(in.uv) + glow * params.intensity, 1.0);
+}
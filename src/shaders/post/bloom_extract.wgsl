@@ -0,0 +1,35 @@
+#include "common/fullscreen.wgsl"
+
+// keeps what is brighter than the threshold, rendered at half resolution
+
+struct BloomExtract {
+    threshold: f32,
+    knee: f32,
+    _padding: vec2<f32>,
+};
+@group(0) @binding(2)
+var<uniform> params: BloomExtract;
+
+fn luminance(color: vec3<f32>) -> f32 {
+    return dot(color, vec3<f32>(0.2126, 0.7152, 0.0722));
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    // 4 taps so the half resolution target doesn't skip bright pixels
+    let texel = 1.0 / vec2<f32>(textureDimensions(input));
+    let color = (
+        sample_input(in.uv + vec2<f32>(-0.5, -0.5) * texel)
+        + sample_input(in.uv + vec2<f32>(0.5, -0.5) * texel)
+        + sample_input(in.uv + vec2<f32>(-0.5, 0.5) * texel)
+        + sample_input(in.uv + vec2<f32>(0.5, 0.5) * texel)
+    ) * 0.25;
+
+    // soft knee so the cutoff doesn't produce hard edges
+    let brightness = luminance(color);
+    let soft = clamp(brightness - params.threshold + params.knee, 0.0, 2.0 * params.knee);
+    let soft_curve = soft * soft / (4.0 * params.knee + 0.0001);
+    let contribution = max(soft_curve, brightness - params.threshold) / max(brightness, 0.0001);
+
+    return vec4<f32>(color * contribution, 1.0);
+}
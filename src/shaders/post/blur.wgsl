@@ -0,0 +1,26 @@
+#include "common/fullscreen.wgsl"
+
+// one direction of a separable 9 tap gaussian
+
+struct Blur {
+    direction: vec2<f32>,
+    radius: f32,
+    _padding: f32,
+};
+@group(0) @binding(2)
+var<uniform> params: Blur;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let texel = 1.0 / vec2<f32>(textureDimensions(input));
+    let step = params.direction * texel * params.radius;
+
+    // 9 taps folded into 5 with linear filtering
+    var color = sample_input(in.uv) * 0.2270270270;
+    color += sample_input(in.uv + step * 1.3846153846) * 0.3162162162;
+    color += sample_input(in.uv - step * 1.3846153846) * 0.3162162162;
+    color += sample_input(in.uv + step * 3.2307692308) * 0.0702702703;
+    color += sample_input(in.uv - step * 3.2307692308) * 0.0702702703;
+
+    return vec4<f32>(color, 1.0);
+}
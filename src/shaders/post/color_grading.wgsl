@@ -0,0 +1,25 @@
+#include "common/fullscreen.wgsl"
+
+// the lut is indexed with color / (1 + color) so hdr values fit in it, see `bake_lut`
+
+struct ColorGrading {
+    strength: f32,
+    lut_size: f32,
+    _padding: vec2<f32>,
+};
+@group(0) @binding(2)
+var<uniform> params: ColorGrading;
+@group(0) @binding(3)
+var lut: texture_3d<f32>;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = max(sample_input(in.uv), vec3<f32>(0.0));
+
+    let encoded = color / (1.0 + color);
+    let coord = encoded * (params.lut_size - 1.0) / params.lut_size + 0.5 / params.lut_size;
+    let graded_encoded = textureSampleLevel(lut, linear_sampler, coord, 0.0).rgb;
+    let graded = graded_encoded / max(1.0 - graded_encoded, vec3<f32>(0.0001));
+
+    return vec4<f32>(mix(color, graded, params.strength), 1.0);
+}
@@ -0,0 +1,32 @@
+#include "common/fullscreen.wgsl"
+
+struct Crt {
+    curvature: f32,
+    scanline_strength: f32,
+    scanline_count: f32,
+    aberration: f32,
+};
+@group(0) @binding(2)
+var<uniform> params: Crt;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    // barrel distortion
+    var centered = in.uv * 2.0 - 1.0;
+    centered *= 1.0 + params.curvature * dot(centered.yx, centered.yx);
+    let uv = centered * 0.5 + 0.5;
+
+    if any(uv < vec2<f32>(0.0)) || any(uv > vec2<f32>(1.0)) {
+        return vec4<f32>(0.0, 0.0, 0.0, 1.0);
+    }
+
+    let offset = centered * params.aberration * 0.01;
+    let color = vec3<f32>(
+        sample_input(uv + offset).r,
+        sample_input(uv).g,
+        sample_input(uv - offset).b,
+    );
+
+    let scanline = 0.5 + 0.5 * sin(uv.y * params.scanline_count * 6.28318530);
+    return vec4<f32>(color * (1.0 - params.scanline_strength * scanline), 1.0);
+}
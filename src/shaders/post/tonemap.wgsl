@@ -0,0 +1,45 @@
+#include "common/fullscreen.wgsl"
+
+// last pass of the stack, maps the hdr scene into the swapchain.
+// MANUAL_SRGB is set when the swapchain format doesn't encode srgb itself
+
+const TONEMAP_NONE: u32 = 0u;
+const TONEMAP_REINHARD: u32 = 1u;
+const TONEMAP_ACES: u32 = 2u;
+
+struct Tonemap {
+    exposure: f32,
+    mode: u32,
+    _padding: vec2<f32>,
+};
+@group(0) @binding(2)
+var<uniform> params: Tonemap;
+
+// narkowicz's fit of the aces curve
+fn aces(x: vec3<f32>) -> vec3<f32> {
+    return clamp((x * (2.51 * x + 0.03)) / (x * (2.43 * x + 0.59) + 0.14), vec3<f32>(0.0), vec3<f32>(1.0));
+}
+
+fn linear_to_srgb(x: vec3<f32>) -> vec3<f32> {
+    let low = x * 12.92;
+    let high = 1.055 * pow(x, vec3<f32>(1.0 / 2.4)) - 0.055;
+    return select(high, low, x <= vec3<f32>(0.0031308));
+}
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let color = max(sample_input(in.uv), vec3<f32>(0.0)) * exp2(params.exposure);
+
+    var mapped: vec3<f32>;
+    switch params.mode {
+        case TONEMAP_REINHARD: { mapped = color / (1.0 + color); }
+        case TONEMAP_ACES: { mapped = aces(color); }
+        default: { mapped = clamp(color, vec3<f32>(0.0), vec3<f32>(1.0)); }
+    }
+
+#ifdef MANUAL_SRGB
+    mapped = linear_to_srgb(mapped);
+#endif
+
+    return vec4<f32>(mapped, 1.0);
+}
@@ -0,0 +1,18 @@
+#include "common/fullscreen.wgsl"
+
+struct Vignette {
+    strength: f32,
+    radius: f32,
+    softness: f32,
+    _padding: f32,
+};
+@group(0) @binding(2)
+var<uniform> params: Vignette;
+
+@fragment
+fn fs_main(in: FullscreenOutput) -> @location(0) vec4<f32> {
+    let distance = length(in.uv - vec2<f32>(0.5, 0.5)) * 1.41421356;
+    let falloff = smoothstep(params.radius, params.radius - params.softness, distance);
+
+    return vec4<f32>(sample_input(in.uv) * mix(1.0, falloff, params.strength), 1.0);
+}
@@ -1,6 +1,7 @@
 // Vertex shader
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
 };
 
 struct VertexOutput {
@@ -8,11 +9,8 @@ struct VertexOutput {
     @location(0) uv: vec2<f32>, // Add this line
 };
 
-struct CameraUniform {
-    view_proj: mat4x4<f32>,
-};
-@group(0) @binding(0)
-var<uniform> camera: CameraUniform;
+#include "common/camera.wgsl"
+#include "common/model.wgsl"
 
 
 @vertex
@@ -22,8 +20,8 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
 
-    out.clip_position = camera.view_proj * vec4<f32>((model.position), 1.0);
-    out.uv = model.position.xy * 0.5 + 0.5;
+    out.clip_position = camera.view_proj * node.model * vec4<f32>((model.position), 1.0);
+    out.uv = model.uv;
     return out;
 }
 
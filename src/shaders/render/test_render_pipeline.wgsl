@@ -1,5 +1,6 @@
 struct VertexInput {
     @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
 };
 
 
@@ -9,11 +10,7 @@ struct VertexOutput {
 };
 
 
-struct CameraUniform {
-    view_proj: mat4x4<f32>,
-};
-@group(0) @binding(0)
-var<uniform> camera: CameraUniform;
+#include "common/camera.wgsl"
 
 @vertex
 fn vs_main(
@@ -23,7 +20,7 @@ fn vs_main(
     var out: VertexOutput;
 
     out.clip_position = camera.view_proj * vec4<f32>((model.position), 1.0);
-    out.uv = model.position.xy + 0.5;
+    out.uv = model.uv;
     return out;
 }
 
@@ -0,0 +1,43 @@
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) uv: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) uv: vec2<f32>,
+};
+
+#include "common/camera.wgsl"
+
+@group(1) @binding(0)
+var t_diffuse: texture_2d<f32>;
+@group(1) @binding(1)
+var s_diffuse: sampler;
+
+// center and full size in world units, picked per draw with a dynamic offset
+struct Quad {
+    position: vec2<f32>,
+    size: vec2<f32>,
+    tint: vec4<f32>,
+};
+@group(2) @binding(0)
+var<uniform> quad: Quad;
+
+
+@vertex
+fn vs_main(model: VertexInput) -> VertexOutput {
+    var out: VertexOutput;
+
+    let world = quad.position + model.position.xy * 0.5 * quad.size;
+    out.clip_position = camera.view_proj * vec4<f32>(world, 0.0, 1.0);
+    // images are stored top row first
+    out.uv = vec2<f32>(model.uv.x, 1.0 - model.uv.y);
+    return out;
+}
+
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_diffuse, s_diffuse, in.uv) * quad.tint;
+}
@@ -2,30 +2,61 @@ use std::iter;
 use cgmath::{Vector3};
 use egui::Context;
 use egui_wgpu::ScreenDescriptor;
-use wgpu::{CommandEncoder, TextureView};
 use winit::dpi::PhysicalSize;
 use winit::event::WindowEvent;
+use winit::keyboard::KeyCode;
 use winit::window::Window;
 use crate::bundles::automata::automata_bundle::AutomataBundle;
 use crate::bundles::automata::automata_gui;
-use crate::egui::gui::EguiRenderer;
+use crate::bundles::debug_draw::debug_draw_bundle::DebugDrawBundle;
+use crate::bundles::debug_draw::debug_draw_gui;
+use crate::bundles::post_process::post_process_bundle::{PostProcessBundle, HDR_FORMAT};
+use crate::bundles::post_process::post_process_gui;
+use crate::bundles::showcase::showcase_bundle::ShowcaseBundle;
+use crate::bundles::showcase::showcase_gui;
+use crate::egui::gui::{EguiFrame, EguiRenderer};
+use crate::egui::ui_settings_gui::UiSettingsPanel;
+use crate::inbuilt::render_graph::{RenderGraph, RenderTargetPool};
 use crate::inbuilt::setup::Setup;
 use crate::packages::camera_package::{CameraPackage, OrthographicCamera};
+use crate::packages::globals_package::GlobalsPackage;
 use crate::packages::input_manager_package::InputManager;
+use crate::packages::log_package::{capture_uncaptured_errors, LogBuffer, LogPackage};
+use crate::packages::persistence_package::{AppSettings, PersistencePackage};
+use crate::packages::recorder_package::RecorderPackage;
+use crate::packages::scene_package::ScenePackage;
+use crate::packages::screenshot_package::ScreenshotPackage;
+use crate::packages::shader_package::ShaderPackage;
 use crate::packages::time_package::TimePackage;
+use crate::packages::viewport_package::ViewportPackage;
 use crate::pipelines::test_render_pipeline::TestRenderPipeline;
 
 
 pub struct State<'a> {
    pub setup: Setup<'a>,
    pub egui: EguiRenderer,
+   ui_settings_panel: UiSettingsPanel,
+   render_targets: RenderTargetPool,
+   /// the last render graph error, logged once until a frame renders again
+   graph_error: Option<String>,
 
    // packages
    time_package: TimePackage,
    camera_package: CameraPackage,
+   globals_package: GlobalsPackage,
+   scene_package: ScenePackage,
    input_manager: InputManager,
+   shader_package: ShaderPackage,
+   screenshot_package: ScreenshotPackage,
+   recorder_package: RecorderPackage,
+   persistence_package: PersistencePackage,
+   viewport_package: ViewportPackage,
+   log_package: LogPackage,
 
    automata_bundle: AutomataBundle,
+   post_process_bundle: PostProcessBundle,
+   debug_draw_bundle: DebugDrawBundle,
+   showcase_bundle: ShowcaseBundle,
 
    // pipelines
    #[allow(dead_code)]
@@ -33,16 +64,22 @@ pub struct State<'a> {
 }
 
 impl<'a> State<'a> {
-   pub async fn new(window: &'a Window) -> State<'a> {
+   pub async fn new(window: &'a Window, logs: LogBuffer) -> State<'a> {
 
       // dependents
       let setup = Setup::new(window).await;
-      let egui = EguiRenderer::new(&setup.device, setup.config.format, None, 1, setup.window);
+      capture_uncaptured_errors(&setup.device);
+      let egui = EguiRenderer::new(&setup.device, setup.config.format, None, setup.sample_count, setup.window);
+
+      let persistence_package = PersistencePackage::new();
+      let settings = persistence_package.load_settings();
+      persistence_package.restore_memory(&egui.context);
 
 
       // packages
       let time_package = TimePackage::new();
       let input_manager = InputManager::new();
+      let mut shader_package = ShaderPackage::new(cfg!(debug_assertions));
       let camera_package = CameraPackage::new(&setup.device, OrthographicCamera {
          eye: (0.0, 0.0, 1.0).into(),
          target: (0.0, 0.0, 0.0).into(),
@@ -52,25 +89,82 @@ impl<'a> State<'a> {
       });
 
 
-      let automata_bundle = AutomataBundle::new(&setup, &camera_package);
+      let viewport_package = ViewportPackage::new(&setup);
+      let globals_package = GlobalsPackage::new(&setup.device);
+      let mut scene_package = ScenePackage::new(&setup.device);
+
+
+      let automata_bundle = AutomataBundle::new(&setup, &camera_package, &mut scene_package, HDR_FORMAT, &settings.automata, &mut shader_package);
+      let post_process_bundle = PostProcessBundle::new(&setup, &mut shader_package);
+      let screenshot_package = ScreenshotPackage::new(&setup, &mut shader_package);
+      let debug_draw_bundle = DebugDrawBundle::new(&setup, &camera_package, setup.config.format, &mut shader_package);
+      let showcase_bundle = ShowcaseBundle::new(&setup, &camera_package, &globals_package, HDR_FORMAT, &mut shader_package);
 
 
       // pipelines
-      let test_render_pipeline = TestRenderPipeline::new(&setup, &camera_package);
+      let test_render_pipeline = TestRenderPipeline::new(&setup, &camera_package)
+          .unwrap_or_else(|err| panic!("{err}"));
 
 
-      Self {
+      let mut state = Self {
          setup,
          egui,
+         ui_settings_panel: UiSettingsPanel::new(settings.ui.clone()),
+         render_targets: RenderTargetPool::new(),
+         graph_error: None,
 
          time_package,
          camera_package,
+         globals_package,
+         scene_package,
          input_manager,
+         shader_package,
+         screenshot_package,
+         recorder_package: RecorderPackage::new(),
+         persistence_package,
+         viewport_package,
+         log_package: LogPackage::new(logs),
 
          test_render_pipeline,
 
 
          automata_bundle,
+         post_process_bundle,
+         debug_draw_bundle,
+         showcase_bundle,
+      };
+
+      state.apply_settings(&settings);
+      state
+   }
+
+   pub fn settings(&self) -> AppSettings {
+      AppSettings {
+         automata: self.automata_bundle.settings(),
+         recording: self.recorder_package.settings,
+         screenshot_include_ui: self.screenshot_package.include_ui,
+         debug_draw: self.debug_draw_bundle.enabled,
+         ui: self.ui_settings_panel.settings.clone(),
+      }
+   }
+
+   pub fn apply_settings(&mut self, settings: &AppSettings) {
+      self.automata_bundle.apply_settings(&self.setup, &mut self.shader_package, &settings.automata);
+      self.recorder_package.settings = settings.recording;
+      self.screenshot_package.include_ui = settings.screenshot_include_ui;
+      self.debug_draw_bundle.enabled = settings.debug_draw;
+
+      self.ui_settings_panel.set(settings.ui.clone());
+      let result = self.egui.apply_settings(&settings.ui);
+      self.ui_settings_panel.report(result);
+   }
+
+   /// called when the app exits
+   pub fn save(&self) {
+      if !self.persistence_package.save_on_exit { return; }
+
+      if let Err(err) = self.persistence_package.save(&self.egui.context, &self.settings()) {
+         log::error!("{err}");
       }
    }
 
@@ -92,38 +186,105 @@ impl<'a> State<'a> {
 
    pub fn update(&mut self) {
       self.time_package.update();
-      self.camera_package.update(&mut self.setup.queue, self.time_package.delta_time as f32, &self.input_manager);
+      // runs the callbacks of readbacks the gpu finished, `Readback::then` relies on it
+      self.setup.device.poll(wgpu::Maintain::Poll);
+
+      if self.persistence_package.take_reset() {
+         self.apply_settings(&AppSettings::default());
+         self.persistence_package.reset_memory(&self.egui.context);
+      }
+      self.debug_draw_bundle.begin_frame();
+
+      if self.input_manager.is_key_just_pressed(KeyCode::F2) {
+         self.ui_settings_panel.settings.docked = !self.ui_settings_panel.settings.docked;
+      }
+      // while docked the scene is seen through the viewport's camera, and the mouse is mapped through
+      // where the panel was laid out last frame
+      let docked = self.ui_settings_panel.settings.docked;
+      self.viewport_package.enabled = docked;
+      self.input_manager.viewport = self.viewport_package.region();
+
+      let camera = if docked { &mut self.viewport_package.camera } else { &mut self.camera_package };
+      camera.update(&mut self.setup.queue, self.time_package.delta_time as f32, &self.input_manager);
+      let camera = &*camera;
+
+      self.globals_package.update(&self.setup, &self.time_package, &self.input_manager, camera);
 
       // let mouse_world_pos = self.input_manager.pull_world_pos_2d(&self.camera_package, &self.setup);
-      self.automata_bundle.update(&self.input_manager, &self.setup, &self.camera_package);
+      self.automata_bundle.update(&self.input_manager, &self.setup, camera, &self.scene_package);
+      self.showcase_bundle.update(&self.setup, &self.time_package);
+
+      if self.input_manager.is_key_just_pressed(KeyCode::F3) {
+         self.debug_draw_bundle.enabled = !self.debug_draw_bundle.enabled;
+      }
+      if self.debug_draw_bundle.enabled {
+         self.automata_bundle.debug_draw(&mut self.debug_draw_bundle.shapes, &self.input_manager, camera, &self.scene_package, &self.setup);
+      }
+
+      if self.input_manager.is_key_just_pressed(KeyCode::F12) {
+         self.screenshot_package.request();
+      }
+
+      let changed_shaders = self.shader_package.poll();
+      if !changed_shaders.is_empty() {
+         self.automata_bundle.reload_shaders(&self.setup, &self.camera_package, &self.scene_package, &mut self.shader_package, &changed_shaders);
+         self.post_process_bundle.reload_shaders(&self.setup, &mut self.shader_package, &changed_shaders);
+         self.screenshot_package.reload_shaders(&self.setup, &mut self.shader_package, &changed_shaders);
+         self.debug_draw_bundle.reload_shaders(&self.setup, &self.camera_package, &mut self.shader_package, &changed_shaders);
+         self.showcase_bundle.reload_shaders(&self.setup, &self.camera_package, &self.globals_package, &mut self.shader_package, &changed_shaders);
+      }
 
+      self.scene_package.update(&self.setup.queue);
       self.input_manager.reset();
    }
 
-   pub fn update_gui(&mut self, view: &TextureView, encoder: &mut CommandEncoder) {
+   pub fn run_gui(&mut self) -> EguiFrame {
       let screen_descriptor = ScreenDescriptor {
          size_in_pixels: [self.setup.config.width, self.setup.config.height],
          pixels_per_point: self.setup.window.scale_factor() as f32,
       };
 
+      let settings = self.settings();
+      let docked = self.viewport_package.enabled;
+
       let run_ui = |ui: &Context| {
-         automata_gui::gui(
-            ui,
-            &self.time_package,
-            &mut self.automata_bundle,
-            &self.setup,
-         )
+         // the panels go first, windows are laid out over whatever space they leave
+         self.viewport_package.gui(ui, |ui| {
+            automata_gui::controls(ui, &self.time_package, &mut self.automata_bundle, &self.setup, &mut self.shader_package);
+         });
+         if !docked {
+            automata_gui::gui(
+               ui,
+               &self.time_package,
+               &mut self.automata_bundle,
+               &self.setup,
+               &mut self.shader_package,
+            );
+         }
+
+         post_process_gui::gui(ui, &mut self.post_process_bundle);
+         showcase_gui::gui(ui, &mut self.showcase_bundle);
+         self.shader_package.gui(ui);
+         self.scene_package.gui(ui);
+         self.screenshot_package.gui(ui);
+         self.recorder_package.gui(ui, &self.setup);
+         let camera = if docked { &self.viewport_package.camera } else { &self.camera_package };
+         debug_draw_gui::gui(ui, &mut self.debug_draw_bundle, camera, self.viewport_package.rect());
+         self.persistence_package.gui(ui, &settings);
+         self.ui_settings_panel.gui(ui);
+         self.log_package.gui(ui);
       };
 
-      self.egui.draw(
-         &self.setup.device,
-         &self.setup.queue,
-         encoder,
-         &self.setup.window,
-         &view,
-         screen_descriptor,
-         run_ui,
-      );
+      let frame = self.egui.prepare(self.setup.window, screen_descriptor, run_ui);
+      self.viewport_package.prepare(&self.setup, &mut self.egui);
+
+      // changes from the panel show up from the next frame on
+      if self.ui_settings_panel.take_reload_fonts() {
+         self.egui.reload_fonts();
+      }
+      let result = self.egui.apply_settings(&self.ui_settings_panel.settings);
+      self.ui_settings_panel.report(result);
+      frame
    }
 
    pub fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
@@ -133,17 +294,113 @@ impl<'a> State<'a> {
          label: Some("Render Encoder"),
       });
 
+      // the ui runs first so everything it changes is already in place when the passes are recorded
+      let mut egui_frame = Some(self.run_gui());
+
+      // a screenshot frame is drawn into the capture texture and blitted to the swapchain afterwards,
+      // the ui goes into whichever of the two the screenshot should contain. docked, the scene is part
+      // of the ui, so the screenshot always has both
+      let capturing = self.screenshot_package.begin_frame(&self.setup);
+      let docked = self.viewport_package.target().is_some();
+      let ui_in_capture = !capturing || self.screenshot_package.include_ui || docked;
+
+
+      // while recording the simulation follows the recording's generations instead of the real clock
+      let recorded_frame = self.recorder_package.next_frame();
+      self.automata_bundle.manual_clock = recorded_frame.is_some();
+      if recorded_frame.is_some_and(|frame| frame.step) {
+         self.automata_bundle.request_step();
+      }
+
+
+      let camera = if docked { &self.viewport_package.camera } else { &self.camera_package };
+      let mut graph = RenderGraph::new();
+
+      self.automata_bundle.add_passes(&mut graph, camera, &self.scene_package, self.time_package.delta_time, PostProcessBundle::SCENE);
+      self.showcase_bundle.add_passes(&mut graph, &self.setup, camera, &self.globals_package, PostProcessBundle::SCENE);
+      self.post_process_bundle.add_passes(&mut graph, &self.setup);
+      self.debug_draw_bundle.add_pass(&mut graph, &self.setup, camera);
+
+      let target = self.screenshot_package.target().unwrap_or(&view);
+      let mut executed = match self.viewport_package.target() {
+         // the scene goes into the viewport's texture, the ui that shows it onto the window
+         Some((viewport, size)) => {
+            let scene = graph.execute_at(&self.setup.device, &mut encoder, viewport, size, &mut self.render_targets);
+
+            let mut graph = RenderGraph::new();
+            ViewportPackage::add_clear_pass(&mut graph);
+            if let Some(frame) = egui_frame.take_if(|_| ui_in_capture) {
+               self.egui.add_pass(&mut graph, &self.setup.device, &self.setup.queue, frame);
+            }
+            scene.and(graph.execute(&self.setup, &mut encoder, target, &mut RenderTargetPool::new()))
+         }
+         None => {
+            if let Some(frame) = egui_frame.take_if(|_| ui_in_capture) {
+               self.egui.add_pass(&mut graph, &self.setup.device, &self.setup.queue, frame);
+            }
+            graph.execute(&self.setup, &mut encoder, target, &mut self.render_targets)
+         }
+      };
+
 
-      {
-         self.automata_bundle.automata_pass(&mut encoder, &view, &self.camera_package, &mut self.time_package);
+      if capturing {
+         let mut graph = RenderGraph::new();
+
+         self.screenshot_package.add_present_pass(&mut graph);
+         if let Some(frame) = egui_frame {
+            self.egui.add_pass(&mut graph, &self.setup.device, &self.setup.queue, frame);
+         }
+
+         // no transients in here, a pool of its own keeps the main one from dropping its targets
+         executed = executed.and(graph.execute(&self.setup, &mut encoder, &view, &mut RenderTargetPool::new()));
       }
 
-      self.update_gui(&view, &mut encoder);
+      // a half recorded frame isn't submitted, the window keeps showing the last one
+      if let Err(err) = executed {
+         if self.graph_error.as_ref() != Some(&err) {
+            log::error!("skipping frames: {err}");
+            self.graph_error = Some(err);
+         }
+         if capturing { self.screenshot_package.cancel(); }
+         return Ok(());
+      }
+      self.graph_error = None;
 
 
       self.setup.queue.submit(iter::once(encoder.finish()));
       output.present();
 
+      self.screenshot_package.end_frame(&self.setup);
+      self.record_frame();
+
       Ok(())
    }
-}
\ No newline at end of file
+
+   /// draws the current generation again at the recording's size, without the ui
+   fn record_frame(&mut self) {
+      let Some((width, height)) = self.recorder_package.frame_size() else { return };
+
+      // the window's frame is already submitted, so this only affects the recording
+      let camera = if self.viewport_package.enabled { &mut self.viewport_package.camera } else { &mut self.camera_package };
+      camera.write_with_aspect(&self.setup.queue, width as f32 / height as f32);
+      let camera = &*camera;
+
+      let mut encoder = self.setup.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+         label: Some("Recording Encoder"),
+      });
+
+      let mut graph = RenderGraph::new();
+      self.automata_bundle.add_render_pass(&mut graph, camera, &self.scene_package, PostProcessBundle::SCENE);
+      self.showcase_bundle.add_passes(&mut graph, &self.setup, camera, &self.globals_package, PostProcessBundle::SCENE);
+      self.post_process_bundle.add_passes(&mut graph, &self.setup);
+
+      let (target, targets) = self.recorder_package.target().unwrap();
+      if let Err(err) = graph.execute_at(&self.setup.device, &mut encoder, target, (width, height), targets) {
+         self.recorder_package.abort(err);
+         return;
+      }
+
+      self.setup.queue.submit(iter::once(encoder.finish()));
+      self.recorder_package.end_frame(&self.setup);
+   }
+}
@@ -1,3 +1,6 @@
+use wgpu::{Buffer, BufferAddress, BufferUsages, COPY_BUFFER_ALIGNMENT, Device, Queue};
+
+
 
 
 
@@ -11,4 +14,40 @@ pub fn round_to_x_decimals(num: f32, decimals: u32) -> f32 {
 #[allow(dead_code)]
 pub fn wait(ms: u64) {
    std::thread::sleep(std::time::Duration::from_millis(ms));
-}
\ No newline at end of file
+}
+
+
+/// rounds a byte length up to the 4 byte alignment buffer copies and writes need
+pub fn padded_len(len: usize) -> BufferAddress {
+   let len = len as BufferAddress;
+   len.div_ceil(COPY_BUFFER_ALIGNMENT) * COPY_BUFFER_ALIGNMENT
+}
+
+
+/// writes `contents` to the start of `buffer`, replacing it with a bigger one first when it doesn't fit
+pub fn write_or_grow_buffer(device: &Device, queue: &Queue, buffer: &mut Buffer, label: &str, contents: &[u8], usage: BufferUsages) {
+   let padded_size = padded_len(contents.len());
+
+   if padded_size > buffer.size() {
+      // grow to the next power of two so per-frame geometry doesn't reallocate every frame
+      let size = padded_size.next_power_of_two();
+      *buffer = device.create_buffer(&wgpu::BufferDescriptor {
+         label: Some(label),
+         size,
+         usage: usage | BufferUsages::COPY_DST,
+         mapped_at_creation: false,
+      });
+   }
+
+   if contents.is_empty() { return; }
+
+   // writes must be a multiple of 4 bytes, odd u16 index counts are padded with zeros
+   if padded_size != contents.len() as BufferAddress {
+      let mut padded = contents.to_vec();
+      padded.resize(padded_size as usize, 0);
+      queue.write_buffer(buffer, 0, &padded);
+   }
+   else {
+      queue.write_buffer(buffer, 0, contents);
+   }
+}
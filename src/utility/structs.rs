@@ -2,6 +2,7 @@
 
 
 /// to Ping Or Pong
+#[allow(clippy::upper_case_acronyms)]
 enum POP {
    First,
    Second,
@@ -0,0 +1,46 @@
+use wgpu_template::bundles::post_process::color_grading::{bake_lut, decode, encode, GradingSettings};
+
+#[test]
+fn default_settings_leave_color_unchanged() {
+   let color = [0.02, 0.18, 3.5];
+   let graded = GradingSettings::default().apply(color);
+
+   for i in 0..3 {
+      assert!((graded[i] - color[i]).abs() < 1e-5, "{graded:?} != {color:?}");
+   }
+}
+
+#[test]
+fn encoding_round_trips() {
+   for c in [0.0, 0.1, 1.0, 10.0, 100.0] {
+      assert!((decode(encode(c)) - c).abs() < c * 1e-4 + 1e-6);
+   }
+}
+
+#[test]
+fn default_lut_is_identity() {
+   let size = 8;
+   let lut = bake_lut(&GradingSettings::default(), size);
+   assert_eq!(lut.len(), (size * size * size) as usize);
+
+   for b in 0..size {
+      for g in 0..size {
+         for r in 0..size {
+            let texel = lut[(r + g * size + b * size * size) as usize];
+            let expected = [r, g, b].map(|i| (i as f32 / (size - 1) as f32 * 255.0).round() as i32);
+
+            for k in 0..3 {
+               assert!((texel[k] as i32 - expected[k]).abs() <= 1, "texel {r} {g} {b} is {texel:?}");
+            }
+         }
+      }
+   }
+}
+
+#[test]
+fn zero_saturation_is_grey() {
+   let settings = GradingSettings { saturation: 0.0, ..Default::default() };
+   let [r, g, b] = settings.apply([1.0, 0.2, 0.05]);
+
+   assert!((r - g).abs() < 1e-6 && (g - b).abs() < 1e-6);
+}
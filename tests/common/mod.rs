@@ -0,0 +1,19 @@
+//! helpers shared by the integration tests, every test binary only uses some of them
+#![allow(dead_code)]
+
+use std::path::PathBuf;
+
+/// skipped when there is no adapter, e.g. on ci without a gpu
+pub fn device() -> Option<(wgpu::Device, wgpu::Queue)> {
+   let instance = wgpu::Instance::default();
+   let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))?;
+   pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default(), None)).ok()
+}
+
+/// a file or directory in the system temp dir that only this test process uses, whatever an earlier run left there is removed
+pub fn temp_path(name: &str) -> PathBuf {
+   let path = std::env::temp_dir().join(format!("wgpu_template_{}_{name}", std::process::id()));
+   let _ = std::fs::remove_dir_all(&path);
+   let _ = std::fs::remove_file(&path);
+   path
+}
@@ -0,0 +1,67 @@
+mod common;
+
+use common::device;
+use wgpu_template::bundles::automata::automata_compute_pipeline::Automata;
+use wgpu_template::inbuilt::shader_preprocessor::{preprocess, ShaderDefines};
+use wgpu_template::inbuilt::shader_reflection::ReflectedShader;
+use wgpu_template::packages::compute_package::{workgroup_count, ComputePackage};
+
+#[test]
+fn counts_round_up_per_dimension() {
+   assert_eq!(workgroup_count([100, 1, 1], [8, 1, 1], 65535), Ok([13, 1, 1]));
+   assert_eq!(workgroup_count([56, 56, 1], [16, 16, 1], 65535), Ok([4, 4, 1]));
+   assert_eq!(workgroup_count([64, 64, 64], [4, 4, 4], 65535), Ok([16, 16, 16]));
+   assert_eq!(workgroup_count([0, 1, 1], [8, 1, 1], 65535), Ok([0, 1, 1]));
+}
+
+#[test]
+fn counts_past_the_limit_are_rejected() {
+   assert!(workgroup_count([65536 * 8 + 1, 1, 1], [8, 1, 1], 65535).is_err());
+   assert!(workgroup_count([1, 1, 70_000], [1, 1, 1], 65535).is_err());
+}
+
+#[test]
+fn workgroup_size_is_reflected_after_defines() {
+   let path = Automata::GameOfLife.shader_path();
+   let root = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/shaders");
+   let source = std::fs::read_to_string(root.join(path)).unwrap();
+
+   for size in [8, 16] {
+      let defines = ShaderDefines::new().with("WORKGROUP_SIZE", size);
+      let processed = preprocess(path, &source, &defines, &mut |include| std::fs::read_to_string(root.join(include)).ok()).unwrap();
+      let shader = ReflectedShader::from_wgsl(&processed.source, path).unwrap();
+
+      assert_eq!(shader.workgroup_size("cs_main"), Some([size, size, 1]));
+      assert_eq!(shader.workgroup_size("missing"), None);
+   }
+}
+
+#[test]
+fn bad_entry_points_and_sizes_are_errors() {
+   let Some((device, _queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let source = "
+@compute @workgroup_size(8, 8, 1)
+fn small() {}
+
+@compute @workgroup_size(1024, 1024, 1)
+fn huge() {}
+";
+   let reflected = ReflectedShader::from_wgsl(source, "sizes.wgsl").unwrap();
+   let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+      label: None,
+      source: wgpu::ShaderSource::Wgsl(source.into()),
+   });
+   let create = |entry_point| ComputePackage::new(&device, "sizes", &module, &reflected, entry_point, &[]);
+
+   assert!(create("missing").err().unwrap().contains("no compute entry point `missing`"));
+   assert!(create("huge").err().unwrap().contains("over the device limits"));
+
+   let small = create("small").unwrap();
+   assert_eq!(small.workgroups([100, 100, 1]), Ok([13, 13, 1]));
+   let max = device.limits().max_compute_workgroups_per_dimension;
+   assert!(small.workgroups([(max + 1) * 8, 1, 1]).is_err());
+}
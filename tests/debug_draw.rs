@@ -0,0 +1,69 @@
+use cgmath::{Matrix4, SquareMatrix};
+use wgpu_template::bundles::debug_draw::debug_draw_bundle::{world_to_screen, DebugShapes, CIRCLE_SEGMENTS};
+
+const WHITE: [f32; 4] = [1.0; 4];
+
+fn points(shapes: &DebugShapes) -> Vec<[f32; 2]> {
+   shapes.vertices().iter().map(|v| [v.position[0], v.position[1]]).collect()
+}
+
+fn close(a: [f32; 2], b: [f32; 2]) -> bool {
+   (a[0] - b[0]).abs() < 1e-5 && (a[1] - b[1]).abs() < 1e-5
+}
+
+#[test]
+fn outlines_are_closed_line_lists() {
+   let mut shapes = DebugShapes::new();
+   shapes.rect([0.0, 0.0], [2.0, 1.0], WHITE);
+   let rect = points(&shapes);
+   assert_eq!(rect.len(), 8);
+   assert_eq!(rect, [[0.0, 0.0], [2.0, 0.0], [2.0, 0.0], [2.0, 1.0], [2.0, 1.0], [0.0, 1.0], [0.0, 1.0], [0.0, 0.0]]);
+
+   shapes.clear();
+   shapes.circle([1.0, -1.0], 0.5, WHITE);
+   let circle = points(&shapes);
+   assert_eq!(circle.len(), CIRCLE_SEGMENTS * 2);
+   assert!(close(circle[0], *circle.last().unwrap()));
+   for point in circle {
+      let distance = ((point[0] - 1.0).powi(2) + (point[1] + 1.0).powi(2)).sqrt();
+      assert!((distance - 0.5).abs() < 1e-5);
+   }
+}
+
+#[test]
+fn arrow_head_points_back_along_the_shaft() {
+   let mut shapes = DebugShapes::new();
+   shapes.arrow([0.0, 0.0], [10.0, 0.0], WHITE);
+   let arrow = points(&shapes);
+   assert_eq!(arrow.len(), 6);
+
+   for head in [arrow[3], arrow[5]] {
+      let length = ((head[0] - 10.0).powi(2) + head[1].powi(2)).sqrt();
+      assert!((length - 2.0).abs() < 1e-4);
+      assert!(head[0] < 10.0);
+   }
+   assert!(arrow[3][1] * arrow[5][1] < 0.0, "the two sides of the head mirror each other");
+}
+
+#[test]
+fn labels_are_kept_until_cleared() {
+   let mut shapes = DebugShapes::new();
+   shapes.crosshair([0.0, 0.0], 1.0, WHITE);
+   shapes.text([0.5, 0.5], "grid", WHITE);
+   assert_eq!(shapes.vertices().len(), 4);
+   assert_eq!(shapes.labels()[0].text, "grid");
+
+   shapes.clear();
+   assert!(shapes.is_empty());
+}
+
+#[test]
+fn world_to_screen_flips_y() {
+   let identity = Matrix4::identity();
+   assert_eq!(world_to_screen(identity, [0.0, 0.0], [800.0, 600.0]), [400.0, 300.0]);
+   assert_eq!(world_to_screen(identity, [-1.0, 1.0], [800.0, 600.0]), [0.0, 0.0]);
+   assert_eq!(world_to_screen(identity, [1.0, -1.0], [800.0, 600.0]), [800.0, 600.0]);
+
+   let zoomed = Matrix4::from_scale(2.0);
+   assert_eq!(world_to_screen(zoomed, [0.5, 0.0], [800.0, 600.0]), [800.0, 300.0]);
+}
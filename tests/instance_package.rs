@@ -0,0 +1,71 @@
+mod common;
+
+use common::device;
+use cgmath::{Quaternion, Rotation3, Vector3};
+use wgpu::{VertexFormat, VertexStepMode};
+use wgpu_template::inbuilt::instance_package::{Instance, InstancePackage, InstanceRaw};
+use wgpu_template::inbuilt::vertex_package::{ColoredVertex, LitVertex, TexturedVertex, Vertex, VertexLayout};
+
+fn instances(count: usize) -> Vec<Instance> {
+   (0..count).map(|i| Instance {
+      position: Vector3::new(i as f32, 0.0, 0.0),
+      rotation: Quaternion::from_angle_z(cgmath::Rad(0.0)),
+      scale: Vector3::new(1.0, 1.0, 1.0),
+      color: [1.0; 4],
+   }).collect()
+}
+
+#[test]
+fn instance_layout_is_four_columns_and_a_color_from_location_5() {
+   let desc = InstanceRaw::desc();
+   assert_eq!(desc.step_mode, VertexStepMode::Instance);
+   assert_eq!(desc.array_stride, 80);
+
+   let locations: Vec<_> = desc.attributes.iter().map(|a| (a.shader_location, a.offset, a.format)).collect();
+   assert_eq!(locations, (0..5).map(|i| (5 + i, 16 * i as u64, VertexFormat::Float32x4)).collect::<Vec<_>>());
+
+   // the vertex types stop below 5, so any of them can be drawn instanced
+   for desc in [Vertex::desc(), TexturedVertex::desc(), ColoredVertex::desc(), LitVertex::desc()] {
+      assert!(desc.attributes.iter().all(|a| a.shader_location < 5));
+   }
+}
+
+#[test]
+fn to_raw_puts_the_translation_in_the_last_column() {
+   let instance = Instance {
+      position: Vector3::new(1.0, 2.0, 3.0),
+      rotation: Quaternion::from_angle_z(cgmath::Rad(0.0)),
+      scale: Vector3::new(2.0, 2.0, 2.0),
+      color: [0.5, 0.25, 0.0, 1.0],
+   };
+
+   let raw = instance.to_raw();
+   assert_eq!(raw.model[3], [1.0, 2.0, 3.0, 1.0]);
+   assert_eq!(raw.model[0], [2.0, 0.0, 0.0, 0.0]);
+   assert_eq!(raw.color, instance.color);
+}
+
+#[test]
+fn updates_grow_the_instance_buffer() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   // an empty package still has one instance worth of buffer, so it can be bound
+   let mut package = InstancePackage::new(&device, &[]);
+   assert_eq!(package.instance_buffer.size(), 80);
+   assert_eq!(package.num_instances, 0);
+
+   // 10 instances are 800 bytes, grown to the next power of two
+   package.update(&device, &queue, &instances(10));
+   assert_eq!(package.instance_buffer.size(), 1024);
+   assert_eq!(package.num_instances, 10);
+
+   // fewer instances reuse the buffer
+   package.update_raw(&device, &queue, &instances(2).iter().map(Instance::to_raw).collect::<Vec<_>>());
+   assert_eq!(package.instance_buffer.size(), 1024);
+   assert_eq!(package.num_instances, 2);
+
+   device.poll(wgpu::Maintain::Wait);
+}
@@ -0,0 +1,51 @@
+use log::{Level, LevelFilter, Log, Record};
+use wgpu_template::packages::log_package::{ConsoleLogger, LogBuffer, LogFilter};
+
+fn everything() -> LogFilter {
+   LogFilter { level: LevelFilter::Trace, ..LogFilter::default() }
+}
+
+#[test]
+fn the_buffer_drops_the_oldest_records() {
+   let buffer = LogBuffer::new(3);
+   for i in 0..5 {
+      buffer.push(Level::Info, "test", format!("message {i}"));
+   }
+
+   let messages: Vec<String> = buffer.filtered(&everything()).into_iter().map(|record| record.message).collect();
+   assert_eq!(messages, ["message 2", "message 3", "message 4"]);
+
+   buffer.clear();
+   assert!(buffer.is_empty());
+}
+
+#[test]
+fn filters_combine_level_target_and_search() {
+   let buffer = LogBuffer::new(10);
+   buffer.push(Level::Error, "wgpu", "Validation Error in render pass".to_string());
+   buffer.push(Level::Debug, "wgpu_core", "created a buffer".to_string());
+   buffer.push(Level::Warn, "wgpu_template::event_loop", "Surface timeout".to_string());
+
+   assert_eq!(buffer.filtered(&LogFilter::default()).len(), 2);
+   assert_eq!(buffer.filtered(&LogFilter { target: "wgpu".to_string(), ..everything() }).len(), 3);
+   assert_eq!(buffer.filtered(&LogFilter { target: "event_loop".to_string(), ..everything() }).len(), 1);
+
+   let found = buffer.filtered(&LogFilter { search: " validation ".to_string(), ..everything() });
+   assert_eq!(found.len(), 1);
+   assert!(found[0].line().contains("ERROR wgpu] Validation Error"), "{}", found[0].line());
+}
+
+#[test]
+fn records_are_captured_even_when_stderr_is_silent() {
+   let buffer = LogBuffer::new(10);
+   let stderr = env_logger::Builder::new().filter_level(LevelFilter::Off).build();
+   let logger = ConsoleLogger::new(stderr, buffer.clone(), LevelFilter::Info);
+   assert_eq!(logger.max_level(), LevelFilter::Info);
+
+   logger.log(&Record::builder().level(Level::Warn).target("resize").args(format_args!("physical_size: 0x0")).build());
+   logger.log(&Record::builder().level(Level::Debug).target("resize").args(format_args!("too chatty")).build());
+
+   let records = buffer.filtered(&everything());
+   assert_eq!(records.len(), 1);
+   assert_eq!((records[0].level, records[0].target.as_str(), records[0].message.as_str()), (Level::Warn, "resize", "physical_size: 0x0"));
+}
@@ -0,0 +1,82 @@
+use wgpu_template::inbuilt::obj_loader::{load_obj, parse_obj, ObjError};
+
+fn fixture(name: &str) -> String {
+   format!("{}/tests/fixtures/{name}", env!("CARGO_MANIFEST_DIR"))
+}
+
+#[test]
+fn quad_is_triangulated_and_keeps_attributes() {
+   let meshes = load_obj(fixture("quad.obj")).unwrap();
+   assert_eq!(meshes.len(), 1);
+
+   let quad = &meshes[0];
+   assert_eq!(quad.name, "quad");
+   assert_eq!(quad.vertices.len(), 4);
+   assert_eq!(quad.indices, vec![0, 1, 2, 0, 2, 3]);
+
+   assert_eq!(quad.vertices[2].position, [1.0, 1.0, 0.0]);
+   assert_eq!(quad.vertices[2].uv, [1.0, 1.0]);
+   assert!(quad.vertices.iter().all(|v| v.normal == [0.0, 0.0, 1.0]));
+}
+
+#[test]
+fn objects_are_split_and_shared_vertices_deduplicated() {
+   let meshes = load_obj(fixture("two_objects.obj")).unwrap();
+   assert_eq!(meshes.len(), 2);
+
+   let first = &meshes[0];
+   assert_eq!(first.name, "first");
+   assert_eq!(first.vertices.len(), 4);
+   assert_eq!(first.indices, vec![0, 1, 2, 2, 1, 3]);
+
+   // negative indices are relative to the end of the vertex list
+   let second = &meshes[1];
+   assert_eq!(second.name, "second");
+   assert_eq!(second.indices, vec![0, 1, 2]);
+   assert_eq!(second.vertices[0].position, [5.0, 0.0, 0.0]);
+   assert_eq!(second.vertices[0].normal, [0.0, 0.0, 1.0]);
+   assert_eq!(second.vertices[0].uv, [0.0, 0.0]);
+}
+
+#[test]
+fn out_of_range_index_reports_line() {
+   match load_obj(fixture("bad_index.obj")) {
+      Err(ObjError::Parse { line, message }) => {
+         assert_eq!(line, 5);
+         assert!(message.contains("out of range"), "{message}");
+      }
+      other => panic!("expected parse error, got {:?}", other.map(|m| m.len())),
+   }
+}
+
+#[test]
+fn malformed_lines_are_rejected() {
+   let cases = [
+      ("v 1.0 nope 0.0", 1),
+      ("v 1.0 2.0", 1),
+      ("v 0 0 0\nv 1 0 0\nf 1 2", 3),
+      ("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 1/1 2 3", 4),
+      ("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2", 4),
+   ];
+
+   for (source, expected_line) in cases {
+      match parse_obj(source) {
+         Err(ObjError::Parse { line, .. }) => assert_eq!(line, expected_line, "{source}"),
+         other => panic!("expected parse error for {source:?}, got {:?}", other.map(|m| m.len())),
+      }
+   }
+}
+
+#[test]
+fn unsupported_keywords_are_skipped() {
+   let source = "v 0 0 0\nv 1 0 0\nvp 0.5 0.5\nv 0 1 0\nl 1 2\ncstype bspline\nbogus 1 2 3\nf 1 2 3\n";
+   let meshes = parse_obj(source).unwrap();
+   assert_eq!(meshes.len(), 1);
+   assert_eq!(meshes[0].indices, vec![0, 1, 2]);
+   assert_eq!(meshes[0].vertices[2].position, [0.0, 1.0, 0.0]);
+}
+
+#[test]
+fn missing_file_is_io_error() {
+   assert!(matches!(load_obj(fixture("does_not_exist.obj")), Err(ObjError::Io(_))));
+}
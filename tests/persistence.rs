@@ -0,0 +1,72 @@
+mod common;
+
+use common::temp_path;
+use wgpu_template::bundles::automata::automata_compute_pipeline::{Automata, Boundary};
+use wgpu_template::packages::persistence_package::{load, load_or_default, save, AppSettings, PersistencePackage};
+use wgpu_template::packages::recorder_package::RecordingFormat;
+
+#[test]
+fn settings_round_trip() {
+   let dir = temp_path("persistence_round_trip");
+   let path = dir.join(PersistencePackage::SETTINGS_FILE);
+
+   let mut settings = AppSettings::default();
+   settings.automata.size = 300;
+   settings.automata.automata = Automata::SmoothLife;
+   settings.automata.boundary = Boundary::Wrap;
+   settings.recording.format = RecordingFormat::PngSequence;
+   settings.debug_draw = true;
+
+   assert!(load::<AppSettings>(&path).unwrap().is_none());
+   save(&path, &settings).unwrap();
+   assert_eq!(PersistencePackage::with_directory(&dir).load_settings(), settings);
+
+   std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn missing_fields_take_their_default() {
+   let dir = temp_path("persistence_partial");
+   let path = dir.join(PersistencePackage::SETTINGS_FILE);
+   std::fs::create_dir_all(&dir).unwrap();
+   std::fs::write(&path, "(automata: (brush_size: 42), debug_draw: true)").unwrap();
+
+   let settings: AppSettings = load_or_default(&path);
+   assert_eq!(settings.automata.brush_size, 42);
+   assert_eq!(settings.automata.size, AppSettings::default().automata.size);
+   assert!(settings.debug_draw);
+   assert!(settings.screenshot_include_ui);
+
+   std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn corrupt_files_fall_back_to_defaults_and_are_kept_aside() {
+   let dir = temp_path("persistence_corrupt");
+   let path = dir.join(PersistencePackage::SETTINGS_FILE);
+   std::fs::create_dir_all(&dir).unwrap();
+   std::fs::write(&path, "(automata: (size: \"not a number\"").unwrap();
+
+   let settings: AppSettings = load_or_default(&path);
+   assert_eq!(settings, AppSettings::default());
+   assert!(!path.exists());
+   assert!(dir.join("settings.ron.corrupt").exists());
+
+   std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn egui_memory_survives_a_save() {
+   let dir = temp_path("persistence_memory");
+   let ctx = egui::Context::default();
+   ctx.memory_mut(|m| m.data.insert_persisted(egui::Id::new("open"), true));
+
+   let package = PersistencePackage::with_directory(&dir);
+   package.save(&ctx, &AppSettings::default()).unwrap();
+
+   let restored = egui::Context::default();
+   package.restore_memory(&restored);
+   assert_eq!(restored.memory_mut(|m| m.data.get_persisted::<bool>(egui::Id::new("open"))), Some(true));
+
+   std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,104 @@
+mod common;
+
+use common::device;
+use wgpu::util::DeviceExt;
+use wgpu_template::inbuilt::readback::{padded_bytes_per_row, to_vec, unpad_rows, Readback};
+
+#[test]
+fn rows_are_padded_to_copy_alignment() {
+   assert_eq!(padded_bytes_per_row(64, 4), 256);
+   assert_eq!(padded_bytes_per_row(65, 4), 512);
+   assert_eq!(padded_bytes_per_row(1, 4), 256);
+   assert_eq!(padded_bytes_per_row(1920, 4), 7680);
+}
+
+#[test]
+fn unpadding_keeps_only_pixel_bytes() {
+   let (width, height) = (3, 2);
+   let padded = padded_bytes_per_row(width, 4) as usize;
+
+   let mut data = vec![0xff; padded * height];
+   for row in 0..height {
+      for i in 0..width as usize * 4 {
+         data[row * padded + i] = (row * 100 + i) as u8;
+      }
+   }
+
+   let packed = unpad_rows(&data, width as usize * 4, padded, height);
+   assert_eq!(packed.len(), 24);
+   assert_eq!(packed[..12], (0..12).collect::<Vec<u8>>()[..]);
+   assert_eq!(packed[12..], (100..112).collect::<Vec<u8>>()[..]);
+}
+
+#[test]
+fn bytes_become_typed_values() {
+   let values = [1.5f32, -2.0, 1e6];
+   let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_ne_bytes()).collect();
+
+   // offset by one so the data isn't aligned for f32
+   let mut unaligned = vec![0u8];
+   unaligned.extend(&bytes);
+   assert_eq!(to_vec::<f32>(&unaligned[1..]), values);
+}
+
+#[test]
+fn buffers_and_textures_read_back() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let values: Vec<u32> = (0..64).collect();
+   let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: None,
+      contents: bytemuck::cast_slice(&values),
+      usage: wgpu::BufferUsages::COPY_SRC,
+   });
+
+   // 3 pixels wide so every row is padded
+   let (width, height) = (3, 2);
+   let texels: Vec<u8> = (0..width * height * 4).map(|i| i as u8).collect();
+   let texture = device.create_texture_with_data(&queue, &wgpu::TextureDescriptor {
+      label: None,
+      size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: wgpu::TextureFormat::Rgba8Unorm,
+      usage: wgpu::TextureUsages::COPY_SRC,
+      view_formats: &[],
+   }, wgpu::util::TextureDataOrder::LayerMajor, &texels);
+
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+   let from_buffer = Readback::<u32>::buffer(&device, &mut encoder, &buffer, 16, 32);
+   let from_texture = Readback::<u8>::texture(&device, &mut encoder, &texture).unwrap();
+   queue.submit(std::iter::once(encoder.finish()));
+
+   assert_eq!(from_buffer.map().wait(&device).unwrap(), values[4..12]);
+   assert_eq!(from_texture.map().wait(&device).unwrap(), texels);
+}
+
+#[test]
+fn then_callbacks_run_when_the_device_is_polled() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let values: Vec<u32> = (0..16).collect();
+   let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: None,
+      contents: bytemuck::cast_slice(&values),
+      usage: wgpu::BufferUsages::COPY_SRC,
+   });
+
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+   let readback = Readback::<u32>::buffer(&device, &mut encoder, &buffer, 0, 64);
+   queue.submit(std::iter::once(encoder.finish()));
+
+   let (sender, receiver) = std::sync::mpsc::channel();
+   readback.then(move |data| sender.send(data).unwrap());
+   device.poll(wgpu::Maintain::Wait);
+
+   assert_eq!(receiver.try_recv().unwrap().unwrap(), values);
+}
@@ -0,0 +1,73 @@
+mod common;
+
+use common::temp_path;
+use wgpu_template::packages::recorder_package::{FrameSchedule, FrameSink, RecordedFrame, RecordingFormat};
+use wgpu_template::packages::screenshot_package::CapturedImage;
+
+fn image(value: u8) -> CapturedImage {
+   CapturedImage { width: 4, height: 3, rgba: vec![value; 4 * 3 * 4] }
+}
+
+#[test]
+fn schedule_starts_with_the_initial_state_and_captures_every_nth_generation() {
+   let mut schedule = FrameSchedule::new(3, 2);
+   let frames: Vec<RecordedFrame> = std::iter::from_fn(|| schedule.next_frame()).collect();
+
+   let expected = [(false, true), (true, false), (true, true), (true, false), (true, true)];
+   assert_eq!(frames.len(), expected.len());
+   for (frame, (step, capture)) in frames.iter().zip(expected) {
+      assert_eq!(*frame, RecordedFrame { step, capture });
+   }
+
+   assert!(schedule.is_finished());
+   assert_eq!(schedule.captured(), 3);
+}
+
+#[test]
+fn png_sequence_is_numbered() {
+   let dir = temp_path("png_sequence");
+   let mut sink = FrameSink::create(RecordingFormat::PngSequence, &dir, 4, 3, 30).unwrap();
+   for i in 0..3 {
+      sink.write(image(i * 50)).unwrap();
+   }
+   assert_eq!(sink.finish().unwrap(), dir);
+
+   let mut names: Vec<String> = std::fs::read_dir(&dir).unwrap()
+       .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+       .collect();
+   names.sort();
+   assert_eq!(names, ["frame_00000.png", "frame_00001.png", "frame_00002.png"]);
+
+   std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn gif_has_every_frame_with_the_requested_delay() {
+   let dir = temp_path("gif");
+   let mut sink = FrameSink::create(RecordingFormat::Gif, &dir.join("recording"), 4, 3, 25).unwrap();
+   for i in 0..5 {
+      sink.write(image(i * 40)).unwrap();
+   }
+   let path = sink.finish().unwrap();
+   assert_eq!(path.extension().unwrap(), "gif");
+
+   let mut options = gif::DecodeOptions::new();
+   options.set_color_output(gif::ColorOutput::RGBA);
+   let mut decoder = options.read_info(std::fs::File::open(&path).unwrap()).unwrap();
+   assert_eq!((decoder.width(), decoder.height()), (4, 3));
+
+   let mut frames = 0;
+   while let Some(frame) = decoder.read_next_frame().unwrap() {
+      assert_eq!(frame.delay, 4);
+      frames += 1;
+   }
+   assert_eq!(frames, 5);
+
+   std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn gif_rejects_sizes_past_u16() {
+   let dir = temp_path("gif_too_large");
+   assert!(FrameSink::create(RecordingFormat::Gif, &dir.join("recording"), 70_000, 10, 30).is_err());
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::device;
+use std::cell::Cell;
+use wgpu::TextureFormat;
+use wgpu_template::inbuilt::render_graph::{RenderGraph, RenderTargetPool, Resource, TargetSize, TransientTexture};
+
+const CELLS: Resource = Resource::Texture("cells");
+const SCENE: Resource = Resource::Texture("scene");
+
+#[test]
+fn independent_passes_keep_their_order() {
+   let mut graph = RenderGraph::new();
+   graph.add_pass("a").write(Resource::Buffer("a")).run(|_| Ok(()));
+   graph.add_pass("b").write(Resource::Buffer("b")).run(|_| Ok(()));
+   graph.add_pass("c").run(|_| Ok(()));
+
+   assert_eq!(graph.pass_order().unwrap(), ["a", "b", "c"]);
+}
+
+#[test]
+fn readers_run_after_writers() {
+   let mut graph = RenderGraph::new();
+   graph.add_pass("draw").read(CELLS).write(Resource::Swapchain).run(|_| Ok(()));
+   graph.add_pass("step").read(CELLS).write(CELLS).run(|_| Ok(()));
+   graph.add_pass("seed").write(CELLS).run(|_| Ok(()));
+
+   assert_eq!(graph.pass_order().unwrap(), ["seed", "step", "draw"]);
+}
+
+#[test]
+fn read_write_passes_keep_their_order() {
+   let mut graph = RenderGraph::new();
+   graph.add_pass("queue").read(CELLS).write(CELLS).run(|_| Ok(()));
+   graph.add_pass("step").read(CELLS).write(CELLS).run(|_| Ok(()));
+
+   assert_eq!(graph.pass_order().unwrap(), ["queue", "step"]);
+}
+
+#[test]
+fn overlays_go_on_top_of_the_swapchain() {
+   let mut graph = RenderGraph::new();
+   graph.add_pass("ui").read(Resource::Swapchain).write(Resource::Swapchain).run(|_| Ok(()));
+   graph.add_pass("tonemap").read(SCENE).write(Resource::Swapchain).run(|_| Ok(()));
+   graph.add_pass("scene").write(SCENE).run(|_| Ok(()));
+
+   assert_eq!(graph.pass_order().unwrap(), ["scene", "tonemap", "ui"]);
+}
+
+#[test]
+fn cycles_are_reported() {
+   let mut graph = RenderGraph::new();
+   graph.add_pass("a").read(Resource::Buffer("x")).write(Resource::Buffer("y")).run(|_| Ok(()));
+   graph.add_pass("b").read(Resource::Buffer("y")).write(Resource::Buffer("x")).run(|_| Ok(()));
+
+   let err = graph.pass_order().unwrap_err();
+   assert!(err.contains("cycle") && err.contains('a') && err.contains('b'), "{err}");
+}
+
+#[test]
+fn unwritten_transients_are_reported() {
+   let mut graph = RenderGraph::new();
+   let scene = graph.transient_texture("scene", TransientTexture::new(TextureFormat::Rgba16Float, TargetSize::Swapchain));
+   graph.add_pass("tonemap").read(scene).write(Resource::Swapchain).run(|_| Ok(()));
+
+   assert!(graph.pass_order().unwrap_err().contains("scene"));
+}
+
+#[test]
+fn bad_graphs_are_errors_instead_of_panics() {
+   let Some((device, _queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let swapchain = device.create_texture(&wgpu::TextureDescriptor {
+      label: None,
+      size: wgpu::Extent3d { width: 4, height: 4, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: TextureFormat::Rgba8Unorm,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+      view_formats: &[],
+   }).create_view(&wgpu::TextureViewDescriptor::default());
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+   let mut targets = RenderTargetPool::new();
+   let ran = Cell::new(0);
+
+   // a cycle fails before any pass runs
+   let mut graph = RenderGraph::new();
+   graph.add_pass("a").read(Resource::Buffer("x")).write(Resource::Buffer("y")).run(|_| { ran.set(ran.get() + 1); Ok(()) });
+   graph.add_pass("b").read(Resource::Buffer("y")).write(Resource::Buffer("x")).run(|_| { ran.set(ran.get() + 1); Ok(()) });
+   assert!(graph.execute_at(&device, &mut encoder, &swapchain, (4, 4), &mut targets).unwrap_err().contains("cycle"));
+   assert_eq!(ran.get(), 0);
+
+   // asking for a texture the graph doesn't own stops the passes after it
+   let mut graph = RenderGraph::new();
+   graph.add_pass("draw").write(CELLS).run(|ctx| ctx.view(CELLS).map(|_| ()));
+   graph.add_pass("after").read(CELLS).write(Resource::Swapchain).run(|_| { ran.set(ran.get() + 1); Ok(()) });
+   let err = graph.execute_at(&device, &mut encoder, &swapchain, (4, 4), &mut targets).unwrap_err();
+   assert!(err.contains("`draw`") && err.contains("`cells`"), "{err}");
+   assert_eq!(ran.get(), 0);
+
+   let mut graph = RenderGraph::new();
+   graph.add_pass("swapchain").write(Resource::Swapchain).run(|ctx| { ctx.view(Resource::Swapchain)?; ran.set(ran.get() + 1); Ok(()) });
+   graph.execute_at(&device, &mut encoder, &swapchain, (4, 4), &mut targets).unwrap();
+   assert_eq!(ran.get(), 1);
+}
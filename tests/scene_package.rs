@@ -0,0 +1,74 @@
+mod common;
+
+use common::device;
+use cgmath::{Deg, Vector3};
+use wgpu_template::packages::scene_package::{SceneFull, SceneGraph, ScenePackage, Transform, MAX_NODES};
+
+fn close(a: Vector3<f32>, b: Vector3<f32>) -> bool {
+   let d = a - b;
+   d.x.abs() < 1e-5 && d.y.abs() < 1e-5 && d.z.abs() < 1e-5
+}
+
+#[test]
+fn children_inherit_the_parent_transform() {
+   let mut graph = SceneGraph::new();
+   let parent = graph.add("parent", Transform::from_position(10.0, 0.0).with_rotation_z(Deg(90.0)).with_scale(2.0, 2.0));
+   let child = graph.add_child(parent, "child", Transform::from_position(1.0, 0.0));
+
+   // the child's offset is scaled by 2, turned to point up and moved by the parent's position
+   let origin = graph.local_to_world(child, Vector3::new(0.0, 0.0, 0.0));
+   assert!(close(origin, Vector3::new(10.0, 2.0, 0.0)), "{origin:?}");
+
+   let back = graph.world_to_local(child, origin).unwrap();
+   assert!(close(back, Vector3::new(0.0, 0.0, 0.0)), "{back:?}");
+
+   graph.transform_mut(parent).position.x = 0.0;
+   assert!(close(graph.local_to_world(child, Vector3::new(0.0, 0.0, 0.0)), Vector3::new(0.0, 2.0, 0.0)));
+}
+
+#[test]
+fn reparenting_updates_both_sides_and_rejects_cycles() {
+   let mut graph = SceneGraph::new();
+   let a = graph.add("a", Transform::default());
+   let b = graph.add_child(a, "b", Transform::default());
+   let c = graph.add_child(b, "c", Transform::default());
+
+   assert!(graph.set_parent(a, Some(c)).is_err());
+   assert!(graph.set_parent(b, Some(b)).is_err());
+
+   graph.set_parent(c, Some(a)).unwrap();
+   assert_eq!(graph.node(a).children(), [b, c]);
+   assert!(graph.node(b).children().is_empty());
+   assert_eq!(graph.node(c).parent(), Some(a));
+
+   graph.set_parent(b, None).unwrap();
+   assert_eq!(graph.roots().collect::<Vec<_>>(), [a, b]);
+}
+
+#[test]
+fn zero_scale_has_no_local_space() {
+   let mut graph = SceneGraph::new();
+   let flat = graph.add("flat", Transform::default().with_scale(0.0, 1.0));
+   assert!(graph.world_to_local(flat, Vector3::new(1.0, 1.0, 0.0)).is_none());
+}
+
+#[test]
+fn a_full_scene_still_updates_and_refuses_more_nodes() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let mut scene = ScenePackage::new(&device);
+   let root = scene.add("root", Transform::default()).unwrap();
+   for i in 1..MAX_NODES {
+      scene.add_child(root, &format!("node {i}"), Transform::from_position(i as f32, 0.0)).unwrap();
+   }
+   scene.transform_mut(root).position.y = 1.0;
+   scene.update(&queue);
+   assert_eq!(scene.graph().len(), MAX_NODES);
+
+   assert_eq!(scene.add("extra", Transform::default()), Err(SceneFull));
+   assert_eq!(scene.add_child(root, "extra", Transform::default()), Err(SceneFull));
+   assert_eq!(scene.graph().len(), MAX_NODES);
+}
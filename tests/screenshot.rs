@@ -0,0 +1,167 @@
+mod common;
+
+use common::{device, temp_path};
+use std::time::{Duration, UNIX_EPOCH};
+use wgpu::TextureFormat;
+use wgpu_template::packages::screenshot_package::{read_texture, read_texture_then, timestamped_name, to_rgba8, CapturedImage, ScreenshotError};
+
+fn offscreen_target(device: &wgpu::Device, format: TextureFormat, width: u32, height: u32) -> wgpu::Texture {
+   device.create_texture(&wgpu::TextureDescriptor {
+      label: None,
+      size: wgpu::Extent3d { width, height, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format,
+      usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+      view_formats: &[],
+   })
+}
+
+#[test]
+fn bgra_is_swizzled_and_float_formats_are_rejected() {
+   let mut bgra = vec![1, 2, 3, 4, 5, 6, 7, 8];
+   to_rgba8(TextureFormat::Bgra8UnormSrgb, &mut bgra).unwrap();
+   assert_eq!(bgra, [3, 2, 1, 4, 7, 6, 5, 8]);
+
+   let mut rgba = vec![1, 2, 3, 4];
+   to_rgba8(TextureFormat::Rgba8Unorm, &mut rgba).unwrap();
+   assert_eq!(rgba, [1, 2, 3, 4]);
+
+   assert!(to_rgba8(TextureFormat::Rgba16Float, &mut []).is_err());
+}
+
+#[test]
+fn names_are_utc_timestamps() {
+   assert_eq!(timestamped_name("screenshot", UNIX_EPOCH), "screenshot_1970-01-01_00-00-00-000.png");
+
+   let time = UNIX_EPOCH + Duration::from_millis(1_700_000_000_042);
+   assert_eq!(timestamped_name("screenshot", time), "screenshot_2023-11-14_22-13-20-042.png");
+
+   let leap_day = UNIX_EPOCH + Duration::from_secs(951_782_400);
+   assert_eq!(timestamped_name("frame", leap_day), "frame_2000-02-29_00-00-00-000.png");
+}
+
+#[test]
+fn saved_png_decodes_to_the_same_pixels() {
+   let image = CapturedImage {
+      width: 2,
+      height: 2,
+      rgba: vec![255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 10, 20, 30, 40],
+   };
+
+   let path = temp_path("screenshot").join("test.png");
+   image.save_png(&path).unwrap();
+
+   let decoder = png::Decoder::new(std::fs::File::open(&path).unwrap());
+   let mut reader = decoder.read_info().unwrap();
+   let mut pixels = vec![0; reader.output_buffer_size()];
+   let info = reader.next_frame(&mut pixels).unwrap();
+
+   assert_eq!((info.width, info.height, info.color_type), (2, 2, png::ColorType::Rgba));
+   assert_eq!(pixels, image.rgba);
+
+   std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+}
+
+#[test]
+fn offscreen_renders_read_back_as_rgba() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   // the left two columns red and the last one blue, 3 wide so the copy rows are padded
+   let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+      label: None,
+      source: wgpu::ShaderSource::Wgsl("
+@vertex
+fn vs_main(@builtin(vertex_index) index: u32) -> @builtin(position) vec4<f32> {
+   let uv = vec2<f32>(f32((index << 1u) & 2u), f32(index & 2u));
+   return vec4<f32>(uv * 2.0 - 1.0, 0.0, 1.0);
+}
+
+@fragment
+fn fs_main(@builtin(position) position: vec4<f32>) -> @location(0) vec4<f32> {
+   if position.x < 2.0 { return vec4<f32>(1.0, 0.0, 0.0, 1.0); }
+   return vec4<f32>(0.0, 0.0, 1.0, 1.0);
+}
+".into()),
+   });
+
+   let format = TextureFormat::Bgra8Unorm;
+   let pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+      label: None,
+      layout: None,
+      vertex: wgpu::VertexState { module: &module, entry_point: "vs_main", buffers: &[] },
+      fragment: Some(wgpu::FragmentState {
+         module: &module,
+         entry_point: "fs_main",
+         targets: &[Some(format.into())],
+      }),
+      primitive: wgpu::PrimitiveState::default(),
+      depth_stencil: None,
+      multisample: wgpu::MultisampleState::default(),
+      multiview: None,
+   });
+
+   let target = offscreen_target(&device, format, 3, 2);
+   let view = target.create_view(&wgpu::TextureViewDescriptor::default());
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+   {
+      let mut pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+         label: None,
+         color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view: &view,
+            resolve_target: None,
+            ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: wgpu::StoreOp::Store },
+         })],
+         depth_stencil_attachment: None,
+         occlusion_query_set: None,
+         timestamp_writes: None,
+      });
+      pass.set_pipeline(&pipeline);
+      pass.draw(0..3, 0..1);
+   }
+   queue.submit(std::iter::once(encoder.finish()));
+
+   let image = read_texture(&device, &queue, &target).unwrap();
+   assert_eq!((image.width, image.height), (3, 2));
+   let row = [255, 0, 0, 255, 255, 0, 0, 255, 0, 0, 255, 255];
+   assert_eq!(image.rgba, [row, row].concat());
+
+   let hdr = offscreen_target(&device, TextureFormat::Rgba16Float, 3, 2);
+   assert!(matches!(read_texture(&device, &queue, &hdr), Err(ScreenshotError::UnsupportedFormat(TextureFormat::Rgba16Float))));
+}
+
+#[test]
+fn read_texture_then_hands_over_the_image_when_polled() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let texels: Vec<u8> = (0..3 * 2 * 4).map(|i| i as u8).collect();
+   let texture = wgpu::util::DeviceExt::create_texture_with_data(&device, &queue, &wgpu::TextureDescriptor {
+      label: None,
+      size: wgpu::Extent3d { width: 3, height: 2, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: TextureFormat::Bgra8Unorm,
+      usage: wgpu::TextureUsages::COPY_SRC,
+      view_formats: &[],
+   }, wgpu::util::TextureDataOrder::LayerMajor, &texels);
+
+   let (sender, receiver) = std::sync::mpsc::channel();
+   read_texture_then(&device, &queue, &texture, move |image| sender.send(image).unwrap()).unwrap();
+   device.poll(wgpu::Maintain::Wait);
+
+   let image = receiver.try_recv().unwrap().unwrap();
+   let mut expected = texels;
+   to_rgba8(TextureFormat::Bgra8Unorm, &mut expected).unwrap();
+   assert_eq!((image.width, image.height, image.rgba), (3, 2, expected));
+
+   let hdr = offscreen_target(&device, TextureFormat::Rgba16Float, 3, 2);
+   assert!(read_texture_then(&device, &queue, &hdr, |_| {}).is_err());
+}
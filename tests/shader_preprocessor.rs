@@ -0,0 +1,52 @@
+use wgpu_template::inbuilt::shader_preprocessor::{preprocess, ShaderDefines};
+use wgpu_template::packages::shader_package::validate_preprocessed;
+
+fn resolve(include: &str) -> Option<String> {
+   match include {
+      "common/a.wgsl" => Some("const A = 1;\n#include \"common/b.wgsl\"\nconst A2 = 2;\n".to_string()),
+      "common/b.wgsl" => Some("const B = 3;\n".to_string()),
+      _ => None,
+   }
+}
+
+#[test]
+fn output_lines_map_back_to_the_file_they_came_from() {
+   let source = "#include \"common/a.wgsl\"\n#ifdef FLAG\nconst F = 0;\n#endif\nconst TOP = 4;\n";
+   let processed = preprocess("top.wgsl", source, &ShaderDefines::new().flag("FLAG"), &mut resolve).unwrap();
+
+   let lines: Vec<&str> = processed.source.lines().collect();
+   let origins: Vec<_> = (1..=lines.len()).map(|line| processed.origin(line).unwrap()).collect();
+   assert_eq!(origins, [
+      ("common/a.wgsl", 1),
+      ("common/b.wgsl", 1),
+      ("common/a.wgsl", 2),
+      ("common/a.wgsl", 3),
+      ("top.wgsl", 1),
+      ("top.wgsl", 2),
+      ("top.wgsl", 3),
+      ("top.wgsl", 4),
+      ("top.wgsl", 5),
+   ]);
+   assert_eq!(lines[6], "const F = 0;");
+   assert_eq!(lines[8], "const TOP = 4;");
+
+   assert_eq!(processed.origin(0), None);
+   assert_eq!(processed.origin(lines.len() + 1), None);
+}
+
+#[test]
+fn naga_errors_point_at_the_original_file_and_line() {
+   // a parse error on line 3 of the top file, after 4 lines of includes
+   let source = "#include \"common/a.wgsl\"\n\nconst BROKEN = ;\n";
+   let processed = preprocess("top.wgsl", source, &ShaderDefines::new(), &mut resolve).unwrap();
+   let err = validate_preprocessed(&processed, "top.wgsl").unwrap_err();
+   assert!(err.starts_with("top.wgsl:3:16: "), "{err}");
+   assert!(err.ends_with("const BROKEN = ;"), "{err}");
+
+   // a validation error inside an include is reported in the include
+   let processed = preprocess("top.wgsl", "#include \"common/bad.wgsl\"\n", &ShaderDefines::new(), &mut |include| {
+      (include == "common/bad.wgsl").then(|| "\nfn f() -> f32 {\n   return 1u;\n}\n".to_string())
+   }).unwrap();
+   let err = validate_preprocessed(&processed, "top.wgsl").unwrap_err();
+   assert!(err.starts_with("common/bad.wgsl:"), "{err}");
+}
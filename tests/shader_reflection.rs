@@ -0,0 +1,158 @@
+mod common;
+
+use common::device;
+use wgpu::{BindingType, SamplerBindingType, TextureSampleType};
+use wgpu::util::DeviceExt;
+use wgpu_template::inbuilt::shader_reflection::{ReflectedShader, ShaderLayout};
+
+const SAMPLED: &str = "
+@group(0) @binding(0) var heights: texture_2d<f32>;
+@group(0) @binding(1) var height_sampler: sampler;
+@group(0) @binding(2) var cells: texture_2d<f32>;
+@group(0) @binding(3) var shadow: texture_depth_2d;
+@group(0) @binding(4) var shadow_sampler: sampler_comparison;
+@group(0) @binding(5) var<storage, read_write> result: array<f32>;
+
+@compute @workgroup_size(1)
+fn main() {
+   result[0] = textureSampleLevel(heights, height_sampler, vec2(0.5), 0.0).r;
+   result[1] = textureLoad(cells, vec2(0, 0), 0).r;
+   result[2] = textureSampleCompareLevel(shadow, shadow_sampler, vec2(0.5), 0.5);
+}
+";
+
+fn binding_type(shader: &ReflectedShader, name: &str) -> BindingType {
+   shader.bindings().into_iter().find(|b| b.name == name).unwrap_or_else(|| panic!("no `{name}`")).entry.ty
+}
+
+fn sample_type(shader: &ReflectedShader, name: &str) -> TextureSampleType {
+   let BindingType::Texture { sample_type, .. } = binding_type(shader, name) else { panic!("`{name}` is not a texture") };
+   sample_type
+}
+
+#[test]
+fn only_sampled_textures_are_filterable() {
+   let shader = ReflectedShader::from_wgsl(SAMPLED, "sampled.wgsl").unwrap();
+
+   assert_eq!(sample_type(&shader, "heights"), TextureSampleType::Float { filterable: true });
+   assert_eq!(sample_type(&shader, "cells"), TextureSampleType::Float { filterable: false });
+   assert_eq!(sample_type(&shader, "shadow"), TextureSampleType::Depth);
+   assert_eq!(binding_type(&shader, "height_sampler"), BindingType::Sampler(SamplerBindingType::Filtering));
+   assert_eq!(binding_type(&shader, "shadow_sampler"), BindingType::Sampler(SamplerBindingType::Comparison));
+}
+
+#[test]
+fn non_filterable_textures_get_non_filtering_samplers() {
+   let shader = ReflectedShader::from_wgsl(SAMPLED, "sampled.wgsl").unwrap().non_filterable("heights");
+
+   assert_eq!(sample_type(&shader, "heights"), TextureSampleType::Float { filterable: false });
+   assert_eq!(binding_type(&shader, "height_sampler"), BindingType::Sampler(SamplerBindingType::NonFiltering));
+   assert_eq!(binding_type(&shader, "shadow_sampler"), BindingType::Sampler(SamplerBindingType::Comparison));
+}
+
+#[test]
+fn binding_names_are_checked() {
+   let shader = ReflectedShader::from_wgsl(SAMPLED, "sampled.wgsl").unwrap();
+   let all = ["heights", "height_sampler", "cells", "shadow", "shadow_sampler", "result"];
+
+   assert_eq!(shader.check_bindings(0, &all), Ok(()));
+   assert!(shader.check_bindings(0, &all[1..]).unwrap_err().contains("`heights`"));
+   assert!(shader.check_bindings(0, &[&all[..], &["missing"]].concat()).unwrap_err().contains("`missing`"));
+}
+
+#[test]
+fn bind_group_mistakes_are_errors() {
+   let Some((device, _queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let shader = ReflectedShader::from_wgsl(SAMPLED, "sampled.wgsl").unwrap();
+   let layout = shader.bind_group_layout(&device, 0, None);
+   let buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+      label: None,
+      contents: &[0; 16],
+      usage: wgpu::BufferUsages::STORAGE,
+   });
+
+   let unknown = shader.bind_group(0, &layout).buffer("missing", &buffer).build(&device);
+   assert!(unknown.unwrap_err().contains("no binding named `missing`"));
+
+   let wrong_kind = shader.bind_group(0, &layout).buffer("heights", &buffer).build(&device);
+   assert!(wrong_kind.unwrap_err().contains("`heights`"));
+
+   let incomplete = shader.bind_group(0, &layout).buffer("result", &buffer).build(&device);
+   assert!(incomplete.unwrap_err().contains("missing `heights`"));
+}
+
+#[test]
+fn r32float_textures_can_be_sampled_when_marked() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let source = "
+@group(0) @binding(0) var heights: texture_2d<f32>;
+@group(0) @binding(1) var height_sampler: sampler;
+@group(0) @binding(2) var<storage, read_write> result: array<f32>;
+
+@compute @workgroup_size(1)
+fn main() {
+   result[0] = textureSampleLevel(heights, height_sampler, vec2(0.5), 0.0).r;
+}
+";
+   let shader = ReflectedShader::from_wgsl(source, "heights.wgsl").unwrap().non_filterable("heights");
+
+   device.push_error_scope(wgpu::ErrorFilter::Validation);
+
+   let layout = ShaderLayout::new(&device, &shader, "heights");
+   let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+      label: None,
+      source: wgpu::ShaderSource::Wgsl(source.into()),
+   });
+   let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+      label: None,
+      layout: Some(&layout.pipeline_layout),
+      module: &module,
+      entry_point: "main",
+   });
+
+   let texture = device.create_texture(&wgpu::TextureDescriptor {
+      label: None,
+      size: wgpu::Extent3d { width: 1, height: 1, depth_or_array_layers: 1 },
+      mip_level_count: 1,
+      sample_count: 1,
+      dimension: wgpu::TextureDimension::D2,
+      format: wgpu::TextureFormat::R32Float,
+      usage: wgpu::TextureUsages::TEXTURE_BINDING,
+      view_formats: &[],
+   });
+   let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+   let sampler = device.create_sampler(&wgpu::SamplerDescriptor::default());
+   let buffer = device.create_buffer(&wgpu::BufferDescriptor {
+      label: None,
+      size: 4,
+      usage: wgpu::BufferUsages::STORAGE,
+      mapped_at_creation: false,
+   });
+
+   let bind_group = shader.bind_group(0, &layout.bind_group_layouts[0])
+       .texture_view("heights", &view)
+       .sampler("height_sampler", &sampler)
+       .buffer("result", &buffer)
+       .build(&device)
+       .unwrap();
+
+   let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+   {
+      let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+      pass.set_pipeline(&pipeline);
+      pass.set_bind_group(0, &bind_group, &[]);
+      pass.dispatch_workgroups(1, 1, 1);
+   }
+   queue.submit([encoder.finish()]);
+
+   let error = pollster::block_on(device.pop_error_scope());
+   assert!(error.is_none(), "{error:?}");
+}
@@ -0,0 +1,245 @@
+use std::path::{Path, PathBuf};
+use wgpu::{BindGroupLayoutEntry, BindingType, SamplerBindingType, TextureSampleType};
+use wgpu_template::bundles::automata::automata_compute_pipeline::{Automata, Boundary};
+use wgpu_template::bundles::automata::automata_package::AutomataPackage;
+use wgpu_template::bundles::automata::automata_pipeline::AutomataRenderPipeline;
+use wgpu_template::bundles::automata::automata_queue_compute_pipeline::{QueueComputePipeline, QUEUE_LENGTH};
+use wgpu_template::bundles::debug_draw::debug_draw_pipeline::DebugDrawPipeline;
+use wgpu_template::bundles::post_process::post_process_bundle::*;
+use wgpu_template::inbuilt::shader_preprocessor::{preprocess, ShaderDefines};
+use wgpu_template::inbuilt::shader_reflection::ReflectedShader;
+use wgpu_template::packages::camera_package::CameraPackage;
+use wgpu_template::packages::globals_package::{FrameGlobals, GlobalsPackage};
+use wgpu_template::packages::scene_package::ScenePackage;
+use wgpu_template::packages::texture_package::TexturePackage;
+use wgpu_template::packages::uniform_package::UniformPackage;
+use wgpu_template::pipelines::instanced_render_pipeline::InstancedRenderPipeline;
+use wgpu_template::pipelines::textured_quad_pipeline::{TexturedQuad, TexturedQuadPipeline};
+
+fn shader_root() -> PathBuf {
+   PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("src/shaders")
+}
+
+/// preprocesses straight from disk, the same way `ShaderPackage` does in dev mode
+fn load(path: &str, defines: &ShaderDefines) -> ReflectedShader {
+   let root = shader_root();
+   let source = std::fs::read_to_string(root.join(path)).unwrap_or_else(|err| panic!("failed to read {path}: {err}"));
+
+   let processed = preprocess(path, &source, defines, &mut |include| std::fs::read_to_string(root.join(include)).ok())
+       .unwrap_or_else(|err| panic!("{err}"));
+
+   ReflectedShader::from_wgsl(&processed.source, path).unwrap_or_else(|err| panic!("{err}"))
+}
+
+fn wgsl_files(dir: &Path, found: &mut Vec<String>) {
+   for entry in std::fs::read_dir(dir).unwrap() {
+      let path = entry.unwrap().path();
+      if path.is_dir() {
+         wgsl_files(&path, found);
+      } else if path.extension().is_some_and(|e| e == "wgsl") {
+         found.push(path.strip_prefix(shader_root()).unwrap().to_string_lossy().replace('\\', "/"));
+      }
+   }
+}
+
+fn boundary_defines(defines: ShaderDefines) -> Vec<ShaderDefines> {
+   [Boundary::Unchecked, Boundary::Dead, Boundary::Wrap].iter()
+       .map(|boundary| boundary.add_define(defines.clone()))
+       .collect()
+}
+
+/// a top level shader, the define sets it is built with and the bind group layouts rust gives it
+type PipelineLayout = (&'static str, Vec<ShaderDefines>, Vec<Vec<BindGroupLayoutEntry>>);
+
+fn pipelines() -> Vec<PipelineLayout> {
+   let camera = CameraPackage::bind_group_layout_entries().to_vec();
+   let automata = AutomataPackage::bind_group_layout_entries().to_vec();
+   let model = ScenePackage::bind_group_layout_entries().to_vec();
+   let texture = TexturePackage::bind_group_layout_entries().to_vec();
+   let globals = GlobalsPackage::bind_group_layout_entries().to_vec();
+   let quad = vec![UniformPackage::<TexturedQuad>::layout_entry(wgpu::ShaderStages::VERTEX_FRAGMENT, true)];
+   // reflected by `QueueComputePipeline` itself, `queue_uniform_matches_queue_length` checks the size it expects
+   let queue = load(QueueComputePipeline::SHADER_PATH, &ShaderDefines::new()).layout_entries(2);
+
+   vec![
+      ("render/test_render_pipeline.wgsl", vec![ShaderDefines::new()], vec![camera.clone()]),
+      (InstancedRenderPipeline::SHADER_PATH, vec![ShaderDefines::new().with("GLOBALS_GROUP", 1)], vec![camera.clone(), globals]),
+      (DebugDrawPipeline::SHADER_PATH, vec![ShaderDefines::new()], vec![camera.clone()]),
+      (TexturedQuadPipeline::SHADER_PATH, vec![ShaderDefines::new()], vec![camera.clone(), texture, quad]),
+      (AutomataRenderPipeline::SHADER_PATH, vec![ShaderDefines::new()], vec![camera, automata.clone(), model]),
+      (Automata::GameOfLife.shader_path(), boundary_defines(ShaderDefines::new().with("WORKGROUP_SIZE", 16)), vec![automata.clone(), automata.clone()]),
+      (Automata::SmoothLife.shader_path(), boundary_defines(ShaderDefines::new().with("WORKGROUP_SIZE", 16)), vec![automata.clone(), automata.clone()]),
+      (QueueComputePipeline::SHADER_PATH, boundary_defines(ShaderDefines::new().with("WORKGROUP_SIZE", 8)), vec![automata.clone(), automata, queue]),
+   ]
+}
+
+/// post effects build their layout from the shader, so only the `params` uniform is checked, against its rust struct if there is one
+fn post_effects() -> Vec<(&'static str, Vec<ShaderDefines>, Option<usize>)> {
+   use std::mem::size_of;
+
+   vec![
+      ("post/bloom_extract.wgsl", vec![ShaderDefines::new()], Some(size_of::<BloomExtractParams>())),
+      ("post/blur.wgsl", vec![ShaderDefines::new()], Some(size_of::<BlurParams>())),
+      ("post/bloom_composite.wgsl", vec![ShaderDefines::new()], Some(size_of::<BloomCompositeParams>())),
+      ("post/color_grading.wgsl", vec![ShaderDefines::new()], Some(size_of::<ColorGradingParams>())),
+      ("post/vignette.wgsl", vec![ShaderDefines::new()], Some(size_of::<VignetteParams>())),
+      ("post/crt.wgsl", vec![ShaderDefines::new()], Some(size_of::<CrtParams>())),
+      ("post/tonemap.wgsl", vec![ShaderDefines::new(), ShaderDefines::new().flag("MANUAL_SRGB")], Some(size_of::<TonemapParams>())),
+      ("post/blit.wgsl", vec![ShaderDefines::new()], None),
+   ]
+}
+
+/// whether a layout entry created in rust can be bound to what the shader declares
+fn compatible(rust: &BindingType, shader: &BindingType) -> bool {
+   match (rust, shader) {
+      (
+         BindingType::Buffer { ty, has_dynamic_offset: _, min_binding_size },
+         BindingType::Buffer { ty: shader_ty, has_dynamic_offset: _, min_binding_size: shader_size },
+      ) => ty == shader_ty && (min_binding_size.is_none() || min_binding_size >= shader_size),
+      (BindingType::Sampler(rust), BindingType::Sampler(shader)) => {
+         (*rust == SamplerBindingType::Comparison) == (*shader == SamplerBindingType::Comparison)
+      }
+      (
+         BindingType::Texture { sample_type, view_dimension, multisampled },
+         BindingType::Texture { sample_type: shader_sample, view_dimension: shader_dimension, multisampled: shader_multisampled },
+      ) => {
+         let samples_match = match (sample_type, shader_sample) {
+            (TextureSampleType::Float { .. }, TextureSampleType::Float { .. }) => true,
+            (a, b) => a == b,
+         };
+         samples_match && view_dimension == shader_dimension && multisampled == shader_multisampled
+      }
+      (a @ BindingType::StorageTexture { .. }, b @ BindingType::StorageTexture { .. }) => a == b,
+      _ => false,
+   }
+}
+
+
+#[test]
+fn every_shader_file_is_covered() {
+   let mut files = vec![];
+   wgsl_files(&shader_root(), &mut files);
+
+   let mut covered: Vec<&str> = pipelines().iter().map(|(path, ..)| *path).collect();
+   covered.extend(post_effects().iter().map(|(path, ..)| *path));
+   for file in files.iter().filter(|file| !file.starts_with("common/")) {
+      assert!(covered.contains(&file.as_str()), "{file} is not checked against a pipeline layout");
+   }
+}
+
+#[test]
+fn common_includes_validate_on_their_own() {
+   let mut files = vec![];
+   wgsl_files(&shader_root().join("common"), &mut files);
+   assert!(!files.is_empty());
+
+   for file in files {
+      for defines in boundary_defines(ShaderDefines::new()) {
+         load(&file, &defines);
+      }
+   }
+}
+
+#[test]
+fn shaders_validate_with_every_define_set() {
+   for (path, variants, _) in pipelines() {
+      for defines in variants {
+         let shader = load(path, &defines);
+         assert!(!shader.module.entry_points.is_empty(), "{path} has no entry points");
+      }
+   }
+}
+
+#[test]
+fn shader_bindings_match_rust_layouts() {
+   for (path, variants, layouts) in pipelines() {
+      for defines in variants {
+         let shader = load(path, &defines);
+
+         assert!(
+            shader.group_count() as usize <= layouts.len(),
+            "{path} uses {} bind groups but the pipeline only has {}", shader.group_count(), layouts.len()
+         );
+
+         for binding in shader.bindings() {
+            let group = binding.group;
+            let rust = layouts[group as usize].iter()
+                .find(|entry| entry.binding == binding.entry.binding)
+                .unwrap_or_else(|| panic!("{path}: `{}` at @group({group}) @binding({}) has no layout entry", binding.name, binding.entry.binding));
+
+            assert!(
+               compatible(&rust.ty, &binding.entry.ty),
+               "{path}: `{}` at @group({group}) is {:?} in the shader but {:?} in rust", binding.name, binding.entry.ty, rust.ty
+            );
+            assert!(
+               rust.visibility.contains(binding.entry.visibility),
+               "{path}: `{}` at @group({group}) is used from {:?} but only visible to {:?}", binding.name, binding.entry.visibility, rust.visibility
+            );
+         }
+      }
+   }
+}
+
+#[test]
+fn automata_texture_is_read_write_r32float() {
+   let shader = load(Automata::GameOfLife.shader_path(), &ShaderDefines::new());
+   let read = &shader.layout_entries(0)[0];
+
+   assert_eq!(read.binding, 0);
+   assert_eq!(read.ty, AutomataPackage::bind_group_layout_entries()[0].ty);
+   assert_eq!(read.ty, BindingType::StorageTexture {
+      access: wgpu::StorageTextureAccess::ReadWrite,
+      format: wgpu::TextureFormat::R32Float,
+      view_dimension: wgpu::TextureViewDimension::D2,
+   });
+}
+
+#[test]
+fn queue_uniform_matches_queue_length() {
+   let shader = load(QueueComputePipeline::SHADER_PATH, &ShaderDefines::new());
+   let queue = &shader.layout_entries(2)[0];
+
+   let BindingType::Buffer { min_binding_size, .. } = queue.ty else { panic!("queue is not a buffer") };
+   assert_eq!(min_binding_size.unwrap().get(), 16 * QUEUE_LENGTH as u64);
+   assert_eq!(queue.visibility, wgpu::ShaderStages::COMPUTE);
+}
+
+#[test]
+fn post_effect_params_match_rust_structs() {
+   for (path, variants, rust_size) in post_effects() {
+      for defines in variants {
+         let shader = load(path, &defines);
+         let bindings = shader.bindings();
+
+         for name in ["input", "linear_sampler"] {
+            assert!(bindings.iter().any(|b| b.group == 0 && b.name == name), "{path} has no `{name}` in group 0");
+         }
+
+         let params = bindings.iter().find(|b| b.group == 0 && b.name == "params");
+         let Some(rust_size) = rust_size else {
+            assert!(params.is_none(), "{path} has a `params` uniform but no rust struct");
+            continue;
+         };
+
+         let params = params.unwrap_or_else(|| panic!("{path} has no `params` in group 0"));
+         let BindingType::Buffer { min_binding_size, .. } = params.entry.ty else { panic!("{path}: params is not a buffer") };
+         assert_eq!(min_binding_size.unwrap().get() as usize, rust_size, "{path}: params size differs from the rust struct");
+      }
+   }
+}
+
+#[test]
+fn frame_globals_match_rust_struct() {
+   let rust = GlobalsPackage::bind_group_layout_entries();
+
+   for (defines, group) in [(ShaderDefines::new(), 3), (ShaderDefines::new().with("GLOBALS_GROUP", 1), 1)] {
+      let shader = load("common/globals.wgsl", &defines);
+      let bindings = shader.bindings();
+      let globals = bindings.iter().find(|b| b.name == "globals").expect("no `globals` binding");
+
+      assert_eq!(globals.group, group);
+      let BindingType::Buffer { min_binding_size, .. } = globals.entry.ty else { panic!("globals is not a buffer") };
+      assert_eq!(min_binding_size.unwrap().get() as usize, std::mem::size_of::<FrameGlobals>());
+      assert!(compatible(&rust[0].ty, &globals.entry.ty));
+   }
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::{device, temp_path};
+use wgpu_template::packages::screenshot_package::CapturedImage;
+use wgpu_template::packages::texture_package::{decode_image, mip_chain, mip_level_count, DecodedImage, SamplerSettings, TextureError, TexturePackage};
+
+#[test]
+fn mip_counts_go_down_to_one_texel() {
+   assert_eq!(mip_level_count(1, 1), 1);
+   assert_eq!(mip_level_count(256, 256), 9);
+   assert_eq!(mip_level_count(300, 20), 9);
+}
+
+#[test]
+fn mip_chain_halves_and_averages_in_linear_space() {
+   // a black and white checker averages to linear 0.5, which is 188 in srgb rather than 128
+   let mut rgba = vec![];
+   for i in 0..16 {
+      let v = if (i + i / 4) % 2 == 0 { 255 } else { 0 };
+      rgba.extend([v, v, v, 255]);
+   }
+   let image = DecodedImage { width: 4, height: 4, rgba };
+
+   let levels = mip_chain(&image);
+   let sizes: Vec<(u32, u32)> = levels.iter().map(|level| (level.width, level.height)).collect();
+   assert_eq!(sizes, [(4, 4), (2, 2), (1, 1)]);
+   assert_eq!(levels[1].rgba[..4], [188, 188, 188, 255]);
+   assert_eq!(levels[2].rgba, [188, 188, 188, 255]);
+
+   let odd = mip_chain(&DecodedImage { width: 5, height: 1, rgba: vec![10; 20] });
+   let sizes: Vec<(u32, u32)> = odd.iter().map(|level| (level.width, level.height)).collect();
+   assert_eq!(sizes, [(5, 1), (2, 1), (1, 1)]);
+}
+
+#[test]
+fn png_decodes_through_the_signature() {
+   let image = CapturedImage { width: 2, height: 1, rgba: vec![255, 0, 0, 255, 0, 0, 255, 128] };
+   let path = temp_path("texture.png");
+   image.save_png(&path).unwrap();
+
+   let decoded = decode_image(&std::fs::read(&path).unwrap()).unwrap();
+   std::fs::remove_file(&path).unwrap();
+
+   assert_eq!((decoded.width, decoded.height), (2, 1));
+   assert_eq!(decoded.rgba, image.rgba);
+}
+
+#[test]
+fn unknown_data_is_rejected() {
+   assert!(decode_image(b"GIF89a").is_err());
+   assert!(decode_image(&[0xff, 0xd8, 0xff, 0x00]).is_err());
+}
+
+#[test]
+fn textures_load_from_disk_and_oversized_images_are_errors() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+   let layout = device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+      label: None,
+      entries: &TexturePackage::bind_group_layout_entries(),
+   });
+
+   let path = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/pixels.png");
+   let texture = TexturePackage::load(&device, &queue, &layout, path, SamplerSettings::linear()).unwrap();
+   assert_eq!((texture.width, texture.height), (4, 2));
+   assert_eq!(texture.texture.mip_level_count(), 3);
+
+   let missing = TexturePackage::load(&device, &queue, &layout, "does/not/exist.png", SamplerSettings::linear());
+   assert!(matches!(missing, Err(TextureError::Io(_))));
+
+   let max = device.limits().max_texture_dimension_2d;
+   let wide = DecodedImage { width: max + 1, height: 1, rgba: vec![0; (max as usize + 1) * 4] };
+   let too_large = TexturePackage::from_image(&device, &queue, &layout, "wide", &wide, SamplerSettings::linear());
+   assert!(matches!(too_large, Err(TextureError::TooLarge(width, 1, limit)) if width == max + 1 && limit == max));
+
+   device.poll(wgpu::Maintain::Wait);
+}
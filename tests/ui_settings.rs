@@ -0,0 +1,31 @@
+mod common;
+
+use common::temp_path;
+use std::path::PathBuf;
+use egui::Color32;
+use wgpu_template::egui::gui::{font_definitions, visuals, FontError, Theme};
+
+#[test]
+fn themes_keep_the_template_rounding_and_take_the_accent() {
+   let dark = visuals(Theme::Dark, None);
+   assert!(dark.dark_mode);
+   assert_eq!(dark.window_rounding, egui::Rounding::same(2.0));
+
+   let light = visuals(Theme::Light, Some([200, 40, 10]));
+   assert!(!light.dark_mode);
+   assert_eq!(light.selection.bg_fill, Color32::from_rgb(200, 40, 10));
+   assert_eq!(light.hyperlink_color, Color32::from_rgb(200, 40, 10));
+}
+
+#[test]
+fn bad_font_files_are_errors_instead_of_panics() {
+   assert!(matches!(font_definitions(&[PathBuf::from("does/not/exist.ttf")]), Err(FontError::Io(..))));
+
+   let path = temp_path("font.ttf");
+   std::fs::write(&path, b"definitely not a font").unwrap();
+   assert!(matches!(font_definitions(std::slice::from_ref(&path)), Err(FontError::Invalid(_))));
+   std::fs::remove_file(path).unwrap();
+
+   let fonts = font_definitions(&[]).unwrap();
+   assert_eq!(fonts.font_data.len(), egui::FontDefinitions::default().font_data.len());
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use common::device;
+use wgpu::{BindingType, ShaderStages};
+use wgpu_template::packages::uniform_package::{aligned_stride, UniformPackage};
+
+#[test]
+fn array_stride_rounds_up_to_offset_alignment() {
+   assert_eq!(aligned_stride(64, 256), 256);
+   assert_eq!(aligned_stride(256, 256), 256);
+   assert_eq!(aligned_stride(260, 256), 512);
+   assert_eq!(aligned_stride(12, 4), 12);
+}
+
+#[test]
+fn layout_entry_is_sized_from_the_struct() {
+   let entry = UniformPackage::<[f32; 8]>::layout_entry(ShaderStages::FRAGMENT, true);
+   assert_eq!(entry.visibility, ShaderStages::FRAGMENT);
+
+   let BindingType::Buffer { has_dynamic_offset, min_binding_size, .. } = entry.ty else { panic!("not a buffer") };
+   assert!(has_dynamic_offset);
+   assert_eq!(min_binding_size.unwrap().get(), 32);
+}
+
+#[test]
+fn only_changed_values_are_uploaded() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   // a new package has never been written
+   let mut single = UniformPackage::new(&device, "single", ShaderStages::VERTEX, [1.0f32; 4]);
+   assert!(single.is_dirty());
+   assert!(single.upload(&queue));
+   assert!(!single.is_dirty());
+   assert!(!single.upload(&queue));
+
+   single.set([1.0; 4]);
+   assert!(!single.is_dirty());
+   assert!(!single.upload(&queue));
+
+   single.set([2.0; 4]);
+   assert!(single.is_dirty());
+   assert!(single.upload(&queue));
+
+   let mut array = UniformPackage::new_array(&device, "array", ShaderStages::VERTEX, vec![[0.0f32; 4]; 3]);
+   array.upload(&queue);
+   array.set_at(2, [0.0; 4]);
+   assert!(!array.is_dirty());
+
+   array.set_at(2, [3.0; 4]);
+   assert!(array.is_dirty());
+   assert!(array.upload(&queue));
+   assert!(!array.is_dirty());
+
+   // a mutable borrow could change anything
+   array.get_mut_at(0);
+   assert!(array.is_dirty());
+
+   device.poll(wgpu::Maintain::Wait);
+}
@@ -0,0 +1,89 @@
+use wgpu_template::inbuilt::vertex_library::{circle, cylinder, icosphere, plane, torus, uv_sphere};
+use wgpu_template::inbuilt::vertex_package::LitVertex;
+
+fn sub(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+   [a[0] - b[0], a[1] - b[1], a[2] - b[2]]
+}
+
+fn cross(a: [f32; 3], b: [f32; 3]) -> [f32; 3] {
+   [a[1] * b[2] - a[2] * b[1], a[2] * b[0] - a[0] * b[2], a[0] * b[1] - a[1] * b[0]]
+}
+
+fn dot(a: [f32; 3], b: [f32; 3]) -> f32 {
+   a[0] * b[0] + a[1] * b[1] + a[2] * b[2]
+}
+
+/// indices in bounds, whole triangles, and every face counter clockwise around its normals
+fn check_mesh((vertices, indices): &(Vec<LitVertex>, Vec<u32>), expected_triangles: usize) {
+   assert_eq!(indices.len() % 3, 0);
+   assert_eq!(indices.len() / 3, expected_triangles);
+   assert!(indices.iter().all(|&i| (i as usize) < vertices.len()));
+
+   for tri in indices.chunks(3) {
+      let [a, b, c] = [tri[0], tri[1], tri[2]].map(|i| vertices[i as usize]);
+      let face_normal = cross(sub(b.position, a.position), sub(c.position, a.position));
+      let vertex_normal = [0, 1, 2].map(|k| a.normal[k] + b.normal[k] + c.normal[k]);
+
+      assert!(dot(face_normal, vertex_normal) > 0.0, "triangle {tri:?} is wound clockwise");
+   }
+}
+
+#[test]
+fn uv_sphere_counts_and_winding() {
+   let sectors = 16;
+   let stacks = 8;
+   let mesh = uv_sphere(2.0, sectors, stacks);
+
+   assert_eq!(mesh.0.len(), ((sectors + 1) * (stacks + 1)) as usize);
+   check_mesh(&mesh, (2 * sectors * (stacks - 1)) as usize);
+
+   for v in &mesh.0 {
+      assert!((dot(v.position, v.position).sqrt() - 2.0).abs() < 1e-4);
+   }
+}
+
+#[test]
+fn icosphere_counts_and_winding() {
+   for subdivisions in 0..4 {
+      let mesh = icosphere(1.0, subdivisions);
+      check_mesh(&mesh, 20 * 4usize.pow(subdivisions));
+   }
+
+   // shared edges are split once, so the vertex count follows 10 * 4^n + 2
+   assert_eq!(icosphere(1.0, 2).0.len(), 10 * 16 + 2);
+}
+
+#[test]
+fn plane_counts_and_winding() {
+   let mesh = plane(4.0, 2.0, 5, 3);
+   assert_eq!(mesh.0.len(), 6 * 4);
+   check_mesh(&mesh, 2 * 5 * 3);
+}
+
+#[test]
+fn circle_counts_and_winding() {
+   let mesh = circle(1.0, 32);
+   assert_eq!(mesh.0.len(), 33);
+   check_mesh(&mesh, 32);
+}
+
+#[test]
+fn cylinder_counts_and_winding() {
+   let segments = 24;
+   let mesh = cylinder(1.0, 3.0, segments);
+   check_mesh(&mesh, (4 * segments) as usize);
+}
+
+#[test]
+fn torus_counts_and_winding() {
+   let mesh = torus(2.0, 0.5, 24, 12);
+   assert_eq!(mesh.0.len(), 25 * 13);
+   check_mesh(&mesh, 2 * 24 * 12);
+}
+
+#[test]
+fn degenerate_parameters_are_clamped() {
+   check_mesh(&circle(1.0, 0), 3);
+   check_mesh(&plane(1.0, 1.0, 0, 0), 2);
+   check_mesh(&uv_sphere(1.0, 1, 1), 2 * 3);
+}
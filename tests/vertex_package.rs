@@ -0,0 +1,61 @@
+mod common;
+
+use common::device;
+use wgpu::IndexFormat;
+use wgpu_template::inbuilt::vertex_package::{DynamicVertexPackage, Vertex, VertexPackage};
+use wgpu_template::utility::functions::padded_len;
+
+fn vertices(count: usize) -> Vec<Vertex> {
+   (0..count).map(|i| Vertex { position: [i as f32, 0.0, 0.0] }).collect()
+}
+
+#[test]
+fn byte_lengths_are_padded_to_copy_alignment() {
+   assert_eq!(padded_len(0), 0);
+   assert_eq!(padded_len(6), 8);
+   assert_eq!(padded_len(8), 8);
+   assert_eq!(padded_len(9), 12);
+}
+
+#[test]
+fn the_index_type_picks_the_index_format() {
+   let Some((device, _queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   let short = VertexPackage::new(&device, &vertices(3), &[0u16, 1, 2]);
+   assert_eq!(short.index_format, IndexFormat::Uint16);
+   assert_eq!((short.num_vertices, short.num_indices), (3, 3));
+
+   let long = VertexPackage::new(&device, &vertices(3), &[0u32, 1, 2]);
+   assert_eq!(long.index_format, IndexFormat::Uint32);
+   assert_eq!(long.index_buffer.size(), 12);
+}
+
+#[test]
+fn updates_pad_odd_index_counts_and_grow_the_buffers() {
+   let Some((device, queue)) = device() else {
+      eprintln!("no adapter, skipping");
+      return;
+   };
+
+   // an empty package still gets buffers, wgpu can't create zero sized mapped ones
+   let mut package = DynamicVertexPackage::new::<u16>(&device, &[], &[]);
+   assert_eq!(package.vertex_buffer.size(), 4);
+   assert_eq!(package.index_buffer.size(), 4);
+
+   // three u16 indices are 6 bytes, padded to 8
+   package.update(&device, &queue, &vertices(3), &[0u16, 1, 2]);
+   assert_eq!(package.index_buffer.size(), 8);
+   assert_eq!(package.vertex_buffer.size(), 64);
+   assert_eq!((package.num_vertices, package.num_indices, package.index_format), (3, 3, IndexFormat::Uint16));
+
+   // shrinking keeps the bigger buffers, switching to u32 grows the index buffer again
+   package.update(&device, &queue, &vertices(1), &[0u32, 0, 0, 0, 0]);
+   assert_eq!(package.vertex_buffer.size(), 64);
+   assert_eq!(package.index_buffer.size(), 32);
+   assert_eq!((package.num_vertices, package.num_indices, package.index_format), (1, 5, IndexFormat::Uint32));
+
+   device.poll(wgpu::Maintain::Wait);
+}
@@ -0,0 +1,46 @@
+use cgmath::Vector2;
+use egui::{Pos2, Rect, Vec2};
+use wgpu_template::packages::input_manager_package::{InputManager, ScreenRegion};
+use wgpu_template::packages::viewport_package::{pixel_size, screen_region};
+
+#[test]
+fn panel_rects_map_to_window_pixels() {
+   let rect = Rect::from_min_size(Pos2::new(100.0, 50.0), Vec2::new(200.5, 100.0));
+   let region = screen_region(rect, 2.0);
+
+   assert_eq!(region.min, Vector2::new(200.0, 100.0));
+   assert_eq!(region.size, Vector2::new(401.0, 200.0));
+   assert_eq!(pixel_size(rect.size(), 2.0), (401, 200));
+
+   // a collapsed panel still gets a texture
+   assert_eq!(pixel_size(Vec2::new(0.0, 0.2), 1.0), (1, 1));
+}
+
+#[test]
+fn the_mouse_is_only_in_the_viewport_inside_its_region() {
+   let region = ScreenRegion { min: Vector2::new(200.0, 100.0), size: Vector2::new(400.0, 200.0) };
+   assert_eq!(region.to_local(Vector2::new(250.0, 150.0)), Vector2::new(50.0, 50.0));
+   assert!(region.contains(Vector2::new(200.0, 100.0)));
+   assert!(!region.contains(Vector2::new(600.0, 150.0)));
+   assert!(!region.contains(Vector2::new(199.0, 150.0)));
+
+   let mut input = InputManager::new();
+   input.mouse_screen_pos = Vector2::new(10.0, 10.0);
+   assert!(input.is_mouse_in_viewport());
+
+   input.viewport = Some(region);
+   assert!(!input.is_mouse_in_viewport());
+   input.mouse_screen_pos = Vector2::new(300.0, 150.0);
+   assert!(input.is_mouse_in_viewport());
+}
+
+#[test]
+fn the_viewport_mouse_is_relative_to_the_docked_panel() {
+   let window = Vector2::new(1280.0, 720.0);
+   let mut input = InputManager::new();
+   input.mouse_screen_pos = Vector2::new(300.0, 150.0);
+   assert_eq!(input.viewport_mouse(window), (Vector2::new(300.0, 150.0), window));
+
+   input.viewport = Some(ScreenRegion { min: Vector2::new(200.0, 100.0), size: Vector2::new(400.0, 200.0) });
+   assert_eq!(input.viewport_mouse(window), (Vector2::new(100.0, 50.0), Vector2::new(400.0, 200.0)));
+}